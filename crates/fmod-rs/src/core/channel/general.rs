@@ -1,7 +1,7 @@
 use {
     crate::utils::catch_user_unwind,
     fmod::{raw::*, *},
-    std::{ffi::c_void, ops::Deref},
+    std::{ffi::c_void, ops::Deref, sync::Arc},
 };
 
 impl Deref for Channel {
@@ -17,6 +17,46 @@ impl AsRef<ChannelControl> for Channel {
     }
 }
 
+#[cfg(feature = "debug-introspection")]
+impl Channel {
+    pub(crate) fn fmt_introspect(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        name: std::fmt::Arguments<'_>,
+    ) -> std::fmt::Result {
+        let snapshot = (|| -> Result<_> {
+            let sound = match self.get_current_sound()? {
+                Some(sound) => {
+                    let mut name = String::new();
+                    sound.get_name(&mut name)?;
+                    Some(name)
+                },
+                None => None,
+            };
+            let playing = self.is_playing()?;
+            let paused = self.get_paused()?;
+            Ok((
+                playing,
+                paused,
+                self.get_volume()?,
+                self.get_pitch()?,
+                sound,
+            ))
+        })();
+        match snapshot {
+            Ok((playing, paused, volume, pitch, sound)) => f
+                .debug_struct(&name.to_string())
+                .field("playing", &playing)
+                .field("paused", &paused)
+                .field("volume", &volume)
+                .field("pitch", &pitch)
+                .field("sound", &sound)
+                .finish(),
+            Err(error) => crate::utils::fmt_introspect_error(f, name, error),
+        }
+    }
+}
+
 // General.
 impl Channel {
     /// Sets the callback for Channel level notifications.
@@ -27,6 +67,74 @@ impl Channel {
         ))?;
         Ok(())
     }
+
+    /// Sets a custom rolloff shape for 3D distance attenuation.
+    ///
+    /// To enable a custom rolloff, [`Mode::CustomRolloff3d`] must be set on
+    /// this object (or the [`Sound`] it is playing). The curve's points are
+    /// `(distance, volume, 0)` [`Vector`]s; they must be sorted by distance
+    /// and neither distance nor volume may be negative, otherwise
+    /// [`Error::InvalidParam`] is returned. Between points the volume is
+    /// linearly interpolated, and beyond the last point the volume of the last
+    /// point is used. An empty curve removes the custom rolloff.
+    ///
+    /// When a custom rolloff is specified, the min and max distance settings
+    /// of [`ChannelControl::set_3d_min_max_distance`] are ignored.
+    ///
+    /// FMOD refers to the curve without copying it, so it is kept alive by
+    /// FMOD.rs until it is replaced, the channel ends (including by being
+    /// stolen), or the system is released. The curve of an ended channel is
+    /// freed by the end callback if one was set with [`Channel::set_callback`],
+    /// and otherwise the next time a custom rolloff is set on any channel.
+    pub fn set_3d_custom_rolloff(&self, curve: Vec<Vector>) -> Result {
+        self.set_3d_custom_rolloff_owned(true, curve)
+    }
+
+    /// Retrieves the current custom rolloff shape for 3D distance
+    /// attenuation, if any.
+    ///
+    /// This shares the curve given to [`Channel::set_3d_custom_rolloff`]
+    /// rather than copying it, so it stays valid after the channel ends.
+    /// Returns [`Error::Internal`] if the curve was set through the raw API.
+    pub fn get_3d_custom_rolloff(&self) -> Result<Option<Arc<[Vector]>>> {
+        self.get_3d_custom_rolloff_shared()
+    }
+
+    /// Calls a function with this channel, treating a channel that is no
+    /// longer playing as a benign no-op.
+    ///
+    /// [`Channel`] handles are invalidated when the channel stops or is
+    /// stolen, so it is expected for channel calls to fail with
+    /// [`Error::InvalidHandle`] or [`Error::ChannelStolen`]. Those errors
+    /// become `Ok(None)`, while other errors are still propagated.
+    ///
+    /// ```rust,ignore
+    /// if channel.try_call(|c| c.set_volume(0.5))?.is_none() {
+    ///     // the channel has already finished playing
+    /// }
+    /// ```
+    pub fn try_call<T>(&self, f: impl FnOnce(&Channel) -> Result<T>) -> Result<Option<T>> {
+        match f(self) {
+            Ok(value) => Ok(Some(value)),
+            Err(err) if err.is_channel_gone() => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Checks that this channel handle still refers to a playing channel.
+    ///
+    /// Returns [`Error::InvalidHandle`] if the channel has stopped, or
+    /// [`Error::ChannelStolen`] if it was stolen to play another sound. A
+    /// [`Channel`] reference held across frames should be validated (or used
+    /// with [`Channel::try_call`]) before being used, since the channel may
+    /// have stopped since it was last checked; see the white paper on
+    /// [Channel handles] for more information.
+    ///
+    /// [Channel handles]: https://fmod.com/resources/documentation-api?version=2.02&page=white-papers-handle-system.html#core-api-channels
+    pub fn validate(&self) -> Result {
+        self.is_playing()?;
+        Ok(())
+    }
 }
 
 /// Callback for Channel notifications.
@@ -76,7 +184,14 @@ pub(crate) unsafe extern "system" fn channel_callback<C: ChannelCallback>(
         let channel = Channel::from_raw(channelcontrol as *mut FMOD_CHANNEL);
 
         match callback_type {
-            ChannelControlCallbackType::End => Ok(C::end(channel)),
+            ChannelControlCallbackType::End => {
+                let result = catch_user_unwind(|| Ok(C::end(channel)));
+                // FMOD no longer refers to the ended channel's rolloff curve
+                if let Ok(system) = channel.get_system_object() {
+                    ChannelControl::forget_3d_custom_rolloff(system, channelcontrol);
+                }
+                result
+            },
             ChannelControlCallbackType::VirtualVoice => {
                 let is_virtual = commanddata1 as i32 != 0;
                 Ok(C::virtual_voice(channel, is_virtual))
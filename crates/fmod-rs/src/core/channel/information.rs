@@ -7,6 +7,13 @@ use {
 impl Channel {
     /// Retrieves whether the Channel is being emulated by the virtual voice system.
     ///
+    /// A Channel becomes virtual when more Channels are playing than there are
+    /// real voices available (see [System::set_software_channels]), or when its
+    /// [audibility](ChannelControl::get_audibility) falls below the
+    /// [virtual voice threshold](AdvancedSettings::vol_0_virtual_vol) with
+    /// [InitFlags::Vol0BecomesVirtual]. The split between real and virtual
+    /// Channels can be retrieved with [System::get_channels_playing].
+    ///
     /// See the [Virtual Voices] guide for more information.
     ///
     /// [Virtual Voices]: https://fmod.com/docs/2.02/api/white-papers-virtual-voices.html
@@ -24,6 +31,8 @@ impl Channel {
     }
 
     /// Retrieves the index of this object in the System Channel pool.
+    ///
+    /// This is the ID accepted by [System::get_channel].
     pub fn get_index(&self) -> Result<i32> {
         let mut index = 0;
         ffi!(FMOD_Channel_GetIndex(self.as_raw(), &mut index))?;
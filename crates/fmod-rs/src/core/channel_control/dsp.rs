@@ -33,6 +33,7 @@ impl ChannelControl {
     /// [DSP Architecture and Usage]: https://fmod.com/docs/2.02/api/white-papers-dsp-architecture.html
     pub fn add_dsp(&self, index: i32, dsp: &Dsp) -> Result {
         // FIXME: dropping the DSP without removing it from the mixer network is an error
+        debug_assert_same_system!(self, dsp);
         ffi!(FMOD_Channel_AddDSP(self.as_raw() as _, index, dsp.as_raw()))?;
         Ok(())
     }
@@ -26,6 +26,18 @@ impl ChannelControl {
     /// that [`ChannelGroup`] will still have their existing sends to the
     /// reverb. To avoid this doubling up you should explicitly set the
     /// [`Channel`] wet levels to 0.
+    ///
+    /// Setting `wet` to 0 turns off the reverb send entirely, which is the
+    /// recommended way to exclude 2D sounds from 3D reverb.
+    ///
+    /// `instance` is in the range [0, [`REVERB_MAX_INSTANCES`]), and
+    /// `wet` is a linear level:
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Linear</dd>
+    /// <dt>Range</dt><dd>[0, 1]</dd>
+    /// <dt>Default</dt><dd>1</dd>
+    /// </dl>
     pub fn set_reverb_properties(&self, instance: i32, wet: f32) -> Result {
         ffi!(FMOD_Channel_SetReverbProperties(
             self.as_raw() as _,
@@ -56,6 +68,12 @@ impl ChannelControl {
     /// <span class="emoji">⚠️</span><span>
     /// Currently only supported for Channel, not ChannelGroup.
     /// </span></div></div>
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Linear</dd>
+    /// <dt>Range</dt><dd>[0, 1]</dd>
+    /// <dt>Default</dt><dd>1</dd>
+    /// </dl>
     pub fn set_low_pass_gain(&self, gain: f32) -> Result {
         ffi!(FMOD_Channel_SetLowPassGain(self.as_raw() as _, gain))?;
         Ok(())
@@ -29,6 +29,13 @@ impl ChannelControl {
     ///
     /// If the System is initialized with [`SpeakerMode::Raw`] calling this
     /// function will produce silence.
+    ///
+    /// `pan` ranges from -1 (fully left) to 1 (fully right).
+    ///
+    /// <dl>
+    /// <dt>Range</dt><dd>[-1, 1]</dd>
+    /// <dt>Default</dt><dd>0</dd>
+    /// </dl>
     pub fn set_pan(&self, pan: f32) -> Result {
         ffi!(FMOD_Channel_SetPan(self.as_raw() as _, pan))?;
         Ok(())
@@ -154,4 +154,22 @@ impl ChannelControl {
         ffi!(FMOD_Channel_GetPitch(self.as_raw() as _, &mut pitch))?;
         Ok(pitch)
     }
+
+    /// Sets the relative pitch / playback rate in semitones.
+    ///
+    /// This is a convenience for [`ChannelControl::set_pitch`] which converts
+    /// a musical semitone offset into a pitch multiplier (`2^(n/12)`).
+    /// A value of 0 represents unmodified pitch, -12 represents one octave
+    /// down, and 12 represents one octave up.
+    pub fn set_semitones(&self, semitones: f32) -> Result {
+        self.set_pitch((semitones / 12.0).exp2())
+    }
+
+    /// Retrieves the relative pitch / playback rate in semitones.
+    ///
+    /// This is a convenience for [`ChannelControl::get_pitch`] which converts
+    /// the pitch multiplier into a musical semitone offset (`12 log2(pitch)`).
+    pub fn get_semitones(&self) -> Result<f32> {
+        Ok(self.get_pitch()?.log2() * 12.0)
+    }
 }
@@ -1,7 +1,13 @@
 use {
     fmod::{raw::*, *},
+    parking_lot::Mutex,
     smart_default::SmartDefault,
-    std::ops::{Bound, Range, RangeBounds},
+    std::{
+        collections::HashMap,
+        ops::{Bound, Range, RangeBounds},
+        ptr,
+        sync::Arc,
+    },
 };
 
 // We make the potentially dangerous assumption that for the FMOD_CHANNELCONTROL
@@ -135,9 +141,94 @@ impl ChannelControl {
         Ok(cone)
     }
 
-    // TODO: needs figuring out lifetimes
-    // set_3d_custom_rolloff
-    // get_3d_custom_rolloff
+    // set_3d_custom_rolloff, get_3d_custom_rolloff: FMOD keeps the curve
+    // without copying it, so the curve storage has to know whether this is a
+    // Channel or ChannelGroup to know when it can be freed. See
+    // Channel::set_3d_custom_rolloff and ChannelGroup::set_3d_custom_rolloff.
+
+    pub(crate) fn set_3d_custom_rolloff_owned(
+        &self,
+        is_channel: bool,
+        curve: Vec<Vector>,
+    ) -> Result {
+        let mut last = 0.0;
+        for point in &curve {
+            if !(point.x >= last && point.y >= 0.0) {
+                whoops!(no_panic: "custom rolloff curve must be sorted by distance and non-negative, got {point:?}");
+                yeet!(Error::InvalidParam);
+            }
+            last = point.x;
+        }
+
+        let system = self.get_system_object()?;
+        let Some(state) = system.state() else {
+            whoops!(no_panic: "{system:?} has no FMOD.rs state");
+            yeet!(Error::Internal);
+        };
+        let rolloffs = &state.custom_rolloffs;
+        let control = self.as_raw() as usize;
+
+        // FMOD calls the end callback while holding its own lock, so only
+        // `setting` is held across FMOD calls, never `entries`
+        let _setting = rolloffs.setting.lock();
+        rolloffs.free_ended_channels();
+
+        let curve: Arc<[Vector]> = curve.into();
+        let points = match curve.is_empty() {
+            true => ptr::null_mut(),
+            // FMOD only reads the curve
+            false => curve.as_ptr().cast_mut().cast(),
+        };
+        ffi!(FMOD_Channel_Set3DCustomRolloff(
+            self.as_raw() as _,
+            points,
+            curve.len() as i32,
+        ))?;
+        let mut entries = rolloffs.entries.lock();
+        match curve.is_empty() {
+            true => entries.remove(&control),
+            false => entries.insert(control, CustomRolloff { is_channel, curve }),
+        };
+        Ok(())
+    }
+
+    /// Frees the custom rolloff curve of an ended [`Channel`] or released
+    /// [`ChannelGroup`] of `system`.
+    pub(crate) fn forget_3d_custom_rolloff(system: &System, control: *mut FMOD_CHANNELCONTROL) {
+        if let Some(state) = system.state() {
+            let mut entries = state.custom_rolloffs.entries.lock();
+            entries.remove(&(control as usize));
+        }
+    }
+
+    pub(crate) fn get_3d_custom_rolloff_shared(&self) -> Result<Option<Arc<[Vector]>>> {
+        let mut points = ptr::null_mut();
+        let mut num_points = 0;
+        ffi!(FMOD_Channel_Get3DCustomRolloff(
+            self.as_raw() as _,
+            &mut points,
+            &mut num_points,
+        ))?;
+        if points.is_null() || num_points == 0 {
+            return Ok(None);
+        }
+
+        let system = self.get_system_object()?;
+        let Some(state) = system.state() else {
+            whoops!(no_panic: "{system:?} has no FMOD.rs state");
+            yeet!(Error::Internal);
+        };
+        let entries = state.custom_rolloffs.entries.lock();
+        match entries.get(&(self.as_raw() as usize)) {
+            Some(entry) if entry.curve.as_ptr() == points.cast_const().cast() => {
+                Ok(Some(entry.curve.clone()))
+            },
+            _ => {
+                whoops!(no_panic: "custom rolloff of {self:?} wasn't set by FMOD.rs");
+                Err(Error::Internal)
+            },
+        }
+    }
 
     /// Sets an override value for the 3D distance filter.
     ///
@@ -321,7 +412,7 @@ impl ChannelControl {
     ///
     /// There is a reverb path/send when
     /// [`ChannelControl::set_reverb_properties`] has been used,
-    /// [`occlusion.reverb`] controls its attenuation.
+    /// `occlusion.reverb` controls its attenuation.
     ///
     /// If the [`System`] has been initialized with
     /// [`InitFlags::ChannelDistanceFilter`] or [`InitFlags::ChannelLowpass`]
@@ -348,10 +439,33 @@ impl ChannelControl {
     }
 
     /// Sets the spread of a 3D sound in speaker space.
+    ///
+    /// When the spread angle is 0 (default) a multi-channel signal will
+    /// collapse to mono and be spatialized to a single point based on
+    /// [`ChannelControl::set_3d_attributes`] calculations. As the angle is
+    /// increased, each channel within a multi-channel signal will be rotated
+    /// away from that point. For 2, 4, 6, 8, and 12 channel signals, the
+    /// spread is arranged from leftmost speaker to rightmost speaker
+    /// intelligently, for example in 5.1 the leftmost speaker is rear left,
+    /// followed by front left, center, front right then finally rear right as
+    /// the rightmost speaker (LFE is not spread).
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Degrees</dd>
+    /// <dt>Range</dt><dd>[0, 360]</dd>
+    /// <dt>Default</dt><dd>0</dd>
+    /// </dl>
     pub fn set_3d_spread(&self, angle: f32) -> Result {
         ffi!(FMOD_Channel_Set3DSpread(self.as_raw() as _, angle))?;
         Ok(())
     }
+
+    /// Retrieves the spread of a 3D sound in speaker space.
+    pub fn get_3d_spread(&self) -> Result<f32> {
+        let mut angle = 0.0;
+        ffi!(FMOD_Channel_Get3DSpread(self.as_raw() as _, &mut angle))?;
+        Ok(angle)
+    }
 }
 
 /// Angles and attenuation levels of a 3D cone shape,
@@ -402,3 +516,41 @@ pub struct DistanceFilter3d {
     #[default(0.0)]
     pub center_freq: f32,
 }
+
+/// Custom rolloff curves which FMOD is referring to, kept in the
+/// [`SystemState`] of their system so that they are freed with it.
+#[derive(Default)]
+pub(crate) struct CustomRolloffs {
+    /// Serializes setting curves, so that the curve recorded for an object is
+    /// always the one FMOD was last given.
+    setting: Mutex<()>,
+    /// Keyed by the address of the [`ChannelControl`]. [`Channel`] addresses
+    /// are handles which include a generation, so a recycled channel never
+    /// matches a stale entry.
+    entries: Mutex<HashMap<usize, CustomRolloff>>,
+}
+
+struct CustomRolloff {
+    is_channel: bool,
+    /// Never empty, and shared with [`ChannelControl::get_3d_custom_rolloff`].
+    curve: Arc<[Vector]>,
+}
+
+impl CustomRolloffs {
+    /// Frees the curves of channels which have ended, as FMOD no longer
+    /// refers to them. An FMOD.rs end callback does this as soon as the
+    /// channel ends, but FMOD.rs doesn't install one itself, as that would
+    /// replace any callback set through the raw API.
+    fn free_ended_channels(&self) {
+        let channels: Vec<usize> = (self.entries.lock().iter())
+            .filter(|(_, entry)| entry.is_channel)
+            .map(|(&control, _)| control)
+            .collect();
+        for control in channels {
+            let channel = unsafe { Channel::from_raw(control as *mut FMOD_CHANNEL) };
+            if matches!(channel.validate(), Err(error) if error.is_channel_gone()) {
+                self.entries.lock().remove(&control);
+            }
+        }
+    }
+}
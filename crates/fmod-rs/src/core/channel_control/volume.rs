@@ -20,7 +20,9 @@ impl ChannelControl {
     /// file (if available).
     ///
     /// This value is used to determine which [`Channel`]s should be audible
-    /// and which [`Channel`]s to virtualize when resources are limited.
+    /// and which [`Channel`]s to virtualize when resources are limited. With
+    /// [InitFlags::Vol0BecomesVirtual], [`Channel`]s with an audibility below
+    /// [`AdvancedSettings::vol_0_virtual_vol`] become virtual.
     ///
     /// See the [Virtual Voice System][audibility-calculation] white paper for
     /// more details about how audibility is calculated.
@@ -46,10 +48,6 @@ impl ChannelControl {
     }
 
     /// Retrieves the volume level.
-    ///
-    /// Volume changes when not paused will be ramped to the target value to
-    /// avoid a pop sound, this function allows that setting to be overridden
-    /// and volume changes to be applied immediately.
     pub fn get_volume(&self) -> Result<f32> {
         let mut volume = 0.0;
         ffi!(FMOD_Channel_GetVolume(self.as_raw() as _, &mut volume))?;
@@ -57,6 +55,10 @@ impl ChannelControl {
     }
 
     /// Sets whether volume changes are ramped or instantaneous.
+    ///
+    /// Volume changes when not paused will be ramped to the target value to
+    /// avoid a pop sound, this function allows that setting to be overridden
+    /// and volume changes to be applied immediately.
     pub fn set_volume_ramp(&self, ramp: bool) -> Result {
         let ramp = ramp as i32;
         ffi!(FMOD_Channel_SetVolumeRamp(self.as_raw() as _, ramp))?;
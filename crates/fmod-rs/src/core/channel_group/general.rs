@@ -1,7 +1,7 @@
 use {
     crate::utils::{catch_user_unwind, fmod_get_string},
     fmod::{raw::*, *},
-    std::{ffi::c_void, ops::Deref},
+    std::{ffi::c_void, ops::Deref, ptr, sync::Arc},
 };
 
 impl Deref for ChannelGroup {
@@ -17,6 +17,30 @@ impl AsRef<ChannelControl> for ChannelGroup {
     }
 }
 
+#[cfg(feature = "debug-introspection")]
+impl ChannelGroup {
+    pub(crate) fn fmt_introspect(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        name: std::fmt::Arguments<'_>,
+    ) -> std::fmt::Result {
+        let snapshot = (|| -> Result<_> {
+            let mut group_name = String::new();
+            self.get_name(&mut group_name)?;
+            Ok((group_name, self.get_num_channels()?, self.get_num_groups()?))
+        })();
+        match snapshot {
+            Ok((group_name, num_channels, num_groups)) => f
+                .debug_struct(&name.to_string())
+                .field("name", &group_name)
+                .field("num_channels", &num_channels)
+                .field("num_groups", &num_groups)
+                .finish(),
+            Err(error) => crate::utils::fmt_introspect_error(f, name, error),
+        }
+    }
+}
+
 /// # General.
 impl ChannelGroup {
     /// Retrieves the name set when the group was created.
@@ -38,7 +62,17 @@ impl ChannelGroup {
         /// Any [`Channel`]s or [`ChannelGroup`]s feeding into this group are moved
         /// to the master [`ChannelGroup`].
         pub unsafe fn raw_release(this: *mut FMOD_CHANNELGROUP) -> FMOD_RESULT {
-            FMOD_ChannelGroup_Release(this)
+            let mut system = ptr::null_mut();
+            let _ = FMOD_ChannelGroup_GetSystemObject(this, &mut system);
+            let result = FMOD_ChannelGroup_Release(this);
+            if result == FMOD_OK && !system.is_null() {
+                let system = System::from_raw(system);
+                ChannelControl::forget_3d_custom_rolloff(system, this as _);
+                if let Some(state) = system.state() {
+                    state.port_attachments.detach(this);
+                }
+            }
+            result
         }
     }
 
@@ -50,6 +84,23 @@ impl ChannelGroup {
         ))?;
         Ok(())
     }
+
+    /// Sets a custom rolloff shape for 3D distance attenuation.
+    ///
+    /// See [`Channel::set_3d_custom_rolloff`] for the shape of the curve. The
+    /// curve is kept alive by FMOD.rs until it is replaced, the group is
+    /// released, or the system is released.
+    pub fn set_3d_custom_rolloff(&self, curve: Vec<Vector>) -> Result {
+        self.set_3d_custom_rolloff_owned(false, curve)
+    }
+
+    /// Retrieves the current custom rolloff shape for 3D distance
+    /// attenuation, if any.
+    ///
+    /// See [`Channel::get_3d_custom_rolloff`].
+    pub fn get_3d_custom_rolloff(&self) -> Result<Option<Arc<[Vector]>>> {
+        self.get_3d_custom_rolloff_shared()
+    }
 }
 
 /// Callback for ChannelGroup notifications.
@@ -73,7 +124,7 @@ pub(crate) unsafe extern "system" fn channel_group_callback<C: ChannelGroupCallb
     commanddata2: *mut c_void,
 ) -> FMOD_RESULT {
     catch_user_unwind(|| {
-        let control_type = ChannelControlType::from_raw(controltype);
+        let control_type = ChannelControlType::try_from_raw(controltype)?;
         if control_type != ChannelControlType::ChannelGroup {
             whoops!(no_panic: "channel group callback called with channel");
             yeet!(Error::InvalidParam);
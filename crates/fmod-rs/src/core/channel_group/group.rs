@@ -12,6 +12,7 @@ impl ChannelGroup {
     /// The returned DSP connection will remain valid until the groups are
     /// disconnected.
     pub fn add_group(&self, group: &ChannelGroup) -> Result<Option<NonNull<DspConnection>>> {
+        debug_assert_same_system!(self, group);
         let mut connection = ptr::null_mut();
         ffi!(FMOD_ChannelGroup_AddGroup(
             self.as_raw(),
@@ -34,6 +35,7 @@ impl ChannelGroup {
         &self,
         group: &ChannelGroup,
     ) -> Result<Option<NonNull<DspConnection>>> {
+        debug_assert_same_system!(self, group);
         let mut connection = ptr::null_mut();
         ffi!(FMOD_ChannelGroup_AddGroup(
             self.as_raw(),
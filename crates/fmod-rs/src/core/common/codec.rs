@@ -0,0 +1,381 @@
+//! Functionality relating to authoring codec plugins.
+//!
+//! A codec plugin lets [`System::create_sound`] and
+//! [`System::create_stream`] decode a custom file format. Implement
+//! [`CodecDescription`] and register it with [`System::register_codec`].
+
+use {
+    crate::utils::catch_user_unwind,
+    fmod::{raw::*, *},
+    smart_default::SmartDefault,
+    std::{
+        ffi::c_void,
+        io::{self, Read, Seek, SeekFrom},
+        marker::PhantomData,
+        ptr, slice,
+    },
+};
+
+/// Format information for the PCM data produced by a codec.
+#[derive(Debug, Clone, Copy, SmartDefault, PartialEq)]
+pub struct CodecWaveFormat {
+    /// Format for (decompressed) codec output.
+    #[default(SoundFormat::Pcm16)]
+    pub format: SoundFormat,
+    /// Number of channels used by codec.
+    #[default(2)]
+    pub channels: i32,
+    /// Default frequency of the codec.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Hertz</dd>
+    /// </dl>
+    #[default(44100)]
+    pub frequency: i32,
+    /// Length of the source data.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Bytes</dd>
+    /// </dl>
+    pub length_bytes: u32,
+    /// Length of the decompressed PCM data.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Samples</dd>
+    /// </dl>
+    pub length_pcm: u32,
+    /// Minimum, optimal number of decompressed PCM samples codec can handle.
+    /// 0 or 1 = no buffering, anything higher means FMOD will allocate a PCM
+    /// buffer of this size to read in chunks.
+    pub pcm_block_size: u32,
+    /// Loop start position.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Samples</dd>
+    /// </dl>
+    pub loop_start: i32,
+    /// Loop end position.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Samples</dd>
+    /// </dl>
+    pub loop_end: i32,
+    /// Mode to determine whether the sound should by default load as looping,
+    /// non looping, 2D or 3D.
+    pub mode: Mode,
+    /// Defined channel bitmask to describe which speakers the channels in the
+    /// codec map to, in order of channel count.
+    #[default(ChannelMask::Stereo)]
+    pub channel_mask: ChannelMask,
+    /// Defined channel order type, to describe where each sound channel
+    /// should pan for the number of channels specified.
+    pub channel_order: ChannelOrder,
+    /// Peak volume of sound.
+    pub peak_volume: f32,
+}
+
+impl CodecWaveFormat {
+    fn into_raw(self) -> FMOD_CODEC_WAVEFORMAT {
+        FMOD_CODEC_WAVEFORMAT {
+            name: ptr::null(),
+            format: self.format.into_raw(),
+            channels: self.channels,
+            frequency: self.frequency,
+            lengthbytes: self.length_bytes,
+            lengthpcm: self.length_pcm,
+            pcmblocksize: self.pcm_block_size,
+            loopstart: self.loop_start,
+            loopend: self.loop_end,
+            mode: self.mode.into_raw(),
+            channelmask: self.channel_mask.into_raw(),
+            channelorder: self.channel_order.into_raw(),
+            peakvolume: self.peak_volume,
+        }
+    }
+
+    /// Size of one sample frame (one sample for every channel) in bytes, or
+    /// `None` if the format is not PCM.
+    fn frame_size(&self) -> Option<usize> {
+        let sample_size = match self.format {
+            SoundFormat::Pcm8 => 1,
+            SoundFormat::Pcm16 => 2,
+            SoundFormat::Pcm24 => 3,
+            SoundFormat::Pcm32 | SoundFormat::PcmFloat => 4,
+            _ => return None,
+        };
+        Some(sample_size * usize::try_from(self.channels).ok()?)
+    }
+}
+
+/// Access to the file being decoded, provided to [`CodecDescription`]
+/// callbacks.
+///
+/// This goes through FMOD's file system, including any callbacks set with
+/// [`System::set_file_system`].
+#[derive(Debug)]
+pub struct CodecFile<'a> {
+    raw: *mut FMOD_CODEC_STATE,
+    _phantom: PhantomData<&'a mut FMOD_CODEC_STATE>,
+}
+
+unsafe impl Send for CodecFile<'_> {}
+unsafe impl Sync for CodecFile<'_> {}
+
+impl CodecFile<'_> {
+    raw! {
+        pub fn as_raw(&self) -> *mut FMOD_CODEC_STATE {
+            self.raw
+        }
+    }
+
+    unsafe fn functions(&self) -> &FMOD_CODEC_STATE_FUNCTIONS {
+        &*(*self.raw).functions
+    }
+
+    /// Read bytes from the file into `buffer`, returning the number of bytes
+    /// read. Returns `Ok(0)` at the end of the file.
+    pub fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        let size = u32::try_from(buffer.len()).unwrap_or(u32::MAX);
+        let mut read = 0;
+        unsafe {
+            let read_fn = self.functions().read.ok_or(Error::Unsupported)?;
+            match Error::from_raw(read_fn(
+                self.raw,
+                buffer.as_mut_ptr().cast(),
+                size,
+                &mut read,
+            )) {
+                Ok(()) | Err(Error::FileEof) => Ok(ix!(read)),
+                Err(error) => Err(error),
+            }
+        }
+    }
+
+    /// Seek to an absolute byte position within the file.
+    pub fn seek(&mut self, pos: u32) -> Result {
+        unsafe {
+            let seek = self.functions().seek.ok_or(Error::Unsupported)?;
+            Error::from_raw(seek(self.raw, pos, FMOD_CODEC_SEEK_METHOD_SET as _))
+        }
+    }
+
+    /// Retrieve the current byte position within the file.
+    pub fn tell(&self) -> Result<u32> {
+        let mut pos = 0;
+        unsafe {
+            let tell = self.functions().tell.ok_or(Error::Unsupported)?;
+            Error::from_raw(tell(self.raw, &mut pos))?;
+        }
+        Ok(pos)
+    }
+
+    /// Retrieve the size of the file in bytes.
+    pub fn size(&self) -> Result<u32> {
+        let mut size = 0;
+        unsafe {
+            let size_fn = self.functions().size.ok_or(Error::Unsupported)?;
+            Error::from_raw(size_fn(self.raw, &mut size))?;
+        }
+        Ok(size)
+    }
+}
+
+impl Read for CodecFile<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(CodecFile::read(self, buf)?)
+    }
+}
+
+impl Seek for CodecFile<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::Current(offset) => (self.tell()? as u64).checked_add_signed(offset),
+            SeekFrom::End(offset) => (self.size()? as u64).checked_add_signed(offset),
+        };
+        let pos = pos
+            .and_then(|pos| u32::try_from(pos).ok())
+            .ok_or(io::ErrorKind::InvalidInput)?;
+        CodecFile::seek(self, pos)?;
+        Ok(pos as u64)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.tell()? as u64)
+    }
+}
+
+/// Callbacks to implement a custom codec plugin.
+///
+/// When opening a file each registered codec tests whether it can support
+/// the file format in priority order. [`CodecDescription::open`] should
+/// return [`Error::Format`] if the file is not in the codec's format, which
+/// lets the next codec try.
+///
+/// Each opened sound gets its own codec value, and FMOD does not invoke
+/// callbacks for the same sound concurrently.
+pub trait CodecDescription: Sized + Send {
+    /// Version number of the codec.
+    const VERSION: u32 = 1;
+
+    /// Whether sounds using this codec should be streamed by default.
+    const DEFAULT_AS_STREAM: bool = false;
+
+    /// Open callback for the codec for when FMOD tries to open a sound using
+    /// this codec.
+    ///
+    /// Read the file header and return the codec state along with the format
+    /// of the PCM data that [`CodecDescription::read`] will produce.
+    fn open(file: &mut CodecFile<'_>, mode: Mode) -> Result<(Self, CodecWaveFormat)>;
+
+    /// Close callback for the codec for when FMOD tries to close a sound
+    /// using this codec. Default implemented to just drop the codec.
+    fn close(self, file: &mut CodecFile<'_>) -> Result {
+        let _ = file;
+        Ok(())
+    }
+
+    /// Read callback for the codec for when FMOD tries to read some data from
+    /// the file to the destination format.
+    ///
+    /// `buffer` holds whole sample frames in the [`CodecWaveFormat::format`]
+    /// and channel count reported by [`CodecDescription::open`]. Return the
+    /// number of sample frames written. The buffer is zeroed before this is
+    /// called, so frames which aren't written are silent.
+    fn read(&mut self, file: &mut CodecFile<'_>, buffer: &mut [u8]) -> Result<usize>;
+
+    /// Set position callback for the codec for when FMOD tries to seek within
+    /// a file.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Samples</dd>
+    /// </dl>
+    fn set_position(&mut self, file: &mut CodecFile<'_>, subsound: i32, position: u32) -> Result;
+}
+
+struct CodecPlugin<C> {
+    codec: C,
+    frame_size: usize,
+    waveformat: FMOD_CODEC_WAVEFORMAT,
+}
+
+pub(crate) fn codec_description<C: CodecDescription>(
+    name: &'static CStr8,
+) -> FMOD_CODEC_DESCRIPTION {
+    FMOD_CODEC_DESCRIPTION {
+        apiversion: FMOD_CODEC_PLUGIN_VERSION,
+        name: name.as_ptr() as _,
+        version: C::VERSION,
+        defaultasstream: C::DEFAULT_AS_STREAM as i32,
+        timeunits: TimeUnit::Pcm.into_raw(),
+        open: Some(codec_open::<C>),
+        close: Some(codec_close::<C>),
+        read: Some(codec_read::<C>),
+        getlength: None,
+        setposition: Some(codec_set_position::<C>),
+        getposition: None,
+        soundcreate: None,
+        getwaveformat: None,
+    }
+}
+
+unsafe fn codec_plugin<'a, C>(
+    state: *mut FMOD_CODEC_STATE,
+) -> Result<(&'a mut CodecPlugin<C>, CodecFile<'a>)> {
+    let plugin = (*state).plugindata.cast::<CodecPlugin<C>>();
+    if plugin.is_null() {
+        return Err(Error::Uninitialized);
+    }
+    let file = CodecFile {
+        raw: state,
+        _phantom: PhantomData,
+    };
+    Ok((&mut *plugin, file))
+}
+
+unsafe extern "system" fn codec_open<C: CodecDescription>(
+    state: *mut FMOD_CODEC_STATE,
+    usermode: FMOD_MODE,
+    _userexinfo: *mut FMOD_CREATESOUNDEXINFO,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        let mut file = CodecFile {
+            raw: state,
+            _phantom: PhantomData,
+        };
+        let (codec, format) = C::open(&mut file, Mode::from_raw(usermode))?;
+        let Some(frame_size) = format.frame_size() else {
+            whoops!(no_panic: "codec must produce PCM data, but reported {:?}", format.format);
+            // the codec was opened, so it is closed even though open fails
+            if let Err(error) = catch_user_unwind(|| codec.close(&mut file)) {
+                whoops!(no_panic: "error closing codec: {error}");
+            }
+            return Err(Error::Format);
+        };
+
+        let plugin = Box::into_raw(Box::new(CodecPlugin {
+            codec,
+            frame_size,
+            waveformat: format.into_raw(),
+        }));
+        (*state).plugindata = plugin.cast();
+        (*state).waveformat = &mut (*plugin).waveformat;
+        (*state).numsubsounds = 0;
+        Ok(())
+    })
+    .into_raw()
+}
+
+unsafe extern "system" fn codec_close<C: CodecDescription>(
+    state: *mut FMOD_CODEC_STATE,
+) -> FMOD_RESULT {
+    let plugin = (*state).plugindata.cast::<CodecPlugin<C>>();
+    if plugin.is_null() {
+        return FMOD_OK;
+    }
+    (*state).plugindata = ptr::null_mut();
+    (*state).waveformat = ptr::null_mut();
+    let plugin = Box::from_raw(plugin);
+    let mut file = CodecFile {
+        raw: state,
+        _phantom: PhantomData,
+    };
+    catch_user_unwind(|| plugin.codec.close(&mut file)).into_raw()
+}
+
+unsafe extern "system" fn codec_read<C: CodecDescription>(
+    state: *mut FMOD_CODEC_STATE,
+    buffer: *mut c_void,
+    samples_in: u32,
+    samples_out: *mut u32,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        *samples_out = 0;
+        let (plugin, mut file) = codec_plugin::<C>(state)?;
+        let len = ix!(samples_in) * plugin.frame_size;
+        // FMOD's output buffer may be uninitialized, so zero it before
+        // handing it out as `&mut [u8]`
+        ptr::write_bytes(buffer.cast::<u8>(), 0, len);
+        let buffer = slice::from_raw_parts_mut(buffer.cast(), len);
+        let read = plugin.codec.read(&mut file, buffer)?;
+        *samples_out = Ord::min(read, ix!(samples_in)) as u32;
+        Ok(())
+    })
+    .into_raw()
+}
+
+unsafe extern "system" fn codec_set_position<C: CodecDescription>(
+    state: *mut FMOD_CODEC_STATE,
+    subsound: i32,
+    position: u32,
+    postype: FMOD_TIMEUNIT,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        if TimeUnit::from_raw(postype) != TimeUnit::Pcm {
+            return Err(Error::Format);
+        }
+        let (plugin, mut file) = codec_plugin::<C>(state)?;
+        plugin.codec.set_position(&mut file, subsound, position)
+    })
+    .into_raw()
+}
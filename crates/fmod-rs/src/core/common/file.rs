@@ -1,16 +1,24 @@
 //! Functionality relating to FMOD's use of the file system.
 
 use {
-    crate::utils::catch_user_unwind,
+    crate::utils::{catch_user_unwind, catch_user_unwind_as},
     fmod::{raw::*, *},
+    parking_lot::{Mutex, RwLock},
     std::{
         ffi::CStr,
         ffi::{c_char, c_void},
-        io::{self, Read, Write},
+        fmt,
+        future::Future,
+        io::{self, Read, Seek, SeekFrom, Write},
         marker::PhantomData,
         mem::MaybeUninit,
         pin::Pin,
         slice,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+        },
+        task::{Context, Poll, Waker},
     },
 };
 
@@ -337,7 +345,7 @@ pub(crate) unsafe extern "system" fn useropen<FS: FileSystem>(
     handle: *mut *mut c_void,
     _userdata: *mut c_void,
 ) -> FMOD_RESULT {
-    catch_user_unwind(|| {
+    catch_user_unwind_as(Error::FileBad, || {
         let name = CStr::from_ptr(name);
         let file = FS::open(name)?;
         *filesize = file.file_size.try_into().map_err(|_| Error::FileBad)?;
@@ -352,7 +360,7 @@ pub(crate) unsafe extern "system" fn userclose<FS: FileSystem>(
     _userdata: *mut c_void,
 ) -> FMOD_RESULT {
     let file = Pin::new_unchecked(Box::from_raw(handle.cast()));
-    catch_user_unwind(|| FS::close(file)).into_raw()
+    catch_user_unwind_as(Error::FileBad, || FS::close(file)).into_raw()
 }
 
 /// Callbacks to implement all file I/O instead of using the platform native
@@ -378,7 +386,7 @@ pub(crate) unsafe extern "system" fn userread<FS: SyncFileSystem>(
     bytesread: *mut u32,
     _userdata: *mut c_void,
 ) -> FMOD_RESULT {
-    catch_user_unwind(|| {
+    catch_user_unwind_as(Error::FileBad, || {
         let buffer = slice::from_raw_parts_mut(buffer.cast(), ix!(sizebytes));
         let file = Pin::new_unchecked(&mut *handle.cast());
 
@@ -404,7 +412,94 @@ pub(crate) unsafe extern "system" fn userseek<FS: SyncFileSystem>(
     _userdata: *mut c_void,
 ) -> FMOD_RESULT {
     let file = Pin::new_unchecked(&mut *handle.cast());
-    catch_user_unwind(|| FS::seek(file, pos)).into_raw()
+    catch_user_unwind_as(Error::FileBad, || FS::seek(file, pos)).into_raw()
+}
+
+/// A readable and seekable file, as opened by [`StdFileSystem`].
+pub trait StdFile: Read + Seek + Send {}
+impl<T: Read + Seek + Send + ?Sized> StdFile for T {}
+
+type StdOpener = Arc<dyn Fn(&str) -> io::Result<Box<dyn StdFile>> + Send + Sync>;
+
+static STD_OPENER: RwLock<Option<StdOpener>> = RwLock::new(None);
+
+/// A [`SyncFileSystem`] backed by `std::io` readers, such as entries of a
+/// `.pak`/zip archive or a virtual file system.
+///
+/// Set the function used to open files with [`StdFileSystem::set_opener`],
+/// then install with [`System::set_file_system_sync`]. The opener is global,
+/// and shared by every system using this file system: FMOD doesn't tell the
+/// file callbacks which system is opening a file, so it can't be looked up
+/// per system. To give systems different openers, implement
+/// [`SyncFileSystem`] for a type per system instead.
+///
+/// ```rust,ignore
+/// let archive: HashMap<&str, &'static [u8]> = load_archive()?;
+/// fmod::file::StdFileSystem::set_opener(move |name| {
+///     let data = archive.get(name).ok_or(io::ErrorKind::NotFound)?;
+///     Ok(Box::new(io::Cursor::new(*data)))
+/// });
+/// system.set_file_system_sync::<fmod::file::StdFileSystem>(-1)?;
+/// let sound = system.create_sound(cstr8!("music/theme.ogg"), fmod::Mode::Default)?;
+/// ```
+#[derive(Debug)]
+pub enum StdFileSystem {}
+
+impl StdFileSystem {
+    /// Sets the function used to open files by name.
+    ///
+    /// Files which are already open are unaffected.
+    pub fn set_opener(
+        opener: impl Fn(&str) -> io::Result<Box<dyn StdFile>> + Send + Sync + 'static,
+    ) {
+        *STD_OPENER.write() = Some(Arc::new(opener));
+    }
+
+    /// Removes the function used to open files; opening files fails with
+    /// [`Error::FileNotFound`] until another is set.
+    pub fn clear_opener() {
+        *STD_OPENER.write() = None;
+    }
+}
+
+impl FileSystem for StdFileSystem {
+    type File = Mutex<Box<dyn StdFile>>;
+
+    fn open(name: &CStr) -> Result<FileOpenInfo<Self::File>> {
+        let name = name.to_str().map_err(|_| Error::FileNotFound)?;
+        let Some(opener) = STD_OPENER.read().clone() else {
+            whoops!(no_panic: "StdFileSystem: no opener set to open {name:?}");
+            return Err(Error::FileNotFound);
+        };
+        let mut file = opener(name).map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => Error::FileNotFound,
+            _ => Error::FileBad,
+        })?;
+        let size = file
+            .seek(SeekFrom::End(0))
+            .and_then(|size| file.rewind().map(|()| size))
+            .map_err(|_| Error::FileCouldNotSeek)?;
+        Ok(FileOpenInfo {
+            handle: Box::pin(Mutex::new(file)),
+            file_size: size.try_into().map_err(|_| Error::FileBad)?,
+        })
+    }
+}
+
+impl SyncFileSystem for StdFileSystem {
+    fn read(file: Pin<&mut Self::File>, mut buffer: FileBuffer<'_>) -> Result {
+        buffer
+            .fill_from(file.get_mut().get_mut())
+            .map_err(|_| Error::FileBad)
+    }
+
+    fn seek(file: Pin<&mut Self::File>, pos: u32) -> Result {
+        file.get_mut()
+            .get_mut()
+            .seek(SeekFrom::Start(pos.into()))
+            .map(drop)
+            .map_err(|_| Error::FileCouldNotSeek)
+    }
 }
 
 /// Callbacks to implement all file I/O instead of using the platform native
@@ -455,14 +550,234 @@ pub(crate) unsafe extern "system" fn userasyncread<FS: AsyncFileSystem>(
     _userdata: *mut c_void,
 ) -> FMOD_RESULT {
     (*info).bytesread = 0; // ensure this starts at 0 in case FMOD doesn't
-    catch_user_unwind(|| FS::read(AsyncReadInfo::from_raw(info))).into_raw()
+    catch_user_unwind_as(Error::FileBad, || FS::read(AsyncReadInfo::from_raw(info))).into_raw()
 }
 
 pub(crate) unsafe extern "system" fn userasynccancel<FS: AsyncFileSystem>(
     info: *mut FMOD_ASYNCREADINFO,
     _userdata: *mut c_void,
 ) -> FMOD_RESULT {
-    catch_user_unwind(|| FS::cancel(AsyncReadInfo::from_raw(info))).into_raw()
+    catch_user_unwind_as(Error::FileBad, || FS::cancel(AsyncReadInfo::from_raw(info))).into_raw()
+}
+
+/// A boxed future reading the data for an [`AsyncReadRequest`].
+pub type ReadFuture = Pin<Box<dyn Future<Output = Result<Vec<u8>>> + Send + 'static>>;
+
+/// The parameters of an asynchronous read, received from [`SpawnFileSystem`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AsyncReadRequest {
+    /// Byte offset within the file where the read operation should occur.
+    pub offset: u32,
+    /// Number of bytes to read.
+    pub size: u32,
+    /// Priority hint for how quickly this operation should be serviced
+    /// where 0 represents low importance and 100 represents extreme
+    /// importance.
+    pub priority: i32,
+}
+
+/// Callbacks to implement all file I/O with futures, for use with an async
+/// runtime.
+///
+/// Install with [`System::set_file_system_spawn`]. This is an adapter over
+/// [`AsyncFileSystem`] which manages the outstanding [`AsyncReadInfo`]s:
+///
+/// - When FMOD requests a read, [`read`](Self::read) is called to create a
+///   future producing the data, which is wrapped in a [`ReadTask`] and given
+///   to [`spawn`](Self::spawn) to run on the runtime.
+/// - When the future completes, its data is copied into FMOD's buffer and
+///   the read is marked as done with the future's result.
+/// - When FMOD cancels a read, it is marked as done with
+///   [`Error::FileDiskEjected`] and the future is dropped the next time the
+///   task is polled, without its result being used.
+///
+/// Completion and cancellation are serialized, so exactly one of them
+/// completes a read, and FMOD's buffer is never touched after cancellation.
+/// The future doesn't have access to FMOD's buffer or the file handle; clone
+/// what it needs to read the file (e.g. an `Arc` of the runtime's file type)
+/// out of the file handle in [`read`](Self::read).
+pub trait SpawnFileSystem: FileSystem {
+    /// Create a future which reads `request.size` bytes from `file` at
+    /// `request.offset`.
+    ///
+    /// The future may produce fewer bytes than requested when the end of the
+    /// file is reached; additional bytes are ignored. Returning an error
+    /// such as [`Error::FileBad`] passes it on to FMOD.
+    fn read(file: Pin<&Self::File>, request: AsyncReadRequest) -> ReadFuture;
+
+    /// Spawn a read task onto the async runtime, e.g. `tokio::spawn(task)`.
+    ///
+    /// The task must be polled to completion (or dropped) from a thread other
+    /// than those calling into FMOD, as FMOD may block waiting for the read.
+    fn spawn(task: ReadTask);
+}
+
+/// Adapts a [`SpawnFileSystem`] into an [`AsyncFileSystem`].
+///
+/// Prefer [`System::set_file_system_spawn`] over using this directly.
+#[derive(Debug)]
+pub struct SpawnAdapter<FS: ?Sized>(PhantomData<FS>);
+
+impl<FS: SpawnFileSystem + ?Sized> FileSystem for SpawnAdapter<FS> {
+    type File = FS::File;
+
+    fn open(name: &CStr) -> Result<FileOpenInfo<Self::File>> {
+        FS::open(name)
+    }
+
+    fn close(file: Pin<Box<Self::File>>) -> Result {
+        FS::close(file)
+    }
+}
+
+unsafe impl<FS: SpawnFileSystem + ?Sized> AsyncFileSystem for SpawnAdapter<FS> {
+    unsafe fn read(info: AsyncReadInfo<Self::File>) -> Result {
+        let request = AsyncReadRequest {
+            offset: info.offset(),
+            size: info.size(),
+            priority: info.priority(),
+        };
+        let future = FS::read(info.handle(), request);
+
+        let id = NEXT_READ_ID.fetch_add(1, Ordering::Relaxed);
+        PENDING_READS.lock().push(PendingRead {
+            addr: info.addr(),
+            id,
+            waker: None,
+        });
+        FS::spawn(ReadTask {
+            future: Some(future),
+            info: AsyncReadInfo::from_raw(info.into_raw()),
+            id,
+        });
+        Ok(())
+    }
+
+    unsafe fn cancel(info: AsyncReadInfo<Self::File>) -> Result {
+        let mut pending = PENDING_READS.lock();
+        let Some(index) = pending.iter().position(|read| read.addr == info.addr()) else {
+            // already completed; completion happens under the lock
+            return Ok(());
+        };
+        let read = pending.swap_remove(index);
+        info.done(Err(Error::FileDiskEjected));
+        drop(pending);
+        if let Some(waker) = read.waker {
+            waker.wake();
+        }
+        Ok(())
+    }
+}
+
+struct PendingRead {
+    addr: usize,
+    id: u64,
+    waker: Option<Waker>,
+}
+
+/// Reads which have been requested by FMOD and not yet completed or cancelled.
+/// Addresses can be reused by FMOD once a read is done, so reads are also
+/// identified by a unique id.
+///
+/// This can't be kept in the [SystemState] of the reading system, as FMOD
+/// doesn't tell the file callbacks which system a read is for. The lock also
+/// has to outlive each read: FMOD may cancel a read which has already been
+/// completed, so the completion and the cancellation can't synchronize
+/// through anything owned by the read.
+static PENDING_READS: Mutex<Vec<PendingRead>> = Mutex::new(Vec::new());
+static NEXT_READ_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A read spawned by a [`SpawnFileSystem`].
+///
+/// This future drives the [`ReadFuture`] and completes the read with FMOD.
+/// It resolves early if FMOD cancels the read. If the task is dropped before
+/// completing, e.g. because the runtime shut down, the read is completed
+/// with [`Error::FileDiskEjected`] so FMOD doesn't wait for it forever.
+#[must_use = "futures do nothing unless polled"]
+pub struct ReadTask {
+    future: Option<ReadFuture>,
+    info: AsyncReadInfo<()>,
+    id: u64,
+}
+
+impl fmt::Debug for ReadTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadTask")
+            .field("addr", &self.info.addr())
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ReadTask {
+    /// Calls `f` with the read's pending entry, or returns `None` if the read
+    /// has been cancelled.
+    fn with_pending<R>(
+        &self,
+        pending: &mut Vec<PendingRead>,
+        f: impl FnOnce(&mut Vec<PendingRead>, usize) -> R,
+    ) -> Option<R> {
+        let addr = self.info.addr();
+        let index = pending
+            .iter()
+            .position(|read| read.addr == addr && read.id == self.id)?;
+        Some(f(pending, index))
+    }
+}
+
+impl Future for ReadTask {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.future.is_none() {
+            return Poll::Ready(());
+        }
+
+        // register the waker first, so a cancel racing the poll wakes us
+        let registered = self.with_pending(&mut PENDING_READS.lock(), |pending, index| {
+            pending[index].waker = Some(cx.waker().clone());
+        });
+        if registered.is_none() {
+            self.future = None;
+            return Poll::Ready(());
+        }
+
+        let future = self.future.as_mut().unwrap();
+        let result = match catch_user_unwind_as(Error::FileBad, || Ok(future.as_mut().poll(cx))) {
+            Ok(Poll::Pending) => return Poll::Pending,
+            Ok(Poll::Ready(result)) => result,
+            Err(error) => Err(error),
+        };
+        self.future = None;
+
+        let mut pending = PENDING_READS.lock();
+        self.with_pending(&mut pending, |pending, index| unsafe {
+            pending.swap_remove(index);
+            let result = result.map(|data| {
+                let mut buffer = self.info.buffer_mut();
+                let len = usize::min(data.len(), buffer.capacity() - buffer.written());
+                let _ = buffer.write(&data[..len]);
+            });
+            // still holding the lock, so FMOD can't cancel concurrently
+            self.info.done(result);
+        });
+        Poll::Ready(())
+    }
+}
+
+impl Drop for ReadTask {
+    fn drop(&mut self) {
+        if self.future.take().is_none() {
+            return;
+        }
+
+        let mut pending = PENDING_READS.lock();
+        self.with_pending(&mut pending, |pending, index| unsafe {
+            pending.swap_remove(index);
+            // still holding the lock, so FMOD can't cancel concurrently
+            self.info.done(Err(Error::FileDiskEjected));
+        });
+    }
 }
 
 /// 'Piggyback' on FMOD file reading routines to capture data as it's read.
@@ -525,6 +840,68 @@ pub(crate) unsafe extern "system" fn userseek_listen<FS: ListenFileSystem>(
     catch_user_unwind(|| Ok(FS::seek(handle as usize, pos))).into_raw()
 }
 
+/// A [`ListenFileSystem`] which counts FMOD's file traffic, for profiling.
+///
+/// Attach with [`System::attach_file_system`], then sample the running
+/// totals with [`ReadCounter::stats`]. The counts are global, and shared by
+/// every system the counter is attached to.
+#[derive(Debug)]
+pub enum ReadCounter {}
+
+static COUNTER_OPENS: AtomicU64 = AtomicU64::new(0);
+static COUNTER_READS: AtomicU64 = AtomicU64::new(0);
+static COUNTER_BYTES_READ: AtomicU64 = AtomicU64::new(0);
+static COUNTER_SEEKS: AtomicU64 = AtomicU64::new(0);
+
+/// Running totals of file traffic counted by [`ReadCounter`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReadStats {
+    /// Number of files opened.
+    pub opens: u64,
+    /// Number of read operations.
+    pub reads: u64,
+    /// Total bytes read.
+    pub bytes_read: u64,
+    /// Number of seek operations.
+    pub seeks: u64,
+}
+
+impl ReadCounter {
+    /// Retrieves the running totals counted since the last
+    /// [`reset`](Self::reset).
+    pub fn stats() -> ReadStats {
+        ReadStats {
+            opens: COUNTER_OPENS.load(Ordering::Relaxed),
+            reads: COUNTER_READS.load(Ordering::Relaxed),
+            bytes_read: COUNTER_BYTES_READ.load(Ordering::Relaxed),
+            seeks: COUNTER_SEEKS.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Resets the running totals to zero.
+    pub fn reset() {
+        COUNTER_OPENS.store(0, Ordering::Relaxed);
+        COUNTER_READS.store(0, Ordering::Relaxed);
+        COUNTER_BYTES_READ.store(0, Ordering::Relaxed);
+        COUNTER_SEEKS.store(0, Ordering::Relaxed);
+    }
+}
+
+impl ListenFileSystem for ReadCounter {
+    fn open(_name: &CStr, _size: u32, _handle: usize) {
+        COUNTER_OPENS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn read(_handle: usize, buffer: &[u8], _eof: bool) {
+        COUNTER_READS.fetch_add(1, Ordering::Relaxed);
+        COUNTER_BYTES_READ.fetch_add(buffer.len() as u64, Ordering::Relaxed);
+    }
+
+    fn seek(_handle: usize, _pos: u32) {
+        COUNTER_SEEKS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
 /// 'Piggyback' on FMOD file reading routines to capture data as it's read.
 pub trait AsyncListenFileSystem: ListenFileSystem {
     #[allow(clippy::missing_safety_doc)]
@@ -560,3 +937,257 @@ pub(crate) unsafe extern "system" fn userasynccancel_listen<FS: AsyncListenFileS
 ) -> FMOD_RESULT {
     catch_user_unwind(|| Ok(FS::async_cancel(AsyncReadInfo::from_raw(info)))).into_raw()
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::utils::expect_callback_panic,
+        std::{
+            cell::RefCell,
+            ptr::{self, NonNull},
+            sync::atomic::{AtomicI32, AtomicU32},
+            task::Wake,
+            thread,
+        },
+    };
+
+    /// Reads which never complete at offset 0, and complete at once otherwise.
+    struct TestFs;
+
+    thread_local! {
+        static SPAWNED: RefCell<Option<ReadTask>> = const { RefCell::new(None) };
+    }
+
+    impl FileSystem for TestFs {
+        type File = ();
+
+        fn open(_: &CStr) -> Result<FileOpenInfo<()>> {
+            Err(Error::FileNotFound)
+        }
+    }
+
+    impl SpawnFileSystem for TestFs {
+        fn read(_: Pin<&()>, request: AsyncReadRequest) -> ReadFuture {
+            match request.offset {
+                0 => Box::pin(std::future::pending()),
+                _ => Box::pin(std::future::ready(Ok(vec![1; ix!(request.size)]))),
+            }
+        }
+
+        fn spawn(task: ReadTask) {
+            SPAWNED.with(|spawned| *spawned.borrow_mut() = Some(task));
+        }
+    }
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    /// A read as FMOD would issue it, recording how often it is completed.
+    struct Read {
+        raw: FMOD_ASYNCREADINFO,
+        buffer: [u8; 4],
+        completed: AtomicU32,
+        result: AtomicI32,
+    }
+
+    // the raw pointers only point into the read itself
+    unsafe impl Sync for Read {}
+
+    unsafe extern "system" fn done(info: *mut FMOD_ASYNCREADINFO, result: FMOD_RESULT) {
+        let read = &*(*info).userdata.cast::<Read>();
+        read.result.store(result, Ordering::SeqCst);
+        read.completed.fetch_add(1, Ordering::SeqCst);
+    }
+
+    impl Read {
+        fn new(offset: u32) -> Pin<Box<Self>> {
+            let mut read = Box::new(Read {
+                raw: FMOD_ASYNCREADINFO {
+                    handle: NonNull::<()>::dangling().as_ptr().cast(),
+                    offset,
+                    sizebytes: 4,
+                    priority: 0,
+                    userdata: ptr::null_mut(),
+                    buffer: ptr::null_mut(),
+                    bytesread: 0,
+                    done: Some(done),
+                },
+                buffer: [0; 4],
+                completed: AtomicU32::new(0),
+                result: AtomicI32::new(FMOD_OK),
+            });
+            read.raw.userdata = ptr::addr_of_mut!(*read).cast();
+            read.raw.buffer = read.buffer.as_mut_ptr().cast();
+            Box::into_pin(read)
+        }
+
+        fn info(&self) -> AsyncReadInfo<()> {
+            AsyncReadInfo::from_raw(ptr::addr_of!(self.raw).cast_mut())
+        }
+
+        /// Issues the read, returning the spawned task.
+        fn start(&self) -> ReadTask {
+            unsafe { SpawnAdapter::<TestFs>::read(self.info()) }.unwrap();
+            SPAWNED.with(|spawned| spawned.borrow_mut().take()).unwrap()
+        }
+
+        fn cancel(&self) {
+            unsafe { SpawnAdapter::<TestFs>::cancel(self.info()) }.unwrap();
+        }
+
+        fn completed(&self) -> u32 {
+            self.completed.load(Ordering::SeqCst)
+        }
+
+        fn result(&self) -> Result {
+            Error::from_raw(self.result.load(Ordering::SeqCst))
+        }
+
+        fn is_pending(&self) -> bool {
+            let addr = ptr::addr_of!(self.raw) as usize;
+            PENDING_READS.lock().iter().any(|read| read.addr == addr)
+        }
+    }
+
+    fn poll(task: &mut ReadTask) -> Poll<()> {
+        let waker = Arc::new(NoopWaker).into();
+        Pin::new(task).poll(&mut Context::from_waker(&waker))
+    }
+
+    /// Panics in every callback.
+    struct PanickingFs;
+
+    impl FileSystem for PanickingFs {
+        type File = ();
+
+        fn open(_: &CStr) -> Result<FileOpenInfo<()>> {
+            panic!("open");
+        }
+
+        fn close(_: Pin<Box<()>>) -> Result {
+            panic!("close");
+        }
+    }
+
+    impl SyncFileSystem for PanickingFs {
+        fn read(_: Pin<&mut ()>, _: FileBuffer<'_>) -> Result {
+            panic!("read");
+        }
+
+        fn seek(_: Pin<&mut ()>, _: u32) -> Result {
+            panic!("seek");
+        }
+    }
+
+    #[test]
+    fn file_callback_panics_are_file_bad() {
+        let handle = NonNull::<()>::dangling().as_ptr().cast::<c_void>();
+        let userdata = ptr::null_mut();
+
+        let result = expect_callback_panic("open", || unsafe {
+            let (mut size, mut file) = (0, ptr::null_mut());
+            Error::from_raw(useropen::<PanickingFs>(
+                c"file".as_ptr(),
+                &mut size,
+                &mut file,
+                userdata,
+            ))
+        });
+        assert_eq!(result, Err(Error::FileBad));
+
+        let mut buffer = [0; 4];
+        let result = expect_callback_panic("read", || unsafe {
+            let mut read = 0;
+            Error::from_raw(userread::<PanickingFs>(
+                handle,
+                buffer.as_mut_ptr().cast(),
+                4,
+                &mut read,
+                userdata,
+            ))
+        });
+        assert_eq!(result, Err(Error::FileBad));
+
+        let result = expect_callback_panic("seek", || unsafe {
+            Error::from_raw(userseek::<PanickingFs>(handle, 0, userdata))
+        });
+        assert_eq!(result, Err(Error::FileBad));
+
+        let result = expect_callback_panic("close", || unsafe {
+            Error::from_raw(userclose::<PanickingFs>(handle, userdata))
+        });
+        assert_eq!(result, Err(Error::FileBad));
+    }
+
+    #[test]
+    fn dropped_task_completes_read() {
+        let read = Read::new(0);
+        let mut task = read.start();
+        assert_eq!(poll(&mut task), Poll::Pending);
+        drop(task);
+        assert_eq!(read.completed(), 1);
+        assert_eq!(read.result(), Err(Error::FileDiskEjected));
+        assert!(!read.is_pending());
+
+        let read = Read::new(0);
+        drop(read.start());
+        assert_eq!(read.completed(), 1);
+        assert!(!read.is_pending());
+    }
+
+    #[test]
+    fn dropped_task_after_cancel_or_completion_is_noop() {
+        let read = Read::new(0);
+        let task = read.start();
+        read.cancel();
+        drop(task);
+        assert_eq!(read.completed(), 1);
+        assert_eq!(read.result(), Err(Error::FileDiskEjected));
+
+        let read = Read::new(1);
+        let mut task = read.start();
+        assert_eq!(poll(&mut task), Poll::Ready(()));
+        drop(task);
+        read.cancel();
+        assert_eq!(read.completed(), 1);
+        assert_eq!(read.result(), Ok(()));
+        assert_eq!(read.buffer, [1; 4]);
+        assert!(!read.is_pending());
+    }
+
+    #[test]
+    fn read_completes_exactly_once_under_contention() {
+        const READS: u32 = 256;
+        for round in 0..8 {
+            let reads: Vec<_> = (0..READS).map(|i| Read::new((i + round) % 2)).collect();
+            let tasks: Vec<_> = reads.iter().map(|read| read.start()).collect();
+
+            thread::scope(|scope| {
+                scope.spawn(|| {
+                    for mut task in tasks {
+                        let _ = poll(&mut task);
+                    }
+                });
+                scope.spawn(|| {
+                    for read in reads.iter().rev() {
+                        read.cancel();
+                    }
+                });
+            });
+
+            for read in &reads {
+                assert_eq!(read.completed(), 1);
+                assert!(!read.is_pending());
+                if read.result().is_ok() {
+                    assert_eq!(read.buffer, [1; 4]);
+                } else {
+                    assert_eq!(read.result(), Err(Error::FileDiskEjected));
+                }
+            }
+        }
+    }
+}
@@ -2,6 +2,7 @@ use {
     crate::utils::{decode_sbcd_u16, decode_sbcd_u8},
     fmod::{raw::*, *},
     smart_default::SmartDefault,
+    std::{fmt, ops},
 };
 
 fmod_struct! {
@@ -53,6 +54,124 @@ fmod_struct! {
     }
 }
 
+impl Guid {
+    /// Parses a GUID in FMOD's canonical format, as exported by FMOD Studio:
+    /// `{xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx}`, with hexadecimal digits of
+    /// either case.
+    pub fn parse(s: &str) -> Result<Guid, GuidParseError> {
+        let bytes = s.as_bytes();
+        if bytes.len() != 38 {
+            return Err(GuidParseError::InvalidLength { len: bytes.len() });
+        }
+        for (position, expected) in [
+            (0, '{'),
+            (9, '-'),
+            (14, '-'),
+            (19, '-'),
+            (24, '-'),
+            (37, '}'),
+        ] {
+            if bytes[position] != expected as u8 {
+                return Err(GuidParseError::ExpectedChar { position, expected });
+            }
+        }
+
+        let hex = |start: usize, len: usize| -> Result<u64, GuidParseError> {
+            bytes[start..start + len]
+                .iter()
+                .enumerate()
+                .try_fold(0, |acc, (offset, &digit)| {
+                    let value = (digit as char)
+                        .to_digit(16)
+                        .ok_or(GuidParseError::InvalidHex {
+                            position: start + offset,
+                        })?;
+                    Ok(acc << 4 | value as u64)
+                })
+        };
+
+        let data4_hi = hex(20, 4)?;
+        let data4_lo = hex(25, 12)?;
+        let mut data4 = [0; 8];
+        data4[..2].copy_from_slice(&(data4_hi as u16).to_be_bytes());
+        data4[2..].copy_from_slice(&data4_lo.to_be_bytes()[2..]);
+        Ok(Guid {
+            data1: hex(1, 8)? as u32,
+            data2: hex(10, 4)? as u16,
+            data3: hex(15, 4)? as u16,
+            data4,
+        })
+    }
+}
+
+impl fmt::Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Guid {
+            data1,
+            data2,
+            data3,
+            data4: [a, b, c, d, e, g, h, i],
+        } = *self;
+        write!(
+            f,
+            "{{{data1:08x}-{data2:04x}-{data3:04x}-{a:02x}{b:02x}-{c:02x}{d:02x}{e:02x}{g:02x}{h:02x}{i:02x}}}"
+        )
+    }
+}
+
+impl std::str::FromStr for Guid {
+    type Err = GuidParseError;
+
+    fn from_str(s: &str) -> Result<Guid, GuidParseError> {
+        Guid::parse(s)
+    }
+}
+
+/// An error from [`Guid::parse`].
+///
+/// Positions are byte offsets into the parsed string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GuidParseError {
+    /// The string is not 38 bytes long.
+    InvalidLength {
+        /// The length of the string.
+        len: usize,
+    },
+    /// A brace or dash is missing.
+    ExpectedChar {
+        /// Where the character was expected.
+        position: usize,
+        /// The expected character.
+        expected: char,
+    },
+    /// A character is not a hexadecimal digit.
+    InvalidHex {
+        /// Where the character is.
+        position: usize,
+    },
+}
+
+impl std::error::Error for GuidParseError {}
+
+impl fmt::Display for GuidParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuidParseError::InvalidLength { len } => {
+                write!(f, "invalid GUID length {len}, expected 38")
+            },
+            GuidParseError::ExpectedChar { position, expected } => {
+                write!(f, "invalid GUID, expected {expected:?} at {position}")
+            },
+            GuidParseError::InvalidHex { position } => {
+                write!(
+                    f,
+                    "invalid GUID, expected a hexadecimal digit at {position}"
+                )
+            },
+        }
+    }
+}
+
 fmod_class! {
     /// Named marker for a given point in time.
     ///
@@ -226,7 +345,10 @@ fmod_enum! {
 
 /// Maximum number of channels per frame of audio supported by audio files,
 /// buffers, connections and DSPs.
+///
+/// This is large enough to hold every [Speaker] of any [SpeakerMode].
 pub const MAX_CHANNEL_WIDTH: usize = FMOD_MAX_CHANNEL_WIDTH as usize;
+static_assert!(SpeakerMode::Surround714.speakers().len() <= MAX_CHANNEL_WIDTH);
 
 /// Maximum number of listeners supported.
 pub const MAX_LISTENERS: usize = FMOD_MAX_LISTENERS as usize;
@@ -346,6 +468,136 @@ fmod_flags! {
     }
 }
 
+/// Modes which require extra information provided via [`CreateSoundEx`].
+const EXTENDED_MODES: Mode =
+    Mode::from_raw(FMOD_OPENUSER | FMOD_OPENMEMORY | FMOD_OPENMEMORY_POINT | FMOD_OPENRAW);
+
+/// Sets of [`Mode`] flags of which at most one may be used to create a sound,
+/// and why.
+const EXCLUSIVE_MODES: &[(Mode, &str)] = &[
+    (
+        Mode::from_raw(FMOD_LOOP_OFF | FMOD_LOOP_NORMAL | FMOD_LOOP_BIDI),
+        "a sound has a single loop mode",
+    ),
+    (
+        Mode::from_raw(FMOD_2D | FMOD_3D),
+        "a sound is either 2D or 3D",
+    ),
+    (
+        Mode::from_raw(FMOD_CREATESTREAM | FMOD_CREATESAMPLE | FMOD_CREATECOMPRESSEDSAMPLE),
+        "a sound is either streamed, decompressed into memory, or kept compressed in memory",
+    ),
+    (
+        Mode::from_raw(FMOD_OPENUSER | FMOD_OPENMEMORY | FMOD_OPENMEMORY_POINT),
+        "a sound is either user created, duplicated from memory, or used in place in memory",
+    ),
+    (
+        Mode::from_raw(FMOD_3D_HEADRELATIVE | FMOD_3D_WORLDRELATIVE),
+        "a 3D sound is either relative to the listener or to the world",
+    ),
+    (
+        Mode::from_raw(
+            FMOD_3D_INVERSEROLLOFF
+                | FMOD_3D_LINEARROLLOFF
+                | FMOD_3D_LINEARSQUAREROLLOFF
+                | FMOD_3D_INVERSETAPEREDROLLOFF
+                | FMOD_3D_CUSTOMROLLOFF,
+        ),
+        "a 3D sound has a single rolloff model",
+    ),
+    (
+        Mode::from_raw(FMOD_LOOP_BIDI | FMOD_CREATESTREAM),
+        "bidirectional looping only works on static sounds",
+    ),
+    (
+        Mode::from_raw(FMOD_OPENONLY | FMOD_ACCURATETIME),
+        "accurate time scans the whole file when it is opened, which open only skips",
+    ),
+    (
+        Mode::from_raw(FMOD_UNIQUE | FMOD_LOOP_NORMAL | FMOD_LOOP_BIDI),
+        "a unique sound plays once at a time, and can't loop",
+    ),
+];
+
+impl Mode {
+    /// Checks that this mode can be used to create a sound, with
+    /// [System::create_sound_ex] if `has_exinfo` is set, or
+    /// [System::create_sound] otherwise.
+    ///
+    /// This rejects flags which need a [`CreateSoundEx`] when it isn't
+    /// provided, and contradictory combinations of flags, such as
+    /// [Mode::LoopNormal] with [Mode::LoopBidi]. Problems which depend on the
+    /// sound data, such as using [Mode::OpenMemoryPoint] with compressed data
+    /// without [Mode::CreateCompressedSample], are still reported by FMOD.
+    pub fn validate_for_create(self, has_exinfo: bool) -> Result<(), ModeValidationError> {
+        if !has_exinfo && self.intersects(EXTENDED_MODES) {
+            return Err(ModeValidationError::NeedsCreateSoundEx {
+                flag: lowest_flag(self & EXTENDED_MODES),
+            });
+        }
+
+        for &(exclusive, reason) in EXCLUSIVE_MODES {
+            let used = self & exclusive;
+            let flag = lowest_flag(used);
+            let conflicts_with = lowest_flag(Mode::from_raw(used.into_raw() & !flag.into_raw()));
+            if conflicts_with != Mode::zeroed() {
+                return Err(ModeValidationError::Conflict {
+                    flag,
+                    conflicts_with,
+                    reason,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn lowest_flag(mode: Mode) -> Mode {
+    let raw = mode.into_raw();
+    Mode::from_raw(raw & raw.wrapping_neg())
+}
+
+/// An error from [`Mode::validate_for_create`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModeValidationError {
+    /// The flag needs extra information provided with [`CreateSoundEx`].
+    NeedsCreateSoundEx {
+        /// The flag which needs extra information.
+        flag: Mode,
+    },
+    /// Two flags can't be combined.
+    Conflict {
+        /// The first of the conflicting flags.
+        flag: Mode,
+        /// The second of the conflicting flags.
+        conflicts_with: Mode,
+        /// Why the flags can't be combined.
+        reason: &'static str,
+    },
+}
+
+impl std::error::Error for ModeValidationError {}
+
+impl fmt::Display for ModeValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModeValidationError::NeedsCreateSoundEx { flag } => write!(
+                f,
+                "{flag:?} needs extra information; use create_sound_ex instead"
+            ),
+            ModeValidationError::Conflict {
+                flag,
+                conflicts_with,
+                reason,
+            } => write!(
+                f,
+                "{flag:?} can't be combined with {conflicts_with:?}; {reason}"
+            ),
+        }
+    }
+}
+
 fmod_enum! {
     /// Assigns an enumeration for a speaker index.
     pub enum Speaker: FMOD_SPEAKER
@@ -456,6 +708,90 @@ fmod_enum! {
     }
 }
 
+impl SpeakerMode {
+    /// Retrieves the channel count for this speaker mode.
+    ///
+    /// [SpeakerMode::Default] and [SpeakerMode::Raw] do not have a fixed
+    /// channel count, so this returns `None` for them. Otherwise, this matches
+    /// [System::get_speaker_mode_channels] without requiring a [System].
+    pub const fn channel_count(self) -> Option<usize> {
+        match self {
+            SpeakerMode::Default | SpeakerMode::Raw => None,
+            mode => Some(mode.speakers().len()),
+        }
+    }
+
+    /// The [Speaker]s of this speaker mode, in channel order.
+    ///
+    /// [SpeakerMode::Default] and [SpeakerMode::Raw] do not have a fixed
+    /// speaker layout, so they have no speakers.
+    pub const fn speakers(self) -> &'static [Speaker] {
+        use Speaker::*;
+        match self {
+            SpeakerMode::Default | SpeakerMode::Raw => &[],
+            SpeakerMode::Mono => &[FrontCenter],
+            SpeakerMode::Stereo => &[FrontLeft, FrontRight],
+            SpeakerMode::Quad => &[FrontLeft, FrontRight, SurroundLeft, SurroundRight],
+            SpeakerMode::Surround => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                SurroundLeft,
+                SurroundRight,
+            ],
+            SpeakerMode::Surround51 => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                SurroundLeft,
+                SurroundRight,
+            ],
+            SpeakerMode::Surround71 => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                SurroundLeft,
+                SurroundRight,
+                BackLeft,
+                BackRight,
+            ],
+            SpeakerMode::Surround714 => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                SurroundLeft,
+                SurroundRight,
+                BackLeft,
+                BackRight,
+                TopFrontLeft,
+                TopFrontRight,
+                TopBackLeft,
+                TopBackRight,
+            ],
+        }
+    }
+}
+
+impl Speaker {
+    /// Retrieves the channel index of this speaker in a speaker mode.
+    ///
+    /// Returns `None` if the speaker is not part of the speaker mode's layout.
+    pub const fn index_in(self, mode: SpeakerMode) -> Option<usize> {
+        let speakers = mode.speakers();
+        let mut i = 0;
+        while i < speakers.len() {
+            if speakers[i] as i32 == self as i32 {
+                return Some(i);
+            }
+            i += 1;
+        }
+        None
+    }
+}
+
 fmod_typedef! {
     /// Time types used for position or length.
     pub enum TimeUnit: FMOD_TIMEUNIT {
@@ -479,8 +815,14 @@ fmod_typedef! {
 }
 
 /// Time used for position or length.
+///
+/// Times of the same unit can be added and subtracted; doing so with
+/// mismatched units panics. The operators are checked, and panic on overflow
+/// in all build profiles rather than wrapping; use [`Time::checked_add`] or
+/// [`Time::saturating_add`] (and their `sub` counterparts) to handle
+/// overflow instead. Use [`Time::to_unit`] to convert between units.
 #[allow(missing_docs)]
-#[derive(Debug, Clone, Copy, SmartDefault)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SmartDefault)]
 pub struct Time {
     pub value: u32,
     #[default(TimeUnit::Pcm)]
@@ -532,6 +874,181 @@ impl Time {
     pub fn mod_pattern(value: u32) -> Self {
         Self::new(value, TimeUnit::ModPattern)
     }
+
+    /// Convert this time measure to `target` units.
+    ///
+    /// Only [milliseconds](TimeUnit::Ms), [PCM samples](TimeUnit::Pcm) and
+    /// [PCM bytes](TimeUnit::PcmBytes) can be converted between, and
+    /// milliseconds are rounded down. PCM bytes refer to the decoded PCM data,
+    /// so they can only be converted for a sound with a PCM format; for
+    /// compressed sounds converting to or from PCM bytes returns
+    /// [`Error::Format`]. The other units depend on the sound's contents
+    /// (e.g. the bitrate for [raw bytes](TimeUnit::RawBytes)), so converting
+    /// them to a different unit also returns [`Error::Format`]. Converting to
+    /// or from milliseconds with a `sample_rate` of 0 returns
+    /// [`Error::InvalidParam`], as does a result which does not fit in a `u32`.
+    pub fn to_unit(self, target: TimeUnit, ctx: &TimeContext) -> Result<Time> {
+        if self.unit == target {
+            return Ok(self);
+        }
+
+        let involves_ms = self.unit == TimeUnit::Ms || target == TimeUnit::Ms;
+        if involves_ms && ctx.sample_rate == 0 {
+            whoops!(no_panic: "converting {self:?} to {target:?} with a sample rate of 0");
+            yeet!(Error::InvalidParam);
+        }
+
+        let samples: u64 = match self.unit {
+            TimeUnit::Ms => self.value as u64 * ctx.sample_rate as u64 / 1000,
+            TimeUnit::Pcm => self.value as u64,
+            TimeUnit::PcmBytes => self.value as u64 / ctx.bytes_per_frame()?,
+            _ => yeet!(Error::Format),
+        };
+        let value = match target {
+            TimeUnit::Ms => samples * 1000 / ctx.sample_rate as u64,
+            TimeUnit::Pcm => samples,
+            TimeUnit::PcmBytes => samples * ctx.bytes_per_frame()?,
+            _ => yeet!(Error::Format),
+        };
+
+        let value = value.try_into().map_err(|_| Error::InvalidParam)?;
+        Ok(Time::new(value, target))
+    }
+
+    /// Checked addition. Returns `None` if the units differ or on overflow.
+    pub fn checked_add(self, rhs: Time) -> Option<Time> {
+        if self.unit != rhs.unit {
+            return None;
+        }
+        Some(Time::new(self.value.checked_add(rhs.value)?, self.unit))
+    }
+
+    /// Checked subtraction. Returns `None` if the units differ or on overflow.
+    pub fn checked_sub(self, rhs: Time) -> Option<Time> {
+        if self.unit != rhs.unit {
+            return None;
+        }
+        Some(Time::new(self.value.checked_sub(rhs.value)?, self.unit))
+    }
+
+    /// Saturating addition. Panics if the units differ.
+    pub fn saturating_add(self, rhs: Time) -> Time {
+        assert_same_unit(self, rhs);
+        Time::new(self.value.saturating_add(rhs.value), self.unit)
+    }
+
+    /// Saturating subtraction. Panics if the units differ.
+    pub fn saturating_sub(self, rhs: Time) -> Time {
+        assert_same_unit(self, rhs);
+        Time::new(self.value.saturating_sub(rhs.value), self.unit)
+    }
+}
+
+#[track_caller]
+fn assert_same_unit(lhs: Time, rhs: Time) {
+    assert!(
+        lhs.unit == rhs.unit,
+        "mismatched time units: {:?} and {:?}",
+        lhs.unit,
+        rhs.unit,
+    );
+}
+
+impl ops::Add for Time {
+    type Output = Time;
+
+    #[track_caller]
+    fn add(self, rhs: Time) -> Time {
+        assert_same_unit(self, rhs);
+        let value = self.value.checked_add(rhs.value);
+        Time::new(value.expect("overflow when adding times"), self.unit)
+    }
+}
+
+impl ops::AddAssign for Time {
+    #[track_caller]
+    fn add_assign(&mut self, rhs: Time) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::Sub for Time {
+    type Output = Time;
+
+    #[track_caller]
+    fn sub(self, rhs: Time) -> Time {
+        assert_same_unit(self, rhs);
+        let value = self.value.checked_sub(rhs.value);
+        Time::new(value.expect("overflow when subtracting times"), self.unit)
+    }
+}
+
+impl ops::SubAssign for Time {
+    #[track_caller]
+    fn sub_assign(&mut self, rhs: Time) {
+        *self = *self - rhs;
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.value;
+        match self.unit {
+            TimeUnit::Ms => write!(f, "{}.{:03}s", value / 1000, value % 1000),
+            TimeUnit::Pcm => write!(f, "{value} samples"),
+            TimeUnit::PcmBytes => write!(f, "{value} bytes"),
+            TimeUnit::RawBytes => write!(f, "{value} raw bytes"),
+            TimeUnit::PcmFraction => write!(f, "{value}/2^32 samples"),
+            TimeUnit::ModOrder => write!(f, "order {value}"),
+            TimeUnit::ModRow => write!(f, "row {value}"),
+            TimeUnit::ModPattern => write!(f, "pattern {value}"),
+            unit => write!(f, "{value} ({unit:?})"),
+        }
+    }
+}
+
+/// The format information needed to convert a [`Time`] between units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeContext {
+    /// Sample rate.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Hertz</dd>
+    /// </dl>
+    pub sample_rate: u32,
+    /// Number of channels.
+    pub channels: u32,
+    /// Number of bits per sample, or 0 for compressed formats.
+    pub bits_per_sample: u32,
+}
+
+impl TimeContext {
+    /// Retrieve the format information of a sound, using
+    /// [`Sound::get_format`] and [`Sound::get_defaults`].
+    pub fn from_sound(sound: &Sound) -> Result<Self> {
+        let format = sound.get_format()?;
+        let (frequency, _) = sound.get_defaults()?;
+        let bits_per_sample = match format.format {
+            SoundFormat::Pcm8
+            | SoundFormat::Pcm16
+            | SoundFormat::Pcm24
+            | SoundFormat::Pcm32
+            | SoundFormat::PcmFloat => format.bits_per_sample as u32,
+            _ => 0,
+        };
+        Ok(TimeContext {
+            sample_rate: frequency as u32,
+            channels: format.channels as u32,
+            bits_per_sample,
+        })
+    }
+
+    fn bytes_per_frame(&self) -> Result<u64> {
+        match self.channels as u64 * self.bits_per_sample as u64 / 8 {
+            0 => Err(Error::Format),
+            bytes => Ok(bytes),
+        }
+    }
 }
 
 /// 3D attenuation factors for the direct and reverb paths.
@@ -542,3 +1059,384 @@ pub struct Occlusion {
     /// Occlusion factor for the reverb path where 0 represents no occlusion and 1 represents full occlusion.
     pub reverb: f32,
 }
+
+impl Occlusion {
+    /// No occlusion on either path.
+    pub const NONE: Occlusion = Occlusion {
+        direct: 0.0,
+        reverb: 0.0,
+    };
+
+    /// Clamps both factors to the valid range of [0, 1].
+    pub fn clamp(self) -> Self {
+        Occlusion {
+            direct: self.direct.clamp(0.0, 1.0),
+            reverb: self.reverb.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Combines the occlusion of two occluders, taking the stronger occlusion
+    /// of each path.
+    ///
+    /// This is useful to fold the occlusion from multiple queries, e.g. with
+    /// `occlusions.fold(Occlusion::NONE, Occlusion::combine)`, before passing
+    /// it to [ChannelControl::set_3d_occlusion].
+    pub fn combine(self, other: Self) -> Self {
+        Occlusion {
+            direct: self.direct.max(other.direct),
+            reverb: self.reverb.max(other.reverb),
+        }
+    }
+}
+
+impl From<Occlusion> for (f32, f32) {
+    fn from(occlusion: Occlusion) -> Self {
+        (occlusion.direct, occlusion.reverb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CTX: TimeContext = TimeContext {
+        sample_rate: 48_000,
+        channels: 2,
+        bits_per_sample: 16,
+    };
+
+    #[test]
+    fn time_arithmetic() {
+        assert_eq!(Time::ms(250) + Time::ms(750), Time::ms(1000));
+        assert_eq!(Time::pcm(10) - Time::pcm(4), Time::pcm(6));
+        assert_eq!(Time::pcm(u32::MAX).checked_add(Time::pcm(1)), None);
+        assert_eq!(Time::pcm(0).checked_sub(Time::pcm(1)), None);
+        assert_eq!(Time::pcm(1).checked_add(Time::ms(1)), None);
+        let max = Time::pcm(u32::MAX);
+        assert_eq!(max.saturating_add(Time::pcm(1)), max);
+        assert_eq!(Time::pcm(0).saturating_sub(Time::pcm(1)), Time::pcm(0));
+    }
+
+    #[test]
+    #[should_panic = "overflow when adding times"]
+    fn time_add_overflow_panics() {
+        let _ = Time::pcm(u32::MAX) + Time::pcm(1);
+    }
+
+    #[test]
+    #[should_panic = "overflow when subtracting times"]
+    fn time_sub_overflow_panics() {
+        let _ = Time::ms(0) - Time::ms(1);
+    }
+
+    #[test]
+    #[should_panic = "mismatched time units"]
+    fn time_mismatched_units_panic() {
+        let _ = Time::ms(1) + Time::pcm(1);
+    }
+
+    #[test]
+    fn time_conversion() {
+        let second = Time::ms(1000);
+        assert_eq!(second.to_unit(TimeUnit::Pcm, &CTX), Ok(Time::pcm(48_000)));
+        assert_eq!(
+            second.to_unit(TimeUnit::PcmBytes, &CTX),
+            Ok(Time::pcm_bytes(192_000)),
+        );
+        assert_eq!(
+            Time::pcm_bytes(192_000).to_unit(TimeUnit::Ms, &CTX),
+            Ok(second),
+        );
+        assert_eq!(
+            Time::raw_bytes(10).to_unit(TimeUnit::Pcm, &CTX),
+            Err(Error::Format),
+        );
+        let compressed = TimeContext {
+            bits_per_sample: 0,
+            ..CTX
+        };
+        assert_eq!(
+            second.to_unit(TimeUnit::PcmBytes, &compressed),
+            Err(Error::Format),
+        );
+        assert_eq!(
+            Time::pcm(u32::MAX).to_unit(TimeUnit::Ms, &CTX),
+            Ok(Time::ms((u32::MAX as u64 * 1000 / 48_000) as u32)),
+        );
+        assert_eq!(
+            Time::ms(u32::MAX).to_unit(TimeUnit::PcmBytes, &CTX),
+            Err(Error::InvalidParam),
+        );
+    }
+
+    #[test]
+    fn time_conversion_rejects_zero_sample_rate() {
+        let ctx = TimeContext {
+            sample_rate: 0,
+            ..CTX
+        };
+        for (from, to) in [
+            (Time::ms(1000), TimeUnit::Pcm),
+            (Time::ms(1000), TimeUnit::PcmBytes),
+            (Time::pcm(48_000), TimeUnit::Ms),
+            (Time::pcm_bytes(192_000), TimeUnit::Ms),
+        ] {
+            assert_eq!(from.to_unit(to, &ctx), Err(Error::InvalidParam));
+        }
+        assert_eq!(Time::ms(5).to_unit(TimeUnit::Ms, &ctx), Ok(Time::ms(5)));
+        assert_eq!(
+            Time::pcm(5).to_unit(TimeUnit::PcmBytes, &ctx),
+            Ok(Time::pcm_bytes(20)),
+        );
+    }
+
+    const GUID: &str = "{0123abcd-4567-89ef-0123-456789abcdef}";
+
+    #[test]
+    fn guid_round_trip() {
+        let guid: Guid = GUID.parse().unwrap();
+        assert_eq!(guid.data1, 0x0123abcd);
+        assert_eq!(guid.data2, 0x4567);
+        assert_eq!(guid.data3, 0x89ef);
+        assert_eq!(guid.data4, [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]);
+        assert_eq!(guid.to_string(), GUID);
+        assert_eq!(GUID.to_uppercase().parse::<Guid>(), Ok(guid));
+    }
+
+    #[test]
+    fn guid_rejects_wrong_length() {
+        for s in ["", "{}", &GUID[..37], &format!("{GUID} "), &GUID[1..37]] {
+            assert_eq!(
+                s.parse::<Guid>(),
+                Err(GuidParseError::InvalidLength { len: s.len() }),
+                "{s:?}",
+            );
+        }
+        // length is in bytes, not chars
+        let s = GUID.replace('0', "\u{e9}");
+        assert!(matches!(
+            s.parse::<Guid>(),
+            Err(GuidParseError::InvalidLength { .. }),
+        ));
+    }
+
+    #[test]
+    fn guid_rejects_missing_or_misplaced_braces() {
+        let cases = [
+            (" 0123abcd-4567-89ef-0123-456789abcdef}", 0, '{'),
+            ("{0123abcd-4567-89ef-0123-456789abcdef ", 37, '}'),
+            ("}0123abcd-4567-89ef-0123-456789abcdef{", 0, '{'),
+            ("0123abcd-4567-89ef-0123-456789abcdef{}", 0, '{'),
+            ("{{0123abcd-4567-89ef-0123-456789abcdef", 9, '-'),
+            ("{0123abcd-4567-89ef-0123-456789abcde}f", 37, '}'),
+        ];
+        for (s, position, expected) in cases {
+            assert_eq!(
+                s.parse::<Guid>(),
+                Err(GuidParseError::ExpectedChar { position, expected }),
+                "{s:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn guid_rejects_wrong_dash_positions() {
+        let cases = [
+            ("{0123abc-d4567-89ef-0123-456789abcdef}", 9),
+            ("{0123abcd4-567-89ef-0123-456789abcdef}", 9),
+            ("{0123abcd-45678-9ef-0123-456789abcdef}", 14),
+            ("{0123abcd-4567-89ef0-123-456789abcdef}", 19),
+            ("{0123abcd-4567-89ef-01234-56789abcdef}", 24),
+            ("{0123abcd_4567_89ef_0123_456789abcdef}", 9),
+        ];
+        for (s, position) in cases {
+            assert_eq!(
+                s.parse::<Guid>(),
+                Err(GuidParseError::ExpectedChar {
+                    position,
+                    expected: '-'
+                }),
+                "{s:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn guid_rejects_non_hex_digits() {
+        for position in (1..37).filter(|p| ![9, 14, 19, 24].contains(p)) {
+            for bad in ["g", "G", " ", "-", "+", "x"] {
+                let mut s = GUID.to_owned();
+                s.replace_range(position..position + 1, bad);
+                assert_eq!(
+                    s.parse::<Guid>(),
+                    Err(GuidParseError::InvalidHex { position }),
+                    "{s:?}",
+                );
+            }
+        }
+        // a multibyte character of the right total length
+        let s = "{0123abcd-4567-89ef-0123-456789abc\u{e9}f}";
+        assert_eq!(
+            s.parse::<Guid>(),
+            Err(GuidParseError::InvalidHex { position: 34 }),
+        );
+    }
+
+    /// Every single-bit mode flag.
+    fn mode_flags() -> impl Iterator<Item = Mode> {
+        (0..FMOD_MODE::BITS).map(|bit| Mode::from_raw(1 << bit))
+    }
+
+    #[test]
+    fn mode_flags_are_valid_alone() {
+        for flag in mode_flags() {
+            assert_eq!(flag.validate_for_create(true), Ok(()), "{flag:?}");
+            if !flag.intersects(EXTENDED_MODES) {
+                assert_eq!(flag.validate_for_create(false), Ok(()), "{flag:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn extended_modes_need_create_sound_ex() {
+        for flag in mode_flags().filter(|&flag| flag.intersects(EXTENDED_MODES)) {
+            assert_eq!(
+                flag.validate_for_create(false),
+                Err(ModeValidationError::NeedsCreateSoundEx { flag }),
+            );
+        }
+        // checked before conflicts, which would also need exinfo to be useful
+        assert_eq!(
+            (Mode::OpenUser | Mode::OpenMemory).validate_for_create(false),
+            Err(ModeValidationError::NeedsCreateSoundEx {
+                flag: Mode::OpenUser
+            }),
+        );
+    }
+
+    #[test]
+    fn mode_flag_pairs_follow_exclusive_table() {
+        for first in mode_flags() {
+            for second in mode_flags().filter(|&second| second.into_raw() > first.into_raw()) {
+                let mode = first | second;
+                let expected = EXCLUSIVE_MODES
+                    .iter()
+                    .find(|&&(exclusive, _)| exclusive.is_set(mode))
+                    .map(|&(_, reason)| ModeValidationError::Conflict {
+                        flag: first,
+                        conflicts_with: second,
+                        reason,
+                    });
+                assert_eq!(
+                    mode.validate_for_create(true),
+                    expected.map_or(Ok(()), Err),
+                    "{mode:?}",
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn exclusive_mode_table() {
+        let conflict = |flag, conflicts_with, reason| {
+            Err(ModeValidationError::Conflict {
+                flag,
+                conflicts_with,
+                reason,
+            })
+        };
+        let cases = [
+            (
+                Mode::LoopNormal | Mode::LoopBidi,
+                Mode::LoopNormal,
+                Mode::LoopBidi,
+                0,
+            ),
+            (
+                Mode::LoopOff | Mode::LoopNormal,
+                Mode::LoopOff,
+                Mode::LoopNormal,
+                0,
+            ),
+            (Mode::D2 | Mode::D3, Mode::D2, Mode::D3, 1),
+            (
+                Mode::CreateStream | Mode::CreateSample,
+                Mode::CreateStream,
+                Mode::CreateSample,
+                2,
+            ),
+            (
+                Mode::OpenUser | Mode::OpenMemoryPoint,
+                Mode::OpenUser,
+                Mode::OpenMemoryPoint,
+                3,
+            ),
+            (
+                Mode::HeadRelative3d | Mode::WorldRelative3d,
+                Mode::HeadRelative3d,
+                Mode::WorldRelative3d,
+                4,
+            ),
+            (
+                Mode::LinearRolloff3d | Mode::CustomRolloff3d,
+                Mode::LinearRolloff3d,
+                Mode::CustomRolloff3d,
+                5,
+            ),
+            (
+                Mode::LoopBidi | Mode::CreateStream,
+                Mode::LoopBidi,
+                Mode::CreateStream,
+                6,
+            ),
+            (
+                Mode::OpenOnly | Mode::AccurateTime,
+                Mode::OpenOnly,
+                Mode::AccurateTime,
+                7,
+            ),
+            (
+                Mode::LoopNormal | Mode::Unique,
+                Mode::LoopNormal,
+                Mode::Unique,
+                8,
+            ),
+            (
+                Mode::LoopBidi | Mode::Unique,
+                Mode::LoopBidi,
+                Mode::Unique,
+                8,
+            ),
+        ];
+        for (mode, flag, conflicts_with, entry) in cases {
+            let (_, reason) = EXCLUSIVE_MODES[entry];
+            assert_eq!(
+                mode.validate_for_create(true),
+                conflict(flag, conflicts_with, reason),
+            );
+        }
+        // only the first conflict is reported
+        assert_eq!(
+            (Mode::D2 | Mode::D3 | Mode::LoopOff | Mode::LoopBidi).validate_for_create(true),
+            conflict(Mode::LoopOff, Mode::LoopBidi, EXCLUSIVE_MODES[0].1),
+        );
+        // compatible flags from different sets
+        let mode = Mode::LoopNormal | Mode::D3 | Mode::CreateStream | Mode::LinearRolloff3d;
+        assert_eq!(mode.validate_for_create(false), Ok(()));
+        assert_eq!(
+            (Mode::LoopOff | Mode::Unique | Mode::AccurateTime).validate_for_create(false),
+            Ok(()),
+        );
+    }
+
+    #[test]
+    fn mode_validation_error_display() {
+        let error = (Mode::D2 | Mode::D3)
+            .validate_for_create(false)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "D2 can't be combined with D3; a sound is either 2D or 3D",
+        );
+    }
+}
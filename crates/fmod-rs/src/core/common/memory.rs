@@ -3,8 +3,10 @@
 use {
     crate::utils::str_from_nonnull_unchecked,
     fmod::{raw::*, *},
+    parking_lot::Mutex,
     std::{
         alloc::{alloc, dealloc, realloc, Layout},
+        collections::HashMap,
         ffi::{c_char, c_uint, c_void},
         mem::{self, MaybeUninit},
         ptr,
@@ -43,7 +45,7 @@ pub fn get_stats(blocking: bool) -> Result<Stats> {
 }
 
 /// Information on the memory usage of FMOD.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Stats {
     /// Currently allocated memory at time of call.
     pub current_alloced: i32,
@@ -52,6 +54,92 @@ pub struct Stats {
     pub max_alloced: i32,
 }
 
+/// Runs `f`, returning its result along with how FMOD's memory usage
+/// changed while it ran.
+///
+/// The delta is only exact if nothing else uses FMOD while `f` runs, as
+/// [memory::get_stats] counts all of FMOD's allocations, including those made
+/// by other threads and by the mixer. This makes it best suited to tooling,
+/// such as reporting the memory cost of assets in an asset pipeline.
+///
+/// Statistics are retrieved with `blocking` set, so that allocations queued
+/// by `f` are counted.
+pub fn scoped_stats<T>(f: impl FnOnce() -> T) -> Result<(T, MemoryDelta)> {
+    let before = get_stats(true)?;
+    let result = f();
+    let after = get_stats(true)?;
+    Ok((
+        result,
+        MemoryDelta {
+            current_alloced: after.current_alloced as i64 - before.current_alloced as i64,
+            max_alloced: after.max_alloced as i64 - before.max_alloced as i64,
+        },
+    ))
+}
+
+/// A change in the memory usage of FMOD, from [memory::scoped_stats].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemoryDelta {
+    /// Change in currently allocated memory.
+    pub current_alloced: i64,
+    /// Change in the maximum allocated memory.
+    pub max_alloced: i64,
+}
+
+/// Memory used by a system's sounds created with
+/// [memory::record_sound_memory], by the address of the sound.
+///
+/// This is kept in the system's state, so it's forgotten along with the
+/// system's sounds when the system is released.
+#[derive(Debug, Default)]
+pub(crate) struct SoundMemory {
+    bytes: Mutex<HashMap<usize, usize>>,
+}
+
+impl SoundMemory {
+    fn record(&self, sound: *mut FMOD_SOUND, bytes: usize) {
+        self.bytes.lock().insert(sound as usize, bytes);
+    }
+
+    pub(crate) fn get(&self, sound: *mut FMOD_SOUND) -> Option<usize> {
+        self.bytes.lock().get(&(sound as usize)).copied()
+    }
+
+    /// Called after releasing a sound.
+    pub(crate) fn forget(&self, sound: *mut FMOD_SOUND) {
+        self.bytes.lock().remove(&(sound as usize));
+    }
+}
+
+/// Creates a sound with `create`, recording the memory it uses for
+/// [Sound::estimated_memory] and [SoundGroup::estimated_memory].
+///
+/// The memory is measured with [memory::scoped_stats], so the same caveats
+/// apply. Sounds created with [Mode::NonBlocking] or [Mode::CreateStream]
+/// allocate more memory after creation, which isn't included.
+///
+/// The measurement includes anything other threads allocate or free
+/// meanwhile, such as the mixer freeing a finished stream's buffers. If that
+/// makes the change in memory negative, it is recorded as 0 bytes.
+///
+/// ```rust,ignore
+/// let sound = memory::record_sound_memory(|| system.create_sound(path, Mode::Default))?;
+/// println!("{path}: {} bytes", sound.estimated_memory().unwrap_or(0));
+/// ```
+pub fn record_sound_memory<'a>(
+    create: impl FnOnce() -> Result<Handle<'a, Sound>>,
+) -> Result<Handle<'a, Sound>> {
+    let (sound, delta) = scoped_stats(create)?;
+    let sound = sound?;
+    let system = sound.get_system_object()?;
+    // other threads can free memory meanwhile, which could make this negative
+    let bytes = usize::try_from(delta.current_alloced).unwrap_or(0);
+    if let Some(state) = system.state() {
+        state.sound_memory.record(sound.as_raw(), bytes);
+    }
+    Ok(sound)
+}
+
 /// Specifies for FMOD to allocate and free memory in a user supplied
 /// buffer.
 ///
@@ -182,53 +182,9 @@ const fn channel_count_to_mode(count: usize) -> SpeakerMode {
 }
 
 fn speaker_to_channel(index: Speaker, mode: SpeakerMode) -> usize {
-    match (mode, index) {
-        (SpeakerMode::Mono, Speaker::FrontCenter) => 0,
-
-        (SpeakerMode::Stereo, Speaker::FrontLeft) => 0,
-        (SpeakerMode::Stereo, Speaker::FrontRight) => 1,
-
-        (SpeakerMode::Quad, Speaker::FrontLeft) => 0,
-        (SpeakerMode::Quad, Speaker::FrontRight) => 1,
-        (SpeakerMode::Quad, Speaker::SurroundLeft) => 2,
-        (SpeakerMode::Quad, Speaker::SurroundRight) => 3,
-
-        (SpeakerMode::Surround, Speaker::FrontLeft) => 0,
-        (SpeakerMode::Surround, Speaker::FrontRight) => 1,
-        (SpeakerMode::Surround, Speaker::FrontCenter) => 2,
-        (SpeakerMode::Surround, Speaker::SurroundLeft) => 3,
-        (SpeakerMode::Surround, Speaker::SurroundRight) => 4,
-
-        (SpeakerMode::Surround51, Speaker::FrontLeft) => 0,
-        (SpeakerMode::Surround51, Speaker::FrontRight) => 1,
-        (SpeakerMode::Surround51, Speaker::FrontCenter) => 2,
-        (SpeakerMode::Surround51, Speaker::LowFrequency) => 3,
-        (SpeakerMode::Surround51, Speaker::SurroundLeft) => 4,
-        (SpeakerMode::Surround51, Speaker::SurroundRight) => 5,
-
-        (SpeakerMode::Surround71, Speaker::FrontLeft) => 0,
-        (SpeakerMode::Surround71, Speaker::FrontRight) => 1,
-        (SpeakerMode::Surround71, Speaker::FrontCenter) => 2,
-        (SpeakerMode::Surround71, Speaker::LowFrequency) => 3,
-        (SpeakerMode::Surround71, Speaker::SurroundLeft) => 4,
-        (SpeakerMode::Surround71, Speaker::SurroundRight) => 5,
-        (SpeakerMode::Surround71, Speaker::BackLeft) => 6,
-        (SpeakerMode::Surround71, Speaker::BackRight) => 7,
-
-        (SpeakerMode::Surround714, Speaker::FrontLeft) => 0,
-        (SpeakerMode::Surround714, Speaker::FrontRight) => 1,
-        (SpeakerMode::Surround714, Speaker::FrontCenter) => 2,
-        (SpeakerMode::Surround714, Speaker::LowFrequency) => 3,
-        (SpeakerMode::Surround714, Speaker::SurroundLeft) => 4,
-        (SpeakerMode::Surround714, Speaker::SurroundRight) => 5,
-        (SpeakerMode::Surround714, Speaker::BackLeft) => 6,
-        (SpeakerMode::Surround714, Speaker::BackRight) => 7,
-        (SpeakerMode::Surround714, Speaker::TopFrontLeft) => 8,
-        (SpeakerMode::Surround714, Speaker::TopFrontRight) => 9,
-        (SpeakerMode::Surround714, Speaker::TopBackLeft) => 10,
-        (SpeakerMode::Surround714, Speaker::TopBackRight) => 11,
-
-        (mode, index) => panic!("{index:?} is an invalid speaker index for {mode:?}"),
+    match index.index_in(mode) {
+        Some(channel) => channel,
+        None => panic!("{index:?} is an invalid speaker index for {mode:?}"),
     }
 }
 
@@ -0,0 +1,360 @@
+//! Functionality relating to authoring output plugins.
+//!
+//! An output plugin lets FMOD mix into a custom target such as a proprietary
+//! audio transport, a file, or a network stream. Implement
+//! [`OutputDescription`] and register it with [`System::register_output`],
+//! then select it with [`System::set_output_by_plugin`] before calling
+//! [`System::init`].
+
+use {
+    crate::utils::catch_user_unwind,
+    fmod::{raw::*, *},
+    std::{ffi::c_void, marker::PhantomData, ptr},
+};
+
+fmod_typedef! {
+    /// Output method used to interact with the mixer.
+    pub enum OutputMethod: FMOD_OUTPUT_METHOD {
+        #[default]
+        /// Mixer will execute directly when the output is ready for more
+        /// data. Call [`OutputState::read_from_mixer`] from
+        /// [`OutputDescription::update`] or the output's own thread.
+        MixDirect   = FMOD_OUTPUT_METHOD_MIX_DIRECT,
+        /// Mixer will execute and buffer automatically on a separate thread,
+        /// which can be read from with [`OutputState::read_from_mixer`].
+        MixBuffered = FMOD_OUTPUT_METHOD_MIX_BUFFERED,
+    }
+}
+
+/// Output configuration negotiated during [`OutputDescription::init`].
+///
+/// The fields are initialized to what FMOD requests; the plugin may change
+/// the rate, speaker mode, channel count and buffer count to what it actually
+/// supports. The mixer always outputs [`SoundFormat::PcmFloat`], and the
+/// [buffer length](Self::dsp_buffer_length) can't be changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputConfig {
+    /// Index of the output driver selected by the user.
+    pub driver: i32,
+    /// Flags passed to [`System::init`].
+    pub flags: InitFlags,
+    /// Output rate.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Hertz</dd>
+    /// </dl>
+    pub rate: i32,
+    /// Speaker setup.
+    pub speaker_mode: SpeakerMode,
+    /// Number of channels for the given speaker mode, which must be provided
+    /// if `speaker_mode` is [`SpeakerMode::Raw`].
+    pub channels: i32,
+    dsp_buffer_length: u32,
+    /// Number of buffers to use for the mixer.
+    pub dsp_num_buffers: i32,
+    /// Number of additional buffers to allocate for [`OutputMethod::MixBuffered`].
+    pub dsp_num_additional_buffers: i32,
+}
+
+impl OutputConfig {
+    /// Size of each mixer buffer.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Samples</dd>
+    /// </dl>
+    pub fn dsp_buffer_length(&self) -> u32 {
+        self.dsp_buffer_length
+    }
+}
+
+/// Output plugin state provided to [`OutputDescription`] callbacks.
+#[derive(Debug, Clone, Copy)]
+pub struct OutputState<'a> {
+    raw: *mut FMOD_OUTPUT_STATE,
+    channels: usize,
+    _phantom: PhantomData<&'a FMOD_OUTPUT_STATE>,
+}
+
+unsafe impl Send for OutputState<'_> {}
+unsafe impl Sync for OutputState<'_> {}
+
+impl OutputState<'_> {
+    raw! {
+        pub fn as_raw(&self) -> *mut FMOD_OUTPUT_STATE {
+            self.raw
+        }
+    }
+
+    /// Number of interleaved channels produced by the mixer, as negotiated
+    /// in [`OutputDescription::init`].
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Read from the mixer into the provided buffer of interleaved samples.
+    ///
+    /// The buffer length must be a multiple of [`OutputState::channels`];
+    /// the mixer is advanced by `buffer.len() / channels` samples.
+    pub fn read_from_mixer(&self, buffer: &mut [f32]) -> Result {
+        if self.channels == 0 || buffer.len() % self.channels != 0 {
+            return Err(Error::InvalidParam);
+        }
+        let length = buffer.len() / self.channels;
+        unsafe {
+            let read = (*self.raw).readfrommixer.ok_or(Error::InvalidParam)?;
+            Error::from_raw(read(self.raw, buffer.as_mut_ptr().cast(), length as u32))
+        }
+    }
+
+    /// Request the output to shut down and restart.
+    ///
+    /// If this is issued, the output will not reset immediately, but on the
+    /// next update the output will first shut down with
+    /// [`OutputDescription::stop`] and [`OutputDescription::close`],
+    /// then be reinitialized.
+    pub fn request_reset(&self) -> Result {
+        unsafe {
+            let reset = (*self.raw).requestreset.ok_or(Error::InvalidParam)?;
+            Error::from_raw(reset(self.raw))
+        }
+    }
+}
+
+/// Callbacks to implement a custom output plugin.
+///
+/// The plugin is created by [`OutputDescription::init`] when the [`System`]
+/// initializes and is dropped by [`OutputDescription::close`]. Since the
+/// remaining callbacks may be invoked from both the thread calling
+/// [`System::update`] and FMOD's mixer thread, they take `&self`; use
+/// interior mutability for any state which changes during playback.
+///
+/// The callbacks are invoked from within FMOD and should not call back into
+/// the [`System`] which owns the output.
+pub trait OutputDescription: Sized + Send + Sync {
+    /// Version number of the plugin.
+    const VERSION: u32 = 1;
+
+    /// Method used to interact with the mixer.
+    const METHOD: OutputMethod = OutputMethod::MixDirect;
+
+    /// Initialization callback which is called when [`System::init`] is
+    /// called with this output selected.
+    ///
+    /// Adjust `config` to what the output supports before returning.
+    fn init(config: &mut OutputConfig) -> Result<Self>;
+
+    /// Callback for starting audio output, called once the mixer is ready.
+    fn start(&self, state: OutputState<'_>) -> Result {
+        let _ = state;
+        Ok(())
+    }
+
+    /// Callback for stopping audio output, called before
+    /// [`OutputDescription::close`].
+    fn stop(&self, state: OutputState<'_>) -> Result {
+        let _ = state;
+        Ok(())
+    }
+
+    /// Shut down callback which is called when the [`System`] is closed or
+    /// the output is reset. Default implemented to just drop the plugin.
+    fn close(self, state: OutputState<'_>) -> Result {
+        let _ = state;
+        Ok(())
+    }
+
+    /// Update callback, called once per [`System::update`].
+    ///
+    /// For [`OutputMethod::MixDirect`] outputs this is a convenient place to
+    /// call [`OutputState::read_from_mixer`].
+    fn update(&self, state: OutputState<'_>) -> Result {
+        let _ = state;
+        Ok(())
+    }
+
+    /// Mixer callback, called from the mixer thread to give the output a
+    /// chance to drive the mixer.
+    fn mixer(&self, state: OutputState<'_>) -> Result {
+        let _ = state;
+        Ok(())
+    }
+
+    /// Callback providing the low-level output device handle retrieved by
+    /// [`System::get_output_handle`].
+    fn get_handle(&self) -> *mut c_void {
+        ptr::null_mut()
+    }
+}
+
+struct OutputPlugin<O> {
+    channels: usize,
+    plugin: O,
+}
+
+pub(crate) fn output_description<O: OutputDescription>(
+    name: &'static CStr8,
+) -> FMOD_OUTPUT_DESCRIPTION {
+    FMOD_OUTPUT_DESCRIPTION {
+        apiversion: FMOD_OUTPUT_PLUGIN_VERSION,
+        name: name.as_ptr() as _,
+        version: O::VERSION,
+        method: O::METHOD.into_raw(),
+        getnumdrivers: None,
+        getdriverinfo: None,
+        init: Some(output_init::<O>),
+        start: Some(output_start::<O>),
+        stop: Some(output_stop::<O>),
+        close: Some(output_close::<O>),
+        update: Some(output_update::<O>),
+        gethandle: Some(output_get_handle::<O>),
+        mixer: Some(output_mixer::<O>),
+        object3dgetinfo: None,
+        object3dalloc: None,
+        object3dfree: None,
+        object3dupdate: None,
+        openport: None,
+        closeport: None,
+        devicelistchanged: None,
+    }
+}
+
+unsafe fn output_plugin<'a, O>(
+    state: *mut FMOD_OUTPUT_STATE,
+) -> Result<(&'a OutputPlugin<O>, OutputState<'a>)> {
+    let plugin = (*state).plugindata.cast::<OutputPlugin<O>>();
+    if plugin.is_null() {
+        return Err(Error::Uninitialized);
+    }
+    let plugin = &*plugin;
+    let state = OutputState {
+        raw: state,
+        channels: plugin.channels,
+        _phantom: PhantomData,
+    };
+    Ok((plugin, state))
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "system" fn output_init<O: OutputDescription>(
+    state: *mut FMOD_OUTPUT_STATE,
+    selecteddriver: i32,
+    flags: FMOD_INITFLAGS,
+    outputrate: *mut i32,
+    speakermode: *mut FMOD_SPEAKERMODE,
+    speakermodechannels: *mut i32,
+    outputformat: *mut FMOD_SOUND_FORMAT,
+    dspbufferlength: i32,
+    dspnumbuffers: *mut i32,
+    dspnumadditionalbuffers: *mut i32,
+    _extradriverdata: *mut c_void,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        let mut config = OutputConfig {
+            driver: selecteddriver,
+            flags: InitFlags::from_raw(flags),
+            rate: *outputrate,
+            speaker_mode: SpeakerMode::try_from_raw(*speakermode)?,
+            channels: *speakermodechannels,
+            dsp_buffer_length: dspbufferlength as u32,
+            dsp_num_buffers: *dspnumbuffers,
+            dsp_num_additional_buffers: *dspnumadditionalbuffers,
+        };
+        let plugin = O::init(&mut config)?;
+        let channels = match config.speaker_mode.channel_count() {
+            Some(channels) => Some(channels),
+            None => usize::try_from(config.channels).ok().filter(|&n| n != 0),
+        };
+        let Some(channels) = channels else {
+            whoops!(no_panic: "output plugin chose {} raw channels", config.channels);
+            let state = OutputState {
+                raw: state,
+                channels: 0,
+                _phantom: PhantomData,
+            };
+            // the plugin was created, so it is closed even though init fails
+            if let Err(error) = catch_user_unwind(|| plugin.close(state)) {
+                whoops!(no_panic: "error closing output plugin: {error}");
+            }
+            yeet!(Error::InvalidParam);
+        };
+
+        *outputrate = config.rate;
+        *speakermode = config.speaker_mode.into_raw();
+        *speakermodechannels = channels as i32;
+        *outputformat = SoundFormat::PcmFloat.into_raw();
+        *dspnumbuffers = config.dsp_num_buffers;
+        *dspnumadditionalbuffers = config.dsp_num_additional_buffers;
+        (*state).plugindata = Box::into_raw(Box::new(OutputPlugin { channels, plugin })).cast();
+        Ok(())
+    })
+    .into_raw()
+}
+
+unsafe extern "system" fn output_start<O: OutputDescription>(
+    state: *mut FMOD_OUTPUT_STATE,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        let (plugin, state) = output_plugin::<O>(state)?;
+        plugin.plugin.start(state)
+    })
+    .into_raw()
+}
+
+unsafe extern "system" fn output_stop<O: OutputDescription>(
+    state: *mut FMOD_OUTPUT_STATE,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        let (plugin, state) = output_plugin::<O>(state)?;
+        plugin.plugin.stop(state)
+    })
+    .into_raw()
+}
+
+unsafe extern "system" fn output_close<O: OutputDescription>(
+    state: *mut FMOD_OUTPUT_STATE,
+) -> FMOD_RESULT {
+    let plugin = (*state).plugindata.cast::<OutputPlugin<O>>();
+    if plugin.is_null() {
+        return FMOD_OK;
+    }
+    (*state).plugindata = ptr::null_mut();
+    let plugin = Box::from_raw(plugin);
+    let state = OutputState {
+        raw: state,
+        channels: plugin.channels,
+        _phantom: PhantomData,
+    };
+    catch_user_unwind(|| plugin.plugin.close(state)).into_raw()
+}
+
+unsafe extern "system" fn output_update<O: OutputDescription>(
+    state: *mut FMOD_OUTPUT_STATE,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        let (plugin, state) = output_plugin::<O>(state)?;
+        plugin.plugin.update(state)
+    })
+    .into_raw()
+}
+
+unsafe extern "system" fn output_mixer<O: OutputDescription>(
+    state: *mut FMOD_OUTPUT_STATE,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        let (plugin, state) = output_plugin::<O>(state)?;
+        plugin.plugin.mixer(state)
+    })
+    .into_raw()
+}
+
+unsafe extern "system" fn output_get_handle<O: OutputDescription>(
+    state: *mut FMOD_OUTPUT_STATE,
+    handle: *mut *mut c_void,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        let (plugin, _) = output_plugin::<O>(state)?;
+        *handle = plugin.plugin.get_handle();
+        Ok(())
+    })
+    .into_raw()
+}
@@ -17,6 +17,7 @@ impl Dsp {
         input: &Dsp,
         kind: DspConnectionType,
     ) -> Result<NonNull<DspConnection>> {
+        debug_assert_same_system!(self, input);
         let mut connection = ptr::null_mut();
         ffi!(FMOD_DSP_AddInput(
             self.as_raw(),
@@ -4,9 +4,50 @@ use {
     std::{borrow::Cow, ffi::c_void, ptr, time::Duration},
 };
 
+#[cfg(feature = "debug-introspection")]
+impl Dsp {
+    pub(crate) fn fmt_introspect(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        name: std::fmt::Arguments<'_>,
+    ) -> std::fmt::Result {
+        let snapshot = (|| -> Result<_> { Ok((self.get_type()?, self.get_bypass()?)) })();
+        match snapshot {
+            Ok((kind, bypass)) => f
+                .debug_struct(&name.to_string())
+                .field("type", &kind)
+                .field("bypass", &bypass)
+                .finish(),
+            Err(error) => crate::utils::fmt_introspect_error(f, name, error),
+        }
+    }
+}
+
 /// # General.
 impl Dsp {
-    // TODO: show_config_dialog
+    /// Display or hide a DSP unit configuration dialog box inside the target
+    /// window.
+    ///
+    /// Some DSP plugins (especially VST plugins) use dialog boxes to display
+    /// graphical user interfaces for modifying their parameters, rather than
+    /// using the other method of enumerating their parameters and setting them
+    /// with [`Dsp::set_parameter`].
+    ///
+    /// To find out what size window to create to store the configuration
+    /// screen, use [`Dsp::get_info`] where you can get the width and height.
+    ///
+    /// # Safety
+    ///
+    /// `hwnd` must be a valid handle to a window the dialog can be displayed
+    /// inside of. On Windows, this is an `HWND`.
+    pub unsafe fn show_config_dialog(&self, hwnd: *mut c_void, show: bool) -> Result {
+        ffi!(FMOD_DSP_ShowConfigDialog(
+            self.as_raw(),
+            hwnd,
+            show as FMOD_BOOL,
+        ))?;
+        Ok(())
+    }
 
     /// Reset a DSPs internal state ready for new input signal.
     ///
@@ -1,6 +1,11 @@
 use {
     fmod::{effect::*, raw::*, *},
-    std::borrow::Borrow,
+    std::{
+        borrow::Borrow,
+        ffi::{c_char, CStr},
+        mem::size_of,
+        ptr,
+    },
 };
 
 /// # Parameters.
@@ -23,13 +28,19 @@ impl Dsp {
         index: impl DspParam<T>,
         value: impl Borrow<T>,
     ) -> Result {
+        self.check_param_kind(&index)?;
         T::set_dsp_parameter(self, index.into(), value.borrow())
     }
 
-    // /// Retrieves a DSP parameter by index.
-    // pub fn get_parameter<T: DspParamType>(&self, index: impl DspParam<T>) -> Result<T> {
-    //     T::get_dsp_parameter(self, index.into())
-    // }
+    /// Retrieves a DSP parameter by index.
+    ///
+    /// Data parameters must be exactly the size of `T`, otherwise
+    /// [`Error::InvalidParam`] is returned. They are copied while the DSP
+    /// engine is locked, as described in [`Dsp::get_parameter_data`].
+    pub fn get_parameter<T: DspParamType>(&self, index: impl DspParam<T>) -> Result<T> {
+        self.check_param_kind(&index)?;
+        T::get_dsp_parameter(self, index.into())
+    }
 
     /// Retrieves the string representation of a DSP parameter by index.
     pub fn get_parameter_string<T: ?Sized + DspParamType>(
@@ -38,11 +49,225 @@ impl Dsp {
         string: &mut String,
     ) -> Result {
         string.clear();
+        self.check_param_kind(&index)?;
         let mut bytes = [0; FMOD_DSP_GETPARAM_VALUESTR_LENGTH as usize];
         *string += T::get_dsp_parameter_string(self, index.into(), &mut bytes)?;
         Ok(())
     }
 
-    // set_data_parameter, get_data_parameter, get_data_parameter_string
-    // get_parameter_info
+    /// Retrieve information about a specified parameter.
+    ///
+    /// Use [`Dsp::get_num_parameters`] to find the number of parameters.
+    pub fn get_parameter_info(&self, index: i32) -> Result<DspParameterInfo> {
+        let mut desc = ptr::null_mut();
+        ffi!(FMOD_DSP_GetParameterInfo(self.as_raw(), index, &mut desc))?;
+        Ok(unsafe { DspParameterInfo::from_raw(&*desc) })
+    }
+
+    /// Sets a data DSP parameter by index, from its raw bytes.
+    ///
+    /// For typed data, prefer [`Dsp::set_parameter`].
+    pub fn set_parameter_data<T: ?Sized + DspParamData>(
+        &self,
+        index: impl DspParam<T>,
+        data: &[u8],
+    ) -> Result {
+        self.check_param_kind(&index)?;
+        <[u8]>::set_dsp_parameter(self, index.into(), data)
+    }
+
+    /// Retrieves a copy of a data DSP parameter by index, as raw bytes.
+    ///
+    /// FMOD returns a pointer to the data owned by the DSP, which is copied
+    /// while the DSP engine is locked, so the mixer can't update it
+    /// meanwhile. This waits for the current mix to finish, unless this
+    /// thread already holds a [`DspLock`]. Don't call it from the mixer
+    /// thread, such as in a DSP callback, as that would wait for itself; use
+    /// [`Dsp::get_parameter_data_unlocked`] there.
+    ///
+    /// For data of a known type, prefer [`Dsp::get_parameter`].
+    pub fn get_parameter_data<T: ?Sized + DspParamData>(
+        &self,
+        index: impl DspParam<T>,
+    ) -> Result<Vec<u8>> {
+        self.check_param_kind(&index)?;
+        self.with_dsp_locked(|| {
+            let (data, length) = self.parameter_data(index.into())?;
+            Ok(unsafe { copy_parameter_data(data, length) })
+        })
+    }
+
+    /// Retrieves a copy of a data DSP parameter by index, as raw bytes,
+    /// without locking the DSP engine.
+    ///
+    /// Unlike [`Dsp::get_parameter_data`], this never waits for the mixer.
+    /// If the mixer updates the data while it's being copied, as it does for
+    /// e.g. FFT spectrum data, the copy may mix old and new values.
+    pub fn get_parameter_data_unlocked<T: ?Sized + DspParamData>(
+        &self,
+        index: impl DspParam<T>,
+    ) -> Result<Vec<u8>> {
+        self.check_param_kind(&index)?;
+        let (data, length) = self.parameter_data(index.into())?;
+        Ok(unsafe { copy_parameter_data(data, length) })
+    }
+
+    /// Runs `f` while the DSP engine is locked, taking the lock unless this
+    /// thread already holds it.
+    pub(crate) fn with_dsp_locked<R>(&self, f: impl FnOnce() -> Result<R>) -> Result<R> {
+        let system = self.get_system_object()?;
+        let lock = DspLock::unless_held(system)?;
+        let result = f();
+        if let Some(lock) = lock {
+            lock.unlock()?;
+        }
+        result
+    }
+
+    /// Retrieves the pointer to and length of a data DSP parameter.
+    pub(crate) fn parameter_data(&self, index: i32) -> Result<(*const u8, usize)> {
+        let mut value = ptr::null_mut();
+        let mut length = 0;
+        ffi!(FMOD_DSP_GetParameterData(
+            self.as_raw(),
+            index,
+            &mut value,
+            &mut length,
+            ptr::null_mut(),
+            0,
+        ))?;
+        match value.is_null() {
+            true => Ok((ptr::null(), 0)),
+            false => Ok((value.cast_const().cast(), ix!(length))),
+        }
+    }
+
+    /// In debug builds, check that a typed parameter is for this DSP's type.
+    fn check_param_kind<T: ?Sized + DspParamType, P: DspParam<T>>(&self, _: &P) -> Result {
+        if cfg!(debug_assertions) && P::KIND != DspType::Unknown {
+            let kind = self.get_type()?;
+            if kind != P::KIND {
+                whoops!(no_panic: "{:?} parameter used on {kind:?} DSP", P::KIND);
+                yeet!(Error::DspType);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copies `length` bytes of data parameter from `data`, which may be null
+/// if `length` is 0.
+///
+/// # Safety
+///
+/// `data` must be valid to read `length` bytes from.
+unsafe fn copy_parameter_data(data: *const u8, length: usize) -> Vec<u8> {
+    let mut copy = Vec::with_capacity(length);
+    if length != 0 {
+        // copy from the pointer, rather than through a slice, as the mixer
+        // may write to the data concurrently
+        ptr::copy_nonoverlapping(data, copy.as_mut_ptr(), length);
+        copy.set_len(length);
+    }
+    copy
+}
+
+/// Reads a data parameter of `length` bytes from `data` as a `T`, which must
+/// be exactly its size.
+///
+/// # Safety
+///
+/// `data` must be valid to read `length` bytes from, and any `size_of::<T>()`
+/// bytes must be a valid `T`.
+pub(crate) unsafe fn read_parameter_data<T>(data: *const u8, length: usize) -> Result<T> {
+    match data.is_null() || length != size_of::<T>() {
+        true => {
+            whoops!(no_panic: "data parameter has length {length}, expected {}", size_of::<T>());
+            Err(Error::InvalidParam)
+        },
+        false => Ok(data.cast::<T>().read_unaligned()),
+    }
+}
+
+/// Information about a DSP parameter, from [`Dsp::get_parameter_info`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DspParameterInfo {
+    /// Parameter name.
+    pub name: String,
+    /// Unit type label.
+    pub label: String,
+    /// Description of the parameter.
+    pub description: String,
+    /// Type and range of the parameter.
+    pub kind: DspParameterKind,
+}
+
+impl DspParameterInfo {
+    raw! {
+        pub unsafe fn from_raw(desc: &FMOD_DSP_PARAMETER_DESC) -> Self {
+            let string = |ptr: *const c_char| match ptr.is_null() {
+                true => String::new(),
+                false => CStr::from_ptr(ptr).to_string_lossy().into_owned(),
+            };
+            let kind = match desc.r#type {
+                FMOD_DSP_PARAMETER_TYPE_FLOAT => DspParameterKind::Float {
+                    min: desc.payload.floatdesc.min,
+                    max: desc.payload.floatdesc.max,
+                    default: desc.payload.floatdesc.defaultval,
+                },
+                FMOD_DSP_PARAMETER_TYPE_INT => DspParameterKind::Int {
+                    min: desc.payload.intdesc.min,
+                    max: desc.payload.intdesc.max,
+                    default: desc.payload.intdesc.defaultval,
+                },
+                FMOD_DSP_PARAMETER_TYPE_BOOL => DspParameterKind::Bool {
+                    default: desc.payload.booldesc.defaultval != 0,
+                },
+                _ => DspParameterKind::Data {
+                    data_type: desc.payload.datadesc.datatype,
+                },
+            };
+            DspParameterInfo {
+                name: string(desc.name.as_ptr()),
+                label: string(desc.label.as_ptr()),
+                description: string(desc.description),
+                kind,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_of_exactly_the_size_is_read() {
+        let data = [1u8, 2, 3, 4];
+        let read = unsafe { read_parameter_data::<[u8; 4]>(data.as_ptr(), data.len()) };
+        assert_eq!(read, Ok([1, 2, 3, 4]));
+        let read = unsafe { read_parameter_data::<u32>(data.as_ptr(), data.len()) };
+        assert_eq!(read, Ok(u32::from_ne_bytes(data)));
+    }
+
+    #[test]
+    fn data_of_another_size_is_rejected() {
+        let data = [0u8; 16];
+        // smaller data must not be read past its end
+        let read = unsafe { read_parameter_data::<[u8; 8]>(data.as_ptr(), 4) };
+        assert_eq!(read, Err(Error::InvalidParam));
+        // larger data isn't silently truncated
+        let read = unsafe { read_parameter_data::<[u8; 8]>(data.as_ptr(), 16) };
+        assert_eq!(read, Err(Error::InvalidParam));
+        let read = unsafe { read_parameter_data::<[u8; 8]>(ptr::null(), 8) };
+        assert_eq!(read, Err(Error::InvalidParam));
+    }
+
+    #[test]
+    fn data_is_copied_exactly() {
+        let data = [5u8, 6, 7];
+        assert_eq!(unsafe { copy_parameter_data(data.as_ptr(), 3) }, data);
+        assert_eq!(unsafe { copy_parameter_data(data.as_ptr(), 2) }, data[..2]);
+        assert!(unsafe { copy_parameter_data(ptr::null(), 0) }.is_empty());
+    }
 }
@@ -0,0 +1,493 @@
+use {
+    crate::utils::{catch_user_unwind, catch_user_unwind_as},
+    fmod::{raw::*, *},
+    std::{ffi::c_char, mem, ptr, slice},
+};
+
+/// Callbacks to implement a custom DSP effect.
+///
+/// Register the effect with [`System::register_dsp`] and create instances of
+/// it with [`System::create_dsp_by_plugin`]. Parameters described by
+/// [`DspDescription::PARAMETERS`] can then be used with [`Dsp::set_parameter`]
+/// like those of the built in effects, by index.
+///
+/// The effect has a single input and a single output, and processes
+/// interleaved `f32` samples. Since processing happens on the mixer thread
+/// while parameters are set from the thread calling [`Dsp::set_parameter`],
+/// all callbacks after [`DspDescription::create`] take `&self`; use atomics
+/// or other interior mutability for any state which changes during
+/// playback.
+pub trait DspDescription: Sized + Send + Sync {
+    /// Plugin writer's version number.
+    const VERSION: u32 = 1;
+
+    /// Parameters exposed by the effect.
+    const PARAMETERS: &'static [DspParameterDesc] = &[];
+
+    /// Called on [`System::create_dsp_by_plugin`].
+    fn create() -> Result<Self>;
+
+    /// Called when the DSP is released. Default implemented to just drop the
+    /// effect.
+    fn release(self) -> Result {
+        Ok(())
+    }
+
+    /// Called when the DSP is first added to the graph or playback is reset,
+    /// and should clear any history such as filter state.
+    fn reset(&self) -> Result {
+        Ok(())
+    }
+
+    /// Processing callback.
+    ///
+    /// `input` and `output` hold the same number of sample frames of
+    /// `channels` interleaved samples each.
+    ///
+    /// Return [`Error::DspSilence`] to output silence. If this panics, the
+    /// output is also silenced, as it may have been partially written.
+    fn read(&self, input: &[f32], output: &mut [f32], channels: usize) -> Result;
+
+    /// Sets a [`DspParameterKind::Float`] parameter by index.
+    fn set_parameter_float(&self, index: i32, value: f32) -> Result {
+        let _ = (index, value);
+        Err(Error::InvalidParam)
+    }
+
+    /// Retrieves a [`DspParameterKind::Float`] parameter by index.
+    fn get_parameter_float(&self, index: i32) -> Result<f32> {
+        let _ = index;
+        Err(Error::InvalidParam)
+    }
+
+    /// Sets a [`DspParameterKind::Int`] parameter by index.
+    fn set_parameter_int(&self, index: i32, value: i32) -> Result {
+        let _ = (index, value);
+        Err(Error::InvalidParam)
+    }
+
+    /// Retrieves a [`DspParameterKind::Int`] parameter by index.
+    fn get_parameter_int(&self, index: i32) -> Result<i32> {
+        let _ = index;
+        Err(Error::InvalidParam)
+    }
+
+    /// Sets a [`DspParameterKind::Bool`] parameter by index.
+    fn set_parameter_bool(&self, index: i32, value: bool) -> Result {
+        let _ = (index, value);
+        Err(Error::InvalidParam)
+    }
+
+    /// Retrieves a [`DspParameterKind::Bool`] parameter by index.
+    fn get_parameter_bool(&self, index: i32) -> Result<bool> {
+        let _ = index;
+        Err(Error::InvalidParam)
+    }
+}
+
+/// Description of a parameter exposed by a [`DspDescription`].
+#[derive(Debug, Clone, Copy)]
+pub struct DspParameterDesc {
+    /// Parameter name, truncated to 15 bytes.
+    pub name: &'static CStr8,
+    /// Unit type label, truncated to 15 bytes.
+    pub label: &'static CStr8,
+    /// Description of the parameter.
+    pub description: &'static CStr8,
+    /// Type and range of the parameter.
+    pub kind: DspParameterKind,
+}
+
+/// Type and range of a [`DspParameterDesc`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DspParameterKind {
+    /// A float parameter with a linear mapping.
+    #[allow(missing_docs)]
+    Float { min: f32, max: f32, default: f32 },
+    /// An integer parameter.
+    #[allow(missing_docs)]
+    Int { min: i32, max: i32, default: i32 },
+    /// A boolean parameter.
+    #[allow(missing_docs)]
+    Bool { default: bool },
+    /// A data parameter. Negative types are predefined by FMOD, while
+    /// positive types are user defined.
+    ///
+    /// [`DspDescription`] effects cannot expose data parameters;
+    /// [`System::register_dsp`] rejects them with [`Error::InvalidParam`].
+    /// This kind is only reported for the parameters of other effects.
+    #[allow(missing_docs)]
+    Data { data_type: i32 },
+}
+
+fn copy_truncated<const N: usize>(src: &CStr8) -> [c_char; N] {
+    let mut dst = [0; N];
+    for (dst, &src) in dst[..N - 1].iter_mut().zip(src.as_bytes()) {
+        *dst = src as c_char;
+    }
+    dst
+}
+
+impl DspParameterDesc {
+    fn into_raw(self) -> Result<FMOD_DSP_PARAMETER_DESC> {
+        let (kind, payload) = match self.kind {
+            DspParameterKind::Float { min, max, default } => (
+                FMOD_DSP_PARAMETER_TYPE_FLOAT,
+                FMOD_DSP_PARAMETER_DESC_PAYLOAD {
+                    floatdesc: FMOD_DSP_PARAMETER_DESC_FLOAT {
+                        min,
+                        max,
+                        defaultval: default,
+                        mapping: FMOD_DSP_PARAMETER_FLOAT_MAPPING {
+                            r#type: FMOD_DSP_PARAMETER_FLOAT_MAPPING_TYPE_LINEAR,
+                            piecewiselinearmapping:
+                                FMOD_DSP_PARAMETER_FLOAT_MAPPING_PIECEWISE_LINEAR {
+                                    numpoints: 0,
+                                    pointparamvalues: ptr::null_mut(),
+                                    pointpositions: ptr::null_mut(),
+                                },
+                        },
+                    },
+                },
+            ),
+            DspParameterKind::Int { min, max, default } => (
+                FMOD_DSP_PARAMETER_TYPE_INT,
+                FMOD_DSP_PARAMETER_DESC_PAYLOAD {
+                    intdesc: FMOD_DSP_PARAMETER_DESC_INT {
+                        min,
+                        max,
+                        defaultval: default,
+                        goestoinf: 0,
+                        valuenames: ptr::null(),
+                    },
+                },
+            ),
+            DspParameterKind::Bool { default } => (
+                FMOD_DSP_PARAMETER_TYPE_BOOL,
+                FMOD_DSP_PARAMETER_DESC_PAYLOAD {
+                    booldesc: FMOD_DSP_PARAMETER_DESC_BOOL {
+                        defaultval: default as FMOD_BOOL,
+                        valuenames: ptr::null(),
+                    },
+                },
+            ),
+            DspParameterKind::Data { .. } => {
+                // there are no get/set callbacks to serve data parameters
+                whoops!(no_panic: "DspDescription parameter {:?} is a data parameter, which is unsupported", self.name);
+                yeet!(Error::InvalidParam);
+            },
+        };
+        Ok(FMOD_DSP_PARAMETER_DESC {
+            r#type: kind,
+            name: copy_truncated(self.name),
+            label: copy_truncated(self.label),
+            description: self.description.as_ptr() as _,
+            payload,
+        })
+    }
+}
+
+pub(crate) fn dsp_description<D: DspDescription>(name: &CStr8) -> Result<FMOD_DSP_DESCRIPTION> {
+    // FMOD refers to the parameter descriptions for as long as the plugin is
+    // registered, so they live as long as the (leaked) description does.
+    let params: &'static mut [FMOD_DSP_PARAMETER_DESC] = D::PARAMETERS
+        .iter()
+        .map(|param| param.into_raw())
+        .collect::<Result<Vec<_>>>()?
+        .leak();
+    let param_ptrs: &'static mut [*mut FMOD_DSP_PARAMETER_DESC] = params
+        .iter_mut()
+        .map(|param| param as *mut _)
+        .collect::<Vec<_>>()
+        .leak();
+
+    let mut description: FMOD_DSP_DESCRIPTION = unsafe { mem::zeroed() };
+    description.pluginsdkversion = FMOD_PLUGIN_SDK_VERSION;
+    description.name = copy_truncated(name);
+    description.version = D::VERSION;
+    description.numinputbuffers = 1;
+    description.numoutputbuffers = 1;
+    description.create = Some(dsp_create::<D>);
+    description.release = Some(dsp_release::<D>);
+    description.reset = Some(dsp_reset::<D>);
+    description.read = Some(dsp_read::<D>);
+    description.numparameters = param_ptrs.len() as i32;
+    description.paramdesc = param_ptrs.as_mut_ptr();
+    description.setparameterfloat = Some(dsp_set_float::<D>);
+    description.setparameterint = Some(dsp_set_int::<D>);
+    description.setparameterbool = Some(dsp_set_bool::<D>);
+    description.getparameterfloat = Some(dsp_get_float::<D>);
+    description.getparameterint = Some(dsp_get_int::<D>);
+    description.getparameterbool = Some(dsp_get_bool::<D>);
+    Ok(description)
+}
+
+unsafe fn dsp_plugin<'a, D>(state: *mut FMOD_DSP_STATE) -> Result<&'a D> {
+    let plugin = (*state).plugindata.cast::<D>();
+    if plugin.is_null() {
+        return Err(Error::Uninitialized);
+    }
+    Ok(&*plugin)
+}
+
+unsafe extern "system" fn dsp_create<D: DspDescription>(state: *mut FMOD_DSP_STATE) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        let plugin = D::create()?;
+        (*state).plugindata = Box::into_raw(Box::new(plugin)).cast();
+        Ok(())
+    })
+    .into_raw()
+}
+
+unsafe extern "system" fn dsp_release<D: DspDescription>(
+    state: *mut FMOD_DSP_STATE,
+) -> FMOD_RESULT {
+    let plugin = (*state).plugindata.cast::<D>();
+    if plugin.is_null() {
+        return FMOD_OK;
+    }
+    (*state).plugindata = ptr::null_mut();
+    let plugin = Box::from_raw(plugin);
+    catch_user_unwind(|| plugin.release()).into_raw()
+}
+
+unsafe extern "system" fn dsp_reset<D: DspDescription>(state: *mut FMOD_DSP_STATE) -> FMOD_RESULT {
+    catch_user_unwind(|| dsp_plugin::<D>(state)?.reset()).into_raw()
+}
+
+unsafe extern "system" fn dsp_read<D: DspDescription>(
+    state: *mut FMOD_DSP_STATE,
+    inbuffer: *mut f32,
+    outbuffer: *mut f32,
+    length: u32,
+    inchannels: i32,
+    outchannels: *mut i32,
+) -> FMOD_RESULT {
+    // FMOD only accepts DONTPROCESS from the should-process callback, which
+    // isn't used; from read, SILENCE is how to report that there's no output
+    let result = catch_user_unwind_as(Error::DspSilence, || {
+        let plugin = dsp_plugin::<D>(state)?;
+        let channels = ix!(inchannels);
+        let len = ix!(length) * channels;
+        if inbuffer == outbuffer {
+            whoops!(no_panic: "DSP read callback was given aliasing input and output buffers");
+            return Err(Error::Internal);
+        }
+        let input = slice::from_raw_parts(inbuffer, len);
+        let output = slice::from_raw_parts_mut(outbuffer, len);
+        *outchannels = inchannels;
+        plugin.read(input, output, channels)
+    });
+    if result == Err(Error::DspSilence) && inbuffer != outbuffer {
+        // silent, either as requested or because the plugin panicked partway
+        // through writing the output
+        ptr::write_bytes(outbuffer, 0, ix!(length) * ix!(inchannels));
+        *outchannels = inchannels;
+    }
+    result.into_raw()
+}
+
+unsafe extern "system" fn dsp_set_float<D: DspDescription>(
+    state: *mut FMOD_DSP_STATE,
+    index: i32,
+    value: f32,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| dsp_plugin::<D>(state)?.set_parameter_float(index, value)).into_raw()
+}
+
+unsafe extern "system" fn dsp_set_int<D: DspDescription>(
+    state: *mut FMOD_DSP_STATE,
+    index: i32,
+    value: i32,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| dsp_plugin::<D>(state)?.set_parameter_int(index, value)).into_raw()
+}
+
+unsafe extern "system" fn dsp_set_bool<D: DspDescription>(
+    state: *mut FMOD_DSP_STATE,
+    index: i32,
+    value: FMOD_BOOL,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| dsp_plugin::<D>(state)?.set_parameter_bool(index, value != 0)).into_raw()
+}
+
+unsafe extern "system" fn dsp_get_float<D: DspDescription>(
+    state: *mut FMOD_DSP_STATE,
+    index: i32,
+    value: *mut f32,
+    valuestr: *mut c_char,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        let got = dsp_plugin::<D>(state)?.get_parameter_float(index)?;
+        if !value.is_null() {
+            *value = got;
+        }
+        if !valuestr.is_null() {
+            *valuestr = 0;
+        }
+        Ok(())
+    })
+    .into_raw()
+}
+
+unsafe extern "system" fn dsp_get_int<D: DspDescription>(
+    state: *mut FMOD_DSP_STATE,
+    index: i32,
+    value: *mut i32,
+    valuestr: *mut c_char,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        let got = dsp_plugin::<D>(state)?.get_parameter_int(index)?;
+        if !value.is_null() {
+            *value = got;
+        }
+        if !valuestr.is_null() {
+            *valuestr = 0;
+        }
+        Ok(())
+    })
+    .into_raw()
+}
+
+unsafe extern "system" fn dsp_get_bool<D: DspDescription>(
+    state: *mut FMOD_DSP_STATE,
+    index: i32,
+    value: *mut FMOD_BOOL,
+    valuestr: *mut c_char,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        let got = dsp_plugin::<D>(state)?.get_parameter_bool(index)?;
+        if !value.is_null() {
+            *value = got as FMOD_BOOL;
+        }
+        if !valuestr.is_null() {
+            *valuestr = 0;
+        }
+        Ok(())
+    })
+    .into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::utils::expect_callback_panic};
+
+    fn param(kind: DspParameterKind) -> DspParameterDesc {
+        DspParameterDesc {
+            name: cstr8!("param"),
+            label: cstr8!(""),
+            description: cstr8!(""),
+            kind,
+        }
+    }
+
+    #[test]
+    fn value_parameters_are_described() {
+        let desc = param(DspParameterKind::Int {
+            min: 0,
+            max: 10,
+            default: 5,
+        });
+        let raw = desc.into_raw().unwrap();
+        assert_eq!(raw.r#type, FMOD_DSP_PARAMETER_TYPE_INT);
+        assert_eq!(unsafe { raw.payload.intdesc.defaultval }, 5);
+    }
+
+    #[test]
+    fn data_parameters_are_rejected() {
+        let desc = param(DspParameterKind::Data { data_type: 1 });
+        assert_eq!(desc.into_raw().err(), Some(Error::InvalidParam));
+    }
+
+    /// Panics in every callback.
+    struct Panicking;
+
+    impl DspDescription for Panicking {
+        fn create() -> Result<Self> {
+            panic!("create");
+        }
+
+        fn reset(&self) -> Result {
+            panic!("reset");
+        }
+
+        fn read(&self, _: &[f32], output: &mut [f32], _: usize) -> Result {
+            output[0] = 1.0;
+            panic!("read");
+        }
+
+        fn set_parameter_float(&self, _: i32, _: f32) -> Result {
+            panic!("set_parameter_float");
+        }
+
+        fn get_parameter_float(&self, _: i32) -> Result<f32> {
+            panic!("get_parameter_float");
+        }
+    }
+
+    fn state(plugin: Option<Panicking>) -> FMOD_DSP_STATE {
+        let mut state: FMOD_DSP_STATE = unsafe { mem::zeroed() };
+        if let Some(plugin) = plugin {
+            state.plugindata = Box::into_raw(Box::new(plugin)).cast();
+        }
+        state
+    }
+
+    #[test]
+    fn create_panic_is_an_error() {
+        let mut state = state(None);
+        let result = expect_callback_panic("create", || unsafe {
+            Error::from_raw(dsp_create::<Panicking>(&mut state))
+        });
+        assert_eq!(result, Err(Error::RustPanicked));
+        assert!(state.plugindata.is_null());
+    }
+
+    #[test]
+    fn read_panic_outputs_silence() {
+        let mut state = state(Some(Panicking));
+        let input = [0.5; 8];
+        let mut output = [0.5; 8];
+        let mut outchannels = 0;
+        let result = expect_callback_panic("read", || unsafe {
+            Error::from_raw(dsp_read::<Panicking>(
+                &mut state,
+                input.as_ptr().cast_mut(),
+                output.as_mut_ptr(),
+                4,
+                2,
+                &mut outchannels,
+            ))
+        });
+        assert_eq!(result, Err(Error::DspSilence));
+        assert_eq!(output, [0.0; 8]);
+        assert_eq!(outchannels, 2);
+        unsafe { drop(Box::from_raw(state.plugindata.cast::<Panicking>())) };
+    }
+
+    #[test]
+    fn parameter_and_reset_panics_are_errors() {
+        let mut state = state(Some(Panicking));
+        let result = expect_callback_panic("reset", || unsafe {
+            Error::from_raw(dsp_reset::<Panicking>(&mut state))
+        });
+        assert_eq!(result, Err(Error::RustPanicked));
+
+        let result = expect_callback_panic("set_parameter_float", || unsafe {
+            Error::from_raw(dsp_set_float::<Panicking>(&mut state, 0, 1.0))
+        });
+        assert_eq!(result, Err(Error::RustPanicked));
+
+        let mut value = 0.0;
+        let result = expect_callback_panic("get_parameter_float", || unsafe {
+            Error::from_raw(dsp_get_float::<Panicking>(
+                &mut state,
+                0,
+                &mut value,
+                ptr::null_mut(),
+            ))
+        });
+        assert_eq!(result, Err(Error::RustPanicked));
+        unsafe { drop(Box::from_raw(state.plugindata.cast::<Panicking>())) };
+    }
+}
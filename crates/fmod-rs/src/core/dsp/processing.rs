@@ -36,6 +36,10 @@ impl Dsp {
     ///
     /// If `bypass` is true, processing of this unit is skipped but it continues
     /// to process its inputs.
+    ///
+    /// This is the way to toggle an effect in a [Channel] or [ChannelGroup]
+    /// DSP chain without removing it, as deactivating it with
+    /// [`Dsp::set_active`] also silences everything feeding into it.
     pub fn set_bypass(&self, bypass: bool) -> Result {
         ffi!(FMOD_DSP_SetBypass(self.as_raw(), bypass as FMOD_BOOL))?;
         Ok(())
@@ -89,6 +93,9 @@ impl Dsp {
     /// the type of effect. A reverb or echo may take a longer time to go idle
     /// after it stops receiving a valid signal, compared to an effect with a
     /// shorter tail length like an EQ filter.
+    ///
+    /// Idle DSPs are skipped by the mixer, so this can be used to tell which
+    /// effects are currently consuming CPU.
     pub fn get_idle(&self) -> Result<bool> {
         let mut idle = 0;
         ffi!(FMOD_DSP_GetIdle(self.as_raw(), &mut idle))?;
@@ -25,7 +25,12 @@ use {
 
 /// A parameter index for a DSP effect.
 pub trait DspParam<T: ?Sized + DspParamType>: Into<i32> {
-    /// The type of DSP this parameter is for. Mostly informational.
+    /// The type of DSP this parameter is for.
+    ///
+    /// In debug builds, [`Dsp::set_parameter`] and
+    /// [`Dsp::get_parameter_string`] check this against [`Dsp::get_type`] and
+    /// return [`Error::DspType`] on mismatch. [`DspType::Unknown`] (used by
+    /// raw `i32` indices) is not checked.
     const KIND: DspType;
 }
 
@@ -37,14 +42,27 @@ impl<T: ?Sized + DspParamType> DspParam<T> for i32 {
 pub trait DspParamType {
     /// Sets a DSP parameter by index.
     fn set_dsp_parameter(dsp: &Dsp, index: i32, value: &Self) -> Result;
-    // /// Retrieves a DSP parameter by index.
-    // fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<Self>
-    // where
-    //     Self: Sized;
+    /// Retrieves a DSP parameter by index.
+    ///
+    /// By default this returns [`Error::Unsupported`], for types which can
+    /// only be set.
+    fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let _ = (dsp, index);
+        Err(Error::Unsupported)
+    }
     /// Retrieves the string representation of a DSP parameter by index.
     fn get_dsp_parameter_string<'a>(dsp: &Dsp, index: i32, bytes: &'a mut [u8]) -> Result<&'a str>;
 }
 
+/// A DSP parameter type which is passed as data.
+///
+/// Data parameters can also be accessed as raw bytes with
+/// [`Dsp::set_parameter_data`] and [`Dsp::get_parameter_data`].
+pub trait DspParamData: DspParamType {}
+
 impl DspParamType for bool {
     fn set_dsp_parameter(dsp: &Dsp, index: i32, value: &bool) -> Result {
         ffi!(FMOD_DSP_SetParameterBool(
@@ -55,17 +73,17 @@ impl DspParamType for bool {
         Ok(())
     }
 
-    // fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<bool> {
-    //     let mut value = FMOD_BOOL::default();
-    //     ffi!(FMOD_DSP_GetParameterBool(
-    //         dsp.as_raw(),
-    //         index,
-    //         &mut value,
-    //         ptr::null_mut(),
-    //         0,
-    //     ))?;
-    //     Ok(value != 0)
-    // }
+    fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<bool> {
+        let mut value = FMOD_BOOL::default();
+        ffi!(FMOD_DSP_GetParameterBool(
+            dsp.as_raw(),
+            index,
+            &mut value,
+            ptr::null_mut(),
+            0,
+        ))?;
+        Ok(value != 0)
+    }
 
     fn get_dsp_parameter_string<'a>(dsp: &Dsp, index: i32, bytes: &'a mut [u8]) -> Result<&'a str> {
         ffi!(FMOD_DSP_GetParameterBool(
@@ -92,10 +110,6 @@ macro_rules! data_array_param {
                 Ok(())
             }
 
-            // fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<Self> where Self: Sized {
-            //     unreachable!()
-            // }
-
             fn get_dsp_parameter_string<'a>(dsp: &Dsp, index: i32, bytes: &'a mut [u8]) -> Result<&'a str> {
                 ffi!(FMOD_DSP_GetParameterData(
                     dsp.as_raw(),
@@ -120,25 +134,12 @@ macro_rules! data_array_param {
                 Ok(())
             }
 
-            // fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<[$T; N]> {
-            //     let mut value = ptr::null_mut();
-            //     let mut length = 0;
-            //     ffi!(FMOD_DSP_GetParameterData(
-            //         dsp.as_raw(),
-            //         index,
-            //         &mut value,
-            //         &mut length,
-            //         ptr::null_mut(),
-            //         0,
-            //     ))?;
-
-            //     if length as usize >= size_of::<[$T; N]>() {
-            //         yeet!(Error::InvalidParam);
-            //     }
-
-            //     // FIXME: copying out could possibly race a set, what do?
-            //     Ok(unsafe { *value.cast() })
-            // }
+            fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<[$T; N]> {
+                dsp.with_dsp_locked(|| {
+                    let (data, length) = dsp.parameter_data(index)?;
+                    unsafe { read_parameter_data(data, length) }
+                })
+            }
 
             fn get_dsp_parameter_string<'a>(dsp: &Dsp, index: i32, bytes: &'a mut [u8]) -> Result<&'a str> {
                 ffi!(FMOD_DSP_GetParameterData(
@@ -152,6 +153,9 @@ macro_rules! data_array_param {
                 Ok(CStr8::from_utf8_until_nul(bytes).map_err(|_| Error::InvalidString)?)
             }
         }
+
+        impl DspParamData for [$T] {}
+        impl<const N: usize> DspParamData for [$T; N] {}
     )*}
 }
 
@@ -166,17 +170,17 @@ impl DspParamType for f32 {
         Ok(())
     }
 
-    // fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<f32> {
-    //     let mut value = f32::default();
-    //     ffi!(FMOD_DSP_GetParameterFloat(
-    //         dsp.as_raw(),
-    //         index,
-    //         &mut value,
-    //         ptr::null_mut(),
-    //         0,
-    //     ))?;
-    //     Ok(value)
-    // }
+    fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<f32> {
+        let mut value = f32::default();
+        ffi!(FMOD_DSP_GetParameterFloat(
+            dsp.as_raw(),
+            index,
+            &mut value,
+            ptr::null_mut(),
+            0,
+        ))?;
+        Ok(value)
+    }
 
     fn get_dsp_parameter_string<'a>(dsp: &Dsp, index: i32, bytes: &'a mut [u8]) -> Result<&'a str> {
         ffi!(FMOD_DSP_GetParameterFloat(
@@ -196,17 +200,17 @@ impl DspParamType for i32 {
         Ok(())
     }
 
-    // fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<i32> {
-    //     let mut value = i32::default();
-    //     ffi!(FMOD_DSP_GetParameterInt(
-    //         dsp.as_raw(),
-    //         index,
-    //         &mut value,
-    //         ptr::null_mut(),
-    //         0,
-    //     ))?;
-    //     Ok(value)
-    // }
+    fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<i32> {
+        let mut value = i32::default();
+        ffi!(FMOD_DSP_GetParameterInt(
+            dsp.as_raw(),
+            index,
+            &mut value,
+            ptr::null_mut(),
+            0,
+        ))?;
+        Ok(value)
+    }
 
     fn get_dsp_parameter_string<'a>(dsp: &Dsp, index: i32, bytes: &'a mut [u8]) -> Result<&'a str> {
         ffi!(FMOD_DSP_GetParameterInt(
@@ -276,6 +280,18 @@ pub mod ChannelMix {
         }
     }}
 
+    /// The parameter index of [`GainCh<n>`](GainCh), for channels only known
+    /// at runtime, or `None` if `n` is not in `0..32`.
+    ///
+    /// Use with the `i32` parameter index methods, e.g.
+    /// `dsp.set_parameter::<f32>(index, gain)`.
+    pub const fn gain_ch(n: i32) -> Option<i32> {
+        match n {
+            0..=31 => Some(FMOD_DSP_CHANNELMIX_GAIN_CH0 + n),
+            _ => None,
+        }
+    }
+
     /// Output channel for Input channel #N.
     #[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub struct OutputCh<const N: i32>;
@@ -292,6 +308,15 @@ pub mod ChannelMix {
         }
     }}
 
+    /// The parameter index of [`OutputCh<n>`](OutputCh), for channels only
+    /// known at runtime, or `None` if `n` is not in `0..32`.
+    pub const fn output_ch(n: i32) -> Option<i32> {
+        match n {
+            0..=31 => Some(FMOD_DSP_CHANNELMIX_OUTPUT_CH0 + n),
+            _ => None,
+        }
+    }
+
     fmod_enum! {
         #[derive(Default)]
         /// Channel Mix DSP outgrouping parameter types.
@@ -449,6 +474,8 @@ pub mod ConvolutionReverb {
         }
     }
 
+    impl DspParamData for ImpulseResponse {}
+
     // ImpulseResponse
 }
 
@@ -483,6 +510,15 @@ pub mod Delay {
             }
         }
     }}
+
+    /// The parameter index of [`DelayCh<n>`](DelayCh), for channels only
+    /// known at runtime, or `None` if `n` is not in `0..16`.
+    pub const fn delay_ch(n: i32) -> Option<i32> {
+        match n {
+            0..=15 => Some(FMOD_DSP_DELAY_CH0 + n),
+            _ => None,
+        }
+    }
 }
 
 /// Distortion DSP parameter types.
@@ -830,15 +866,35 @@ pub mod LoudnessMeter {
             /// 10th percentile loudness (towards lowest loudness). Uses short term loudness values (3 second averages).
             pub loudness_10th_percentile: f32,
             /// 95th percentile loudness (towards highest loudness). Uses short term loudness values (3 second averages).
-            pub loundness_95th_percentile: f32,
+            pub loudness_95th_percentile: f32,
             /// Array containing distribution of loudness values. Each array entry is a count of the momentary loudness values
             /// (400ms averages) evenly distributed along the range [-60, 6] excluding loudness values outside that range.
             #[default([0.0; 66])]
-            pub loundness_histogram: [f32; 66],
+            pub loudness_histogram: [f32; 66],
             /// Highest peak.
             pub max_true_peak: f32,
             /// Highest momentary loudness value (400ms averages).
-            pub max_momentary_loundness: f32,
+            pub max_momentary_loudness: f32,
+        }
+    }
+
+    impl MeterInfoType {
+        /// 95th percentile loudness; see [`loudness_95th_percentile`](Self::loudness_95th_percentile).
+        #[deprecated = "Misspelled; use the `loudness_95th_percentile` field."]
+        pub fn loundness_95th_percentile(&self) -> f32 {
+            self.loudness_95th_percentile
+        }
+
+        /// Distribution of loudness values; see [`loudness_histogram`](Self::loudness_histogram).
+        #[deprecated = "Misspelled; use the `loudness_histogram` field."]
+        pub fn loundness_histogram(&self) -> &[f32; 66] {
+            &self.loudness_histogram
+        }
+
+        /// Highest momentary loudness value; see [`max_momentary_loudness`](Self::max_momentary_loudness).
+        #[deprecated = "Misspelled; use the `max_momentary_loudness` field."]
+        pub fn max_momentary_loundness(&self) -> f32 {
+            self.max_momentary_loudness
         }
     }
 }
@@ -1085,22 +1141,35 @@ pub mod Oscillator {
         Noise = 5,
     }
 
+    impl Waveform {
+        /// Converts from the raw parameter value, or `None` if `raw` is not a
+        /// known waveform.
+        pub const fn from_raw(raw: i32) -> Option<Waveform> {
+            match raw {
+                0 => Some(Waveform::Sine),
+                1 => Some(Waveform::Square),
+                2 => Some(Waveform::SawUp),
+                3 => Some(Waveform::SawDown),
+                4 => Some(Waveform::Triangle),
+                5 => Some(Waveform::Noise),
+                _ => None,
+            }
+        }
+
+        /// Converts to the raw parameter value.
+        pub const fn into_raw(self) -> i32 {
+            self as i32
+        }
+    }
+
     impl DspParamType for Waveform {
         fn set_dsp_parameter(dsp: &Dsp, index: i32, value: &Self) -> Result {
-            dsp.set_parameter::<i32>(index, *value as i32)
+            dsp.set_parameter::<i32>(index, value.into_raw())
         }
 
-        // fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<Self> {
-        //     match dsp.get_parameter::<i32>(index)? {
-        //         0 => Ok(Self::Sine),
-        //         1 => Ok(Self::Square),
-        //         2 => Ok(Self::SawUp),
-        //         3 => Ok(Self::SawDown),
-        //         4 => Ok(Self::Triangle),
-        //         5 => Ok(Self::Noise),
-        //         _ => Err(Error::InvalidParam),
-        //     }
-        // }
+        fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<Self> {
+            Self::from_raw(dsp.get_parameter::<i32>(index)?).ok_or(Error::InvalidParam)
+        }
 
         fn get_dsp_parameter_string<'a>(
             dsp: &Dsp,
@@ -1509,17 +1578,19 @@ impl DspParamType for Sidechain {
         dsp.set_parameter::<[u8; size_of::<FMOD_BOOL>()]>(index, value.to_ne_bytes())
     }
 
-    // fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<Self> {
-    //     Ok(Self {
-    //         sidechainenable: FMOD_BOOL::from_ne_bytes(dsp.get_parameter(index)?) != 0,
-    //     })
-    // }
+    fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<Self> {
+        Ok(Self {
+            sidechainenable: FMOD_BOOL::from_ne_bytes(dsp.get_parameter(index)?) != 0,
+        })
+    }
 
     fn get_dsp_parameter_string<'a>(dsp: &Dsp, index: i32, bytes: &'a mut [u8]) -> Result<&'a str> {
         <[u8; size_of::<FMOD_BOOL>()]>::get_dsp_parameter_string(dsp, index, bytes)
     }
 }
 
+impl DspParamData for Sidechain {}
+
 fmod_struct! {
     /// Overall gain parameter data structure.
     ///
@@ -1,4 +1,7 @@
-use fmod::{raw::*, *};
+use {
+    fmod::{raw::*, *},
+    std::ops::Range,
+};
 
 /// # Polygons.
 impl Geometry {
@@ -79,3 +82,210 @@ pub struct PolygonAttributes {
     /// will cause occlusion.
     pub double_sided: bool,
 }
+
+/// A builder which accumulates polygons for a new [`Geometry`] object.
+///
+/// Unlike calling [`Geometry::add_polygon`] directly, the builder computes
+/// the maximum number of polygons and vertices for you, and validates each
+/// polygon before any are submitted to FMOD.
+///
+/// ```rust,ignore
+/// let wall = GeometryBuilder::new()
+///     .polygon(&[
+///         Vector(-1.0, 0.0, 0.0),
+///         Vector(-1.0, 1.0, 0.0),
+///         Vector(1.0, 1.0, 0.0),
+///         Vector(1.0, 0.0, 0.0),
+///     ])
+///     .occlusion(Occlusion { direct: 1.0, reverb: 1.0 })?
+///     .double_sided(true)?
+///     .build(&system)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GeometryBuilder {
+    polygons: Vec<(PolygonAttributes, Range<usize>)>,
+    vertices: Vec<Vector>,
+}
+
+impl GeometryBuilder {
+    /// Creates a new builder with no polygons.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a polygon.
+    ///
+    /// The polygon starts with default [`PolygonAttributes`]; use
+    /// [`GeometryBuilder::occlusion`] and [`GeometryBuilder::double_sided`]
+    /// to configure it.
+    ///
+    /// See [`Geometry::add_polygon`] for the requirements on the vertices.
+    pub fn polygon(mut self, vertices: &[Vector]) -> Self {
+        let start = self.vertices.len();
+        self.vertices.extend_from_slice(vertices);
+        let end = self.vertices.len();
+        self.polygons
+            .push((PolygonAttributes::default(), start..end));
+        self
+    }
+
+    /// Sets the occlusion factor of the most recently added polygon.
+    ///
+    /// Returns [`Error::InvalidParam`] if no polygon has been added yet.
+    pub fn occlusion(mut self, occlusion: Occlusion) -> Result<Self> {
+        self.last_polygon()?.occlusion = occlusion;
+        Ok(self)
+    }
+
+    /// Sets whether the most recently added polygon is double sided.
+    ///
+    /// Returns [`Error::InvalidParam`] if no polygon has been added yet.
+    pub fn double_sided(mut self, double_sided: bool) -> Result<Self> {
+        self.last_polygon()?.double_sided = double_sided;
+        Ok(self)
+    }
+
+    /// Adds an axis-aligned cuboid as six double sided quads.
+    ///
+    /// `extents` is the half size of the cuboid along each axis.
+    pub fn cuboid(self, center: Vector, extents: Vector, occlusion: Occlusion) -> Self {
+        let corner = |x: f32, y: f32, z: f32| Vector {
+            x: center.x + x * extents.x,
+            y: center.y + y * extents.y,
+            z: center.z + z * extents.z,
+        };
+        #[rustfmt::skip]
+        let faces = [
+            [corner(-1.0, -1.0, -1.0), corner(-1.0,  1.0, -1.0), corner( 1.0,  1.0, -1.0), corner( 1.0, -1.0, -1.0)],
+            [corner(-1.0, -1.0,  1.0), corner( 1.0, -1.0,  1.0), corner( 1.0,  1.0,  1.0), corner(-1.0,  1.0,  1.0)],
+            [corner(-1.0, -1.0, -1.0), corner(-1.0, -1.0,  1.0), corner(-1.0,  1.0,  1.0), corner(-1.0,  1.0, -1.0)],
+            [corner( 1.0, -1.0, -1.0), corner( 1.0,  1.0, -1.0), corner( 1.0,  1.0,  1.0), corner( 1.0, -1.0,  1.0)],
+            [corner(-1.0, -1.0, -1.0), corner( 1.0, -1.0, -1.0), corner( 1.0, -1.0,  1.0), corner(-1.0, -1.0,  1.0)],
+            [corner(-1.0,  1.0, -1.0), corner(-1.0,  1.0,  1.0), corner( 1.0,  1.0,  1.0), corner( 1.0,  1.0, -1.0)],
+        ];
+        faces.iter().fold(self, |mut this, face| {
+            this.vertices.extend_from_slice(face);
+            let end = this.vertices.len();
+            let attributes = PolygonAttributes {
+                occlusion,
+                double_sided: true,
+            };
+            this.polygons.push((attributes, end - face.len()..end));
+            this
+        })
+    }
+
+    /// Creates the geometry object and adds all of the accumulated polygons.
+    ///
+    /// Each polygon is validated to have at least 3 vertices which all lie in
+    /// the same plane. If any polygon is invalid, the reason is logged and
+    /// [`Error::InvalidParam`] is returned without creating the geometry.
+    pub fn build<'a>(&self, system: &'a System) -> Result<Handle<'a, Geometry>> {
+        for (index, (_, vertices)) in self.polygons.iter().enumerate() {
+            let vertices = &self.vertices[vertices.clone()];
+            if vertices.len() < 3 {
+                whoops!(no_panic: "GeometryBuilder polygon {index} has only {} vertices", vertices.len());
+                yeet!(Error::InvalidParam);
+            }
+            if !is_planar(vertices) {
+                whoops!(no_panic: "GeometryBuilder polygon {index} is not planar: {vertices:?}");
+                yeet!(Error::InvalidParam);
+            }
+        }
+
+        let max_polygons = i32::try_from(self.polygons.len()).map_err(|_| Error::InvalidParam)?;
+        let max_vertices = i32::try_from(self.vertices.len()).map_err(|_| Error::InvalidParam)?;
+        let geometry = system.create_geometry(max_polygons, max_vertices)?;
+        for (attributes, vertices) in &self.polygons {
+            geometry.add_polygon(*attributes, &self.vertices[vertices.clone()])?;
+        }
+        Ok(geometry)
+    }
+
+    fn last_polygon(&mut self) -> Result<&mut PolygonAttributes> {
+        match self.polygons.last_mut() {
+            Some((attributes, _)) => Ok(attributes),
+            None => {
+                whoops!(no_panic: "GeometryBuilder polygon attributes set before adding a polygon");
+                Err(Error::InvalidParam)
+            },
+        }
+    }
+}
+
+/// Checks that all vertices lie in the plane of the polygon, using Newell's
+/// method to compute a robust normal for the polygon.
+fn is_planar(vertices: &[Vector]) -> bool {
+    let mut normal = Vector::default();
+    let mut centroid = Vector::default();
+    let mut extent = 0.0_f32;
+    for (i, a) in vertices.iter().enumerate() {
+        let b = &vertices[(i + 1) % vertices.len()];
+        normal.x += (a.y - b.y) * (a.z + b.z);
+        normal.y += (a.z - b.z) * (a.x + b.x);
+        normal.z += (a.x - b.x) * (a.y + b.y);
+        centroid.x += a.x;
+        centroid.y += a.y;
+        centroid.z += a.z;
+        extent = extent.max(a.x.abs()).max(a.y.abs()).max(a.z.abs());
+    }
+
+    let length = (normal.x * normal.x + normal.y * normal.y + normal.z * normal.z).sqrt();
+    if length == 0.0 {
+        // zero area polygons are ignored by FMOD, so allow them
+        return true;
+    }
+
+    let n = vertices.len() as f32;
+    let tolerance = 1e-4 * extent.max(1.0);
+    vertices.iter().all(|v| {
+        let distance = (normal.x * (v.x - centroid.x / n)
+            + normal.y * (v.y - centroid.y / n)
+            + normal.z * (v.z - centroid.z / n))
+            / length;
+        distance.abs() <= tolerance
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_before_polygon_are_rejected() {
+        let builder = GeometryBuilder::new();
+        assert_eq!(
+            builder.clone().occlusion(Occlusion::NONE).err(),
+            Some(Error::InvalidParam),
+        );
+        assert_eq!(builder.double_sided(true).err(), Some(Error::InvalidParam));
+    }
+
+    #[test]
+    fn attributes_apply_to_last_polygon() {
+        let triangle = [
+            Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vector {
+                x: 1.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vector {
+                x: 0.0,
+                y: 1.0,
+                z: 0.0,
+            },
+        ];
+        let builder = GeometryBuilder::new()
+            .polygon(&triangle)
+            .polygon(&triangle)
+            .double_sided(true)
+            .unwrap();
+        assert!(!builder.polygons[0].0.double_sided);
+        assert!(builder.polygons[1].0.double_sided);
+    }
+}
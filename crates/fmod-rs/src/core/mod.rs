@@ -2,17 +2,21 @@
 
 /// Functionality not associated with a specific object.
 pub mod common {
+    pub mod codec;
     pub mod debug;
     pub mod file;
     mod general;
     pub mod memory;
     mod mix;
+    pub mod output;
     pub mod thread;
 
     pub use self::{general::*, mix::*};
 }
 
 pub mod effect;
+pub mod registry;
+pub mod util;
 
 fmod_class! {
     /// Management object from which all resources are created and played.
@@ -71,7 +75,7 @@ fmod_class! {
     /// Create with [`System::create_dsp`], [`System::create_dsp_by_type`] or [`System::create_dsp_by_plugin`].
     class Dsp = FMOD_DSP;
 
-    mod connections, parameters, channel, metering, processing, general, effect;
+    mod connections, parameters, channel, metering, processing, general, effect, plugin;
 }
 
 fmod_class! {
@@ -0,0 +1,164 @@
+//! Looking up channel groups, DSPs, and sound groups by name.
+//!
+//! Objects can be registered on a [`System`] by name, so that they can be
+//! looked up wherever the system is available instead of being passed
+//! around. This is a convenience of FMOD.rs, not part of the FMOD API.
+//!
+//! The system takes ownership of registered objects. They are kept in the
+//! system's userdata, and released when the system is
+//! [closed](System::close_scope) or released; names cannot be unregistered
+//! or reused before then. As the system owns them, registered objects don't
+//! count as [still alive](CloseError::ObjectsStillAlive) when closing it.
+//!
+//! ```rust,ignore
+//! let music = system.create_channel_group(cstr8!("music"))?;
+//! system.register_named_group("music", music)?;
+//! // elsewhere
+//! system.play_sound_in(&track, "music", false)?;
+//! ```
+
+use {
+    fmod::*,
+    std::{collections::HashMap, fmt},
+};
+
+/// Objects registered by name on a [`System`], kept in its FMOD.rs state.
+#[derive(Default)]
+pub(crate) struct Registry {
+    groups: HashMap<String, &'static ChannelGroup>,
+    dsps: HashMap<String, &'static Dsp>,
+    sound_groups: HashMap<String, &'static SoundGroup>,
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registry")
+            .field("groups", &self.groups.keys())
+            .field("dsps", &self.dsps.keys())
+            .field("sound_groups", &self.sound_groups.keys())
+            .finish()
+    }
+}
+
+impl Registry {
+    /// Releases all registered objects, before the system is closed. Every
+    /// object is released even if some fail, and the first error is returned.
+    pub(crate) fn release_all(&mut self) -> Result {
+        let mut result = Ok(());
+        let mut release = |released: Result| result = result.and(released);
+        // DSPs first, as they may be attached to the groups
+        for (_, dsp) in self.dsps.drain() {
+            release(unsafe { Handle::unleak(dsp) }.release());
+        }
+        for (_, group) in self.groups.drain() {
+            release(unsafe { Handle::unleak(group) }.release());
+        }
+        for (_, group) in self.sound_groups.drain() {
+            release(unsafe { Handle::unleak(group) }.release());
+        }
+        result
+    }
+}
+
+fn register<'a, T: ?Sized + Resource>(
+    system: &'a System,
+    name: &str,
+    handle: Handle<'_, T>,
+    map: fn(&mut Registry) -> &mut HashMap<String, &'static T>,
+) -> Result<&'a T> {
+    let Some(state) = system.state() else {
+        whoops!(no_panic: "{system:?} has no FMOD.rs state");
+        return Err(Error::Internal);
+    };
+    let mut registry = state.registry.write();
+    let map = map(&mut registry);
+    if let Some(existing) = map.get(name) {
+        whoops!(no_panic: "{name:?} is already registered as {existing:?}");
+        return Err(Error::InvalidParam);
+    }
+
+    // the system owns the object now, and releases it when it is closed
+    let raw = Handle::into_raw(handle);
+    #[cfg(debug_assertions)]
+    crate::handle::untrack_derived(raw as usize);
+    let object = unsafe { T::from_raw(raw) };
+    map.insert(name.to_owned(), object);
+    Ok(object)
+}
+
+fn lookup<'a, T: ?Sized + Resource>(
+    system: &'a System,
+    name: &str,
+    map: fn(&Registry) -> &HashMap<String, &'static T>,
+) -> Option<&'a T> {
+    let registry = system.state()?.registry.read();
+    map(&registry).get(name).copied()
+}
+
+/// # Registry.
+///
+/// See the [`registry`](crate::registry) module.
+impl System {
+    /// Registers a [`ChannelGroup`] under `name`.
+    ///
+    /// Returns [`Error::InvalidParam`] if `name` is already registered.
+    pub fn register_named_group(
+        &self,
+        name: &str,
+        group: Handle<'_, ChannelGroup>,
+    ) -> Result<&ChannelGroup> {
+        debug_assert_same_system!(system: self, group);
+        register(self, name, group, |registry| &mut registry.groups)
+    }
+
+    /// Retrieves the [`ChannelGroup`] registered under `name`.
+    pub fn named_group(&self, name: &str) -> Option<&ChannelGroup> {
+        lookup(self, name, |registry| &registry.groups)
+    }
+
+    /// Registers a [`Dsp`] under `name`.
+    ///
+    /// Returns [`Error::InvalidParam`] if `name` is already registered.
+    pub fn register_named_dsp(&self, name: &str, dsp: Handle<'_, Dsp>) -> Result<&Dsp> {
+        debug_assert_same_system!(system: self, dsp);
+        register(self, name, dsp, |registry| &mut registry.dsps)
+    }
+
+    /// Retrieves the [`Dsp`] registered under `name`.
+    pub fn named_dsp(&self, name: &str) -> Option<&Dsp> {
+        lookup(self, name, |registry| &registry.dsps)
+    }
+
+    /// Registers a [`SoundGroup`] under `name`.
+    ///
+    /// Returns [`Error::InvalidParam`] if `name` is already registered.
+    pub fn register_named_sound_group(
+        &self,
+        name: &str,
+        group: Handle<'_, SoundGroup>,
+    ) -> Result<&SoundGroup> {
+        debug_assert_same_system!(system: self, group);
+        register(self, name, group, |registry| &mut registry.sound_groups)
+    }
+
+    /// Retrieves the [`SoundGroup`] registered under `name`.
+    pub fn named_sound_group(&self, name: &str) -> Option<&SoundGroup> {
+        lookup(self, name, |registry| &registry.sound_groups)
+    }
+
+    /// Plays a sound on the [`ChannelGroup`] registered under `group`.
+    ///
+    /// This is [`System::play_sound`], or [`System::create_sound_channel`] if
+    /// `paused`, with the group looked up by name. Returns
+    /// [`Error::InvalidParam`] if no group is registered under that name.
+    pub fn play_sound_in(&self, sound: &Sound, group: &str, paused: bool) -> Result<&Channel> {
+        let Some(channel_group) = self.named_group(group) else {
+            whoops!(no_panic: "no channel group is registered as {group:?}");
+            return Err(Error::InvalidParam);
+        };
+        match paused {
+            true => self.create_sound_channel(sound, Some(channel_group)),
+            false => self.play_sound(sound, Some(channel_group)),
+        }
+    }
+}
@@ -17,6 +17,9 @@ impl Sound {
     /// **Note:** Always check the return value to determine the state of the
     /// sound. Do not assume that if this function returns `Ok` then the sound
     /// has finished loading.
+    ///
+    /// To also retrieve the stream buffering state, use
+    /// [`Sound::get_open_state_info`].
     pub fn get_open_state(&self) -> Result<OpenState> {
         let mut state = OpenState::zeroed();
         ffi!(FMOD_Sound_GetOpenState(
@@ -285,6 +288,11 @@ pub struct OpenStateInfo {
     /// Open state of a sound.
     pub state: OpenState,
     /// Filled percentage of a stream's file buffer.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Percent</dd>
+    /// <dt>Range</dt><dd>[0, 100]</dd>
+    /// </dl>
     pub percent_buffered: u32,
     /// Starving state. `true` if a stream has decoded
     /// more than the stream file buffer has ready.
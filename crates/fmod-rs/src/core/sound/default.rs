@@ -54,18 +54,21 @@ impl Sound {
     /// For these modes the volume will attenuate to 0 volume (silence), when
     /// the distance from the sound is equal to or further than the max distance:
     ///
-    /// - [Mode::LinearRollOff]
-    /// - [Mode::LinearSquareRollOff]
+    /// - [Mode::LinearRolloff3d]
+    /// - [Mode::LinearSquareRolloff3d]
     ///
     /// For these modes the volume will stop attenuating at the point of the max
     /// distance, without affecting the _rate_ of attenuation:
     ///
-    /// - [Mode::InverseRollOff]
-    /// - [Mode::InverseTaperedRollOff]
+    /// - [Mode::InverseRolloff3d]
+    /// - [Mode::InverseTaperedRolloff3d]
     ///
     /// For this mode the max distance is ignored:
     ///
-    /// - [Mode::CustomRolloff]
+    /// - [Mode::CustomRolloff3d]
+    ///
+    /// Pass a range such as `1.0..10_000.0`; an unbounded start uses 0 and an
+    /// unbounded end uses infinity.
     pub fn set_3d_min_max_distance(&self, distance: impl RangeBounds<f32>) -> Result {
         let min_distance = match distance.start_bound() {
             Bound::Included(&min_distance) => min_distance,
@@ -132,20 +135,48 @@ impl Sound {
     /// - [Mode::WorldRelative3d]
     /// - [Mode::D2]
     /// - [Mode::D3]
-    /// - [Mode::InverseRollOff3D]
-    /// - [Mode::LinearRollOff3D]
-    /// - [Mode::LinearSquareRollOff3D]
-    /// - [Mode::InverseTaperedRollOff3D]
-    /// - [Mode::CustomRolloff3D]
-    /// - [Mode::IgnoreGeometry3D]
-    ///
-    /// If [Mode::IgnoreGeometry3D] is not specified, the flag will be cleared
+    /// - [Mode::InverseRolloff3d]
+    /// - [Mode::LinearRolloff3d]
+    /// - [Mode::LinearSquareRolloff3d]
+    /// - [Mode::InverseTaperedRolloff3d]
+    /// - [Mode::CustomRolloff3d]
+    /// - [Mode::IgnoreGeometry3d]
+    ///
+    /// Other flags, such as how the sound is opened or loaded, are fixed when
+    /// the sound is created and are not meaningful here.
+    ///
+    /// If [Mode::IgnoreGeometry3d] is not specified, the flag will be cleared
     /// if it was specified previously.
     ///
     /// Changing mode on an already buffered stream may not produced desired
     /// output. See [Streaming Issues](https://fmod.com/docs/2.02/api/glossary.html#streaming-issues).
+    ///
+    /// Other flags are masked out. In debug builds, setting flags which
+    /// differ from the sound's current mode and can't be changed is logged.
     pub fn set_mode(&self, mode: Mode) -> Result {
-        ffi!(FMOD_Sound_SetMode(self.as_raw(), mode.into_raw()))?;
+        let settable = Mode::LoopOff
+            | Mode::LoopNormal
+            | Mode::LoopBidi
+            | Mode::HeadRelative3d
+            | Mode::WorldRelative3d
+            | Mode::D2
+            | Mode::D3
+            | Mode::InverseRolloff3d
+            | Mode::LinearRolloff3d
+            | Mode::LinearSquareRolloff3d
+            | Mode::InverseTaperedRolloff3d
+            | Mode::CustomRolloff3d
+            | Mode::IgnoreGeometry3d;
+        if cfg!(debug_assertions) {
+            let fixed = mode & !settable & !self.get_mode()?;
+            if fixed != Mode::zeroed() {
+                whoops!(no_panic: "{fixed:?} can't be changed after creating {self:?}; ignoring");
+            }
+        }
+        ffi!(FMOD_Sound_SetMode(
+            self.as_raw(),
+            (mode & settable).into_raw()
+        ))?;
         Ok(())
     }
 
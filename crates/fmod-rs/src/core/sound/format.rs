@@ -118,6 +118,10 @@ impl Sound {
     /// [Channel::set_frequency]. A sample rate change will be signalled with
     /// a tag of type [TagType::Fmod].
     ///
+    /// The tag borrows FMOD's copy of its data, which is only valid until the
+    /// sound is released or the tag is updated; use [Tag::into_owned] to keep
+    /// it for longer. To read embedded album art, see [util::extract_artwork].
+    ///
     /// ```no_run
     /// # let system = fmod::System::new()?;
     /// # let sound = system.create_sound(fmod::cstr8!("drumloop.wav"), fmod::Mode::Default)?;
@@ -150,6 +154,34 @@ impl Sound {
         ))?;
         Ok(unsafe { Tag::from_raw(tag)? })
     }
+
+    /// Retrieves the file or URL entries of a playlist, in order.
+    ///
+    /// Playlist files (.asx, .pls, .m3u, .wax) open as [SoundType::Playlist]
+    /// sounds, which contain no audio. Instead, each entry is exposed as a
+    /// [TagType::Playlist] tag named `"FILE"`, alongside tags such as
+    /// `"TITLE"` and `"LENGTH"` which can be read with [Sound::get_tag].
+    ///
+    /// Returns [Error::Format] if the sound is not a playlist.
+    pub fn playlist_entries(&self) -> Result<Vec<String>> {
+        let kind = self.get_format()?.kind;
+        if kind != SoundType::Playlist {
+            whoops!(no_panic: "Sound::playlist_entries called on {kind:?} sound");
+            yeet!(Error::Format);
+        }
+
+        let mut entries = Vec::new();
+        for index in 0.. {
+            let tag = match self.get_tag(Some(cstr8!("FILE")), index) {
+                Err(Error::TagNotFound) => break,
+                tag => tag?,
+            };
+            if let Some(entry) = tag.data.as_str() {
+                entries.push(entry.trim_end_matches('\0').to_owned());
+            }
+        }
+        Ok(entries)
+    }
 }
 
 /// Tag data / metadata description.
@@ -165,14 +197,28 @@ pub struct Tag<'a> {
     pub updated: bool,
 }
 
+/// A [Tag] which owns its name and data.
+pub type OwnedTag = Tag<'static>;
+
 impl Tag<'_> {
+    /// Copies the tag's name and data, so that it can be stored after the
+    /// [Sound] it came from is released or the tag is updated.
+    pub fn into_owned(self) -> OwnedTag {
+        Tag {
+            kind: self.kind,
+            name: Cow::Owned(self.name.into_owned()),
+            data: self.data.into_owned(),
+            updated: self.updated,
+        }
+    }
+
     raw! {
         pub unsafe fn from_raw(tag: FMOD_TAG) -> Result<Self> {
             let name = CStr::from_ptr(tag.name);
             let name = name.to_string_lossy();
             let data = slice::from_raw_parts(tag.data as *const u8, ix!(tag.datalen));
             let data = match TagDataType::from_raw(tag.datatype) {
-                TagDataType::Binary => TagData::Binary(data),
+                TagDataType::Binary => TagData::Binary(Cow::Borrowed(data)),
                 TagDataType::Int if data.len() == 1 => TagData::Int((tag.data as *const u8).read() as _),
                 TagDataType::Int if data.len() == 2 => TagData::Int((tag.data as *const u16).read_unaligned() as _),
                 TagDataType::Int if data.len() == 4 => TagData::Int((tag.data as *const u32).read_unaligned() as _),
@@ -201,7 +247,11 @@ impl Tag<'_> {
 #[derive(Debug, Clone, PartialEq)]
 pub enum TagData<'a> {
     /// Raw binary data.
-    Binary(&'a [u8]),
+    ///
+    /// This is borrowed from FMOD by [Sound::get_tag], and owned after
+    /// [TagData::into_owned]. Previously this held a `&'a [u8]`; use
+    /// [TagData::as_binary] to get a slice either way.
+    Binary(Cow<'a, [u8]>),
     /// Integer data.
     Int(i64),
     /// Floating point data.
@@ -210,6 +260,19 @@ pub enum TagData<'a> {
     Str(Cow<'a, str>),
 }
 
+impl TagData<'_> {
+    /// Copies the data, so that it can be stored after the [Sound] it came
+    /// from is released or the tag is updated.
+    pub fn into_owned(self) -> TagData<'static> {
+        match self {
+            TagData::Binary(data) => TagData::Binary(Cow::Owned(data.into_owned())),
+            TagData::Int(data) => TagData::Int(data),
+            TagData::Float(data) => TagData::Float(data),
+            TagData::Str(data) => TagData::Str(Cow::Owned(data.into_owned())),
+        }
+    }
+}
+
 #[allow(missing_docs)]
 impl<'a> TagData<'a> {
     pub fn as_binary(&self) -> Option<&[u8]> {
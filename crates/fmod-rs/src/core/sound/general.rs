@@ -4,6 +4,29 @@ use {
     std::{ffi::c_void, ptr, slice},
 };
 
+#[cfg(feature = "debug-introspection")]
+impl Sound {
+    pub(crate) fn fmt_introspect(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        name: std::fmt::Arguments<'_>,
+    ) -> std::fmt::Result {
+        let snapshot = (|| -> Result<_> {
+            let mut sound_name = String::new();
+            self.get_name(&mut sound_name)?;
+            Ok((sound_name, self.get_open_state()?))
+        })();
+        match snapshot {
+            Ok((sound_name, open_state)) => f
+                .debug_struct(&name.to_string())
+                .field("name", &sound_name)
+                .field("open_state", &open_state)
+                .finish(),
+            Err(error) => crate::utils::fmt_introspect_error(f, name, error),
+        }
+    }
+}
+
 /// # General.
 impl Sound {
     raw! {
@@ -17,10 +40,27 @@ impl Sound {
         /// the open state for [`OpenState::Ready`] and [`OpenState::Error`] is a
         /// good way to avoid stalls.
         pub unsafe fn raw_release(this: *mut FMOD_SOUND) -> FMOD_RESULT {
-            FMOD_Sound_Release(this)
+            let system = Sound::from_raw(this).get_system_object();
+            let result = FMOD_Sound_Release(this);
+            if result == FMOD_OK {
+                if let Some(state) = system.ok().and_then(System::state) {
+                    state.sound_memory.forget(this);
+                }
+            }
+            result
         }
     }
 
+    /// Retrieves the memory this sound used when it was created, if it was
+    /// created with [memory::record_sound_memory].
+    ///
+    /// This is 0 bytes if the memory in use decreased while creating the
+    /// sound, as other threads may have freed memory meanwhile.
+    pub fn estimated_memory(&self) -> Option<usize> {
+        let state = self.get_system_object().ok()?.state()?;
+        state.sound_memory.get(self.as_raw())
+    }
+
     // TODO: set_user_data, get_user_data
 
     /// Retrieves the parent System object.
@@ -101,8 +141,8 @@ pub(crate) unsafe extern "system" fn pcm_setpos_callback<F: PcmCallback>(
 ///
 /// Since this callback can occur from the async thread, there are restrictions
 /// about what functions can be called during the callback. All Sound functions
-/// are safe to call, except for [`Sound::set_sound_group` and
-/// [`Sound::raw_release`] (dropping a sound handle). It is also safe to call
+/// are safe to call, except for [`Sound::set_sound_group`] and releasing
+/// the sound (dropping a sound handle). It is also safe to call
 /// [`System::get_user_data`]. The rest of the Core API and the Studio API is
 /// not allowed. Calling a non-allowed function will return
 /// [`Error::InvalidThread`].
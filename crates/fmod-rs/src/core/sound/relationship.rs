@@ -28,6 +28,7 @@ impl Sound {
     ///
     /// A format that has subsounds is a container format,
     /// such as FSB, DLS, MOD, S3M, XM, IT.
+    /// Use [`Sound::get_sub_sound`] to address the contained sounds.
     pub fn get_num_sub_sounds(&self) -> Result<i32> {
         let mut num_sub_sounds = 0;
         ffi!(FMOD_Sound_GetNumSubSounds(
@@ -49,6 +50,20 @@ impl Sound {
     /// The sound won't be ready to be used when [`Mode::NonBlocking`] is used,
     /// until the state of the sound becomes [`OpenState::Ready`] or
     /// [`OpenState::Error`].
+    ///
+    /// A stream only decodes one subsound at a time, so retrieving another
+    /// subsound of a stream (such as an FSB opened with
+    /// [`Mode::CreateStream`]) seeks the stream away from the previous one.
+    /// To play multiple subsounds of a file simultaneously, open it as a
+    /// sample, or open the stream multiple times.
+    ///
+    /// The subsound is owned by its parent and is released along with it.
+    /// Calling this on a sound which has no subsounds returns
+    /// [`Error::SubSounds`].
+    ///
+    /// <dl>
+    /// <dt>Range</dt><dd>[0, Sound::get_num_sub_sounds)</dd>
+    /// </dl>
     pub fn get_sub_sound(&self, index: i32) -> Result<&Sound> {
         let mut sub_sound = ptr::null_mut();
         ffi!(FMOD_Sound_GetSubSound(self.as_raw(), index, &mut sub_sound))?;
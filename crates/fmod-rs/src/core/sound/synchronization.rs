@@ -17,6 +17,50 @@ impl Sound {
         Ok(unsafe { SyncPoint::from_raw(point) })
     }
 
+    /// Retrieves the number of sync points stored within a sound.
+    ///
+    /// For for more information on sync points see [Sync Points].
+    ///
+    /// [Sync Points]: https://fmod.com/docs/2.02/api/glossary.html#sync-points
+    pub fn get_num_sync_points(&self) -> Result<i32> {
+        let mut num_sync_points = 0;
+        ffi!(FMOD_Sound_GetNumSyncPoints(
+            self.as_raw(),
+            &mut num_sync_points,
+        ))?;
+        Ok(num_sync_points)
+    }
+
+    /// Retrieves information on an embedded sync point.
+    ///
+    /// This retrieves both the name and the offset of the sync point in one
+    /// go; the offset is returned in the requested `unit`.
+    ///
+    /// For for more information on sync points see [Sync Points].
+    ///
+    /// [Sync Points]: https://fmod.com/docs/2.02/api/glossary.html#sync-points
+    pub fn get_sync_point_info(
+        &self,
+        sync_point: &SyncPoint,
+        unit: TimeUnit,
+    ) -> Result<(String, Time)> {
+        let mut name = String::new();
+        let mut offset = 0;
+        unsafe {
+            fmod_get_string(&mut name, |buf| {
+                ffi!(FMOD_Sound_GetSyncPointInfo(
+                    self.as_raw(),
+                    sync_point.as_raw(),
+                    buf.as_mut_ptr().cast(),
+                    buf.len() as i32,
+                    &mut offset,
+                    unit.into_raw(),
+                ))
+            })?;
+        }
+        Ok((name, Time::new(offset, unit)))
+    }
+
     /// Retrieves information on an embedded sync point.
     ///
     /// For for more information on sync points see [Sync Points].
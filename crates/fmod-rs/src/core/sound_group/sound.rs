@@ -22,6 +22,19 @@ impl SoundGroup {
         Ok(unsafe { Sound::from_raw(sound) })
     }
 
+    /// Retrieves the total memory the sounds in this group used when they were
+    /// created, for sounds created with [memory::record_sound_memory].
+    ///
+    /// Sounds which weren't created with [memory::record_sound_memory] aren't
+    /// counted.
+    pub fn estimated_memory(&self) -> Result<usize> {
+        let mut total = 0;
+        for index in 0..self.get_num_sounds()? {
+            total += self.get_sound(index)?.estimated_memory().unwrap_or(0);
+        }
+        Ok(total)
+    }
+
     /// Retrieves the number of currently playing Channels for the SoundGroup.
     ///
     /// This routine returns the number of [`Channel`]s playing. If the
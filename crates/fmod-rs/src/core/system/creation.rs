@@ -3,8 +3,16 @@ use {
         userasynccancel_listen, userasyncread_listen, userclose_listen, useropen_listen,
         userread_listen, userseek_listen, AsyncListenFileSystem,
     },
+    cstr8::CString8,
     fmod::{raw::*, *},
-    std::{ffi::CStr, fmt, marker::PhantomData, mem, ptr},
+    std::{
+        collections::hash_map::RandomState,
+        ffi::CStr,
+        fmt,
+        hash::{BuildHasher, Hasher},
+        marker::PhantomData,
+        mem, ptr,
+    },
 };
 
 /// # Creation and retrieval.
@@ -52,10 +60,8 @@ impl System {
     /// Use of Mode::NonBlocking is currently not supported for Wasm.
     /// </span></div></div>
     pub fn create_sound(&self, name: &CStr8, mode: Mode) -> Result<Handle<'_, Sound>> {
-        if mode & (Mode::OpenUser | Mode::OpenMemory | Mode::OpenMemoryPoint | Mode::OpenRaw)
-            != Mode::default()
-        {
-            whoops!("System::create_sound called with advanced mode {mode:?}; use create_sound_ex instead");
+        if let Err(error) = mode.validate_for_create(false) {
+            whoops!("System::create_sound called with invalid mode {mode:?}: {error}");
             yeet!(Error::InvalidParam);
         }
 
@@ -69,7 +75,7 @@ impl System {
             exinfo,
             &mut sound,
         ))?;
-        Ok(unsafe { Handle::new(sound) })
+        Ok(unsafe { Handle::new_derived(self, sound) })
     }
 
     /// Loads a sound into memory, opens it for streaming or sets it up for
@@ -114,6 +120,11 @@ impl System {
         mode: Mode,
         info: CreateSoundEx<'_>,
     ) -> Result<Handle<'_, Sound>> {
+        if let Err(error) = mode.validate_for_create(true) {
+            whoops!("System::create_sound_ex called with invalid mode {mode:?}: {error}");
+            yeet!(Error::InvalidParam);
+        }
+
         let mut sound = ptr::null_mut();
         ffi!(FMOD_System_CreateSound(
             self.as_raw(),
@@ -122,7 +133,7 @@ impl System {
             info.as_raw(),
             &mut sound,
         ))?;
-        Ok(Handle::new(sound))
+        Ok(Handle::new_derived(self, sound))
     }
 
     /// Opens a sound for streaming.
@@ -138,11 +149,8 @@ impl System {
     /// If you need access to the extended options, use
     /// [`System::create_sound_ex`] instead and set [`Mode::CreateStream`].
     pub fn create_stream(&self, name: &CStr8, mode: Mode) -> Result<Handle<'_, Sound>> {
-        if matches!(
-            mode,
-            Mode::OpenUser | Mode::OpenMemory | Mode::OpenMemoryPoint | Mode::OpenRaw
-        ) {
-            whoops!("System::create_stream called with extended mode {mode:?}; use create_sound_ex instead");
+        if let Err(error) = (mode | Mode::CreateStream).validate_for_create(false) {
+            whoops!("System::create_stream called with invalid mode {mode:?}: {error}");
             yeet!(Error::InvalidParam);
         }
 
@@ -156,7 +164,51 @@ impl System {
             exinfo,
             &mut sound,
         ))?;
-        Ok(unsafe { Handle::new(sound) })
+        Ok(unsafe { Handle::new_derived(self, sound) })
+    }
+
+    /// Opens a playlist (.asx, .pls, .m3u, .wax) and opens one of its entries
+    /// for streaming.
+    ///
+    /// The entries are read with [`Sound::playlist_entries`], then the entry
+    /// chosen by `start` is opened with [`System::create_stream`] using
+    /// `mode`. The full entry list is returned alongside the stream, so the
+    /// next entry can be opened when the stream ends.
+    ///
+    /// Entries are opened exactly as written in the playlist, so relative
+    /// paths are resolved against the working directory rather than the
+    /// playlist's location.
+    ///
+    /// Returns [`Error::Format`] if `playlist` is not a playlist or has no
+    /// entries.
+    pub fn create_playlist_stream(
+        &self,
+        playlist: &CStr8,
+        mode: Mode,
+        start: PlaylistStart,
+    ) -> Result<PlaylistStream<'_>> {
+        let entries = self
+            .create_sound(playlist, Mode::Default)?
+            .playlist_entries()?;
+        let index = match start {
+            _ if entries.is_empty() => {
+                whoops!(no_panic: "playlist {playlist:?} has no entries");
+                yeet!(Error::Format);
+            },
+            PlaylistStart::First => 0,
+            PlaylistStart::Random => {
+                let random = RandomState::new().build_hasher().finish();
+                (random % entries.len() as u64) as usize
+            },
+        };
+
+        let entry = CString8::new(&*entries[index]).map_err(|_| Error::InvalidParam)?;
+        let stream = self.create_stream(&entry, mode)?;
+        Ok(PlaylistStream {
+            stream,
+            entries,
+            index,
+        })
     }
 
     // TODO: pub fn create_dsp
@@ -182,7 +234,7 @@ impl System {
             kind.into_raw(),
             &mut dsp,
         ))?;
-        Ok(unsafe { Handle::new(dsp) })
+        Ok(unsafe { Handle::new_derived(self, dsp) })
     }
 
     /// Create a ChannelGroup object.
@@ -208,7 +260,7 @@ impl System {
             name.as_ptr() as _,
             &mut channel_group,
         ))?;
-        Ok(unsafe { Handle::new(channel_group) })
+        Ok(unsafe { Handle::new_derived(self, channel_group) })
     }
 
     /// Creates a SoundGroup object.
@@ -228,7 +280,7 @@ impl System {
             name.as_ptr() as _,
             &mut sound_group,
         ))?;
-        Ok(unsafe { Handle::new(sound_group) })
+        Ok(unsafe { Handle::new_derived(self, sound_group) })
     }
 
     /// Creates a 'virtual reverb' object. This object reacts to 3D location and
@@ -273,6 +325,7 @@ impl System {
     /// are released. Then call [System::set_reverb_properties] with the 3D
     /// reverb's slot ID (default is 0) with a property point of 0 or NULL, to
     /// signal that the physical reverb instance should be deleted.
+    /// [System::reserve_reverb_slot] does this automatically.
     ///
     /// If a 3D reverb is still present, and [System::set_reverb_properties]
     /// function is called to free the physical reverb, the 3D reverb system
@@ -284,7 +337,7 @@ impl System {
     pub fn create_reverb_3d(&self) -> Result<Handle<'_, Reverb3d>> {
         let mut reverb = ptr::null_mut();
         ffi!(FMOD_System_CreateReverb3D(self.as_raw(), &mut reverb))?;
-        Ok(unsafe { Handle::new(reverb) })
+        Ok(unsafe { Handle::new_derived(self, reverb) })
     }
 
     /// Creates a Channel to play a Sound. The channel starts paused.
@@ -295,7 +348,7 @@ impl System {
     /// A sound defined as [Mode::D3] will by default play at the 3D position of
     /// the listener. To set the 3D position of the [Channel] before the sound
     /// is audible, start the [Channel] paused by setting the `paused` parameter
-    /// to true, and call [ChannelControl::set_3D_attributes].
+    /// to true, and call [ChannelControl::set_3d_attributes].
     ///
     /// Specifying a `channel_group` as part of `play_sound` is more efficient
     /// than using [Channel::set_channel_group] after play_sound, and could
@@ -315,6 +368,11 @@ impl System {
         sound: &Sound,
         channel_group: Option<&ChannelGroup>,
     ) -> Result<&Channel> {
+        self.check_mixer_running()?;
+        debug_assert_same_system!(system: self, sound);
+        if let Some(channel_group) = channel_group {
+            debug_assert_same_system!(system: self, channel_group);
+        }
         let sound = Sound::as_raw(sound);
         let channelgroup = channel_group
             .map(ChannelGroup::as_raw)
@@ -341,6 +399,11 @@ impl System {
         sound: &Sound,
         channel_group: Option<&ChannelGroup>,
     ) -> Result<&Channel> {
+        self.check_mixer_running()?;
+        debug_assert_same_system!(system: self, sound);
+        if let Some(channel_group) = channel_group {
+            debug_assert_same_system!(system: self, channel_group);
+        }
         let sound = Sound::as_raw(sound);
         let channelgroup = channel_group
             .map(ChannelGroup::as_raw)
@@ -356,6 +419,30 @@ impl System {
         Ok(unsafe { Channel::from_raw(channel) })
     }
 
+    /// Plays a Sound on a Channel, configured with a fluent builder.
+    ///
+    /// The channel is always created paused, configured, and then unpaused
+    /// (unless [`PlaySound::paused`] is requested), avoiding the audible
+    /// glitch of adjusting a channel that has already started playing.
+    ///
+    /// ```rust,ignore
+    /// let channel = system
+    ///     .play(&sound)
+    ///     .channel_group(&group)
+    ///     .volume(0.5)
+    ///     .start()?;
+    /// ```
+    pub fn play<'a>(&'a self, sound: &'a Sound) -> PlaySound<'a> {
+        PlaySound {
+            system: self,
+            sound,
+            channel_group: None,
+            paused: false,
+            volume: None,
+            position: None,
+        }
+    }
+
     /// Creates a channel to plays a DSP along with any of its inputs. The
     /// channel starts paused.
     ///
@@ -377,6 +464,11 @@ impl System {
         dsp: &Dsp,
         channel_group: Option<&ChannelGroup>,
     ) -> Result<&Channel> {
+        self.check_mixer_running()?;
+        debug_assert_same_system!(system: self, dsp);
+        if let Some(channel_group) = channel_group {
+            debug_assert_same_system!(system: self, channel_group);
+        }
         let dsp = Dsp::as_raw(dsp);
         let channelgroup = channel_group
             .map(ChannelGroup::as_raw)
@@ -400,6 +492,11 @@ impl System {
     /// channel being audible, then follow it up with a call to
     /// [`ChannelControl::set_paused`] with `paused` = false.
     pub fn play_dsp(&self, dsp: &Dsp, channel_group: Option<&ChannelGroup>) -> Result<&Channel> {
+        self.check_mixer_running()?;
+        debug_assert_same_system!(system: self, dsp);
+        if let Some(channel_group) = channel_group {
+            debug_assert_same_system!(system: self, channel_group);
+        }
         let dsp = Dsp::as_raw(dsp);
         let channelgroup = channel_group
             .map(ChannelGroup::as_raw)
@@ -464,6 +561,112 @@ impl System {
     }
 }
 
+/// A fluent builder for playing a [`Sound`] on a [`Channel`].
+///
+/// Create with [`System::play`], then call [`PlaySound::start`].
+///
+/// Note that once a [`Channel`] stops playing, either by reaching the end of
+/// its [`Sound`] or by being stolen by the virtual voice system, any further
+/// calls on the [`Channel`] handle will return [`Error::InvalidHandle`] or
+/// [`Error::ChannelStolen`]. This is expected; see the white paper on
+/// [Channel handles] for more information.
+///
+/// [Channel handles]: https://fmod.com/resources/documentation-api?version=2.02&page=white-papers-handle-system.html#core-api-channels
+#[derive(Debug, Clone, Copy)]
+#[must_use = "the sound is not played until `start` is called"]
+pub struct PlaySound<'a> {
+    system: &'a System,
+    sound: &'a Sound,
+    channel_group: Option<&'a ChannelGroup>,
+    paused: bool,
+    volume: Option<f32>,
+    position: Option<Time>,
+}
+
+impl<'a> PlaySound<'a> {
+    /// Leave the channel paused after it is created.
+    ///
+    /// Use [`ChannelControl::set_paused`] to start playback later.
+    pub fn paused(mut self) -> Self {
+        self.paused = true;
+        self
+    }
+
+    /// The [`ChannelGroup`] to output to instead of the master group.
+    pub fn channel_group(mut self, group: &'a ChannelGroup) -> Self {
+        self.channel_group = Some(group);
+        self
+    }
+
+    /// The volume level to start the channel at.
+    ///
+    /// See [`ChannelControl::set_volume`].
+    pub fn volume(mut self, volume: f32) -> Self {
+        self.volume = Some(volume);
+        self
+    }
+
+    /// The playback position to start the channel at.
+    ///
+    /// See [`Channel::set_position`].
+    pub fn position(mut self, position: Time) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Creates the channel and starts playback.
+    ///
+    /// If configuring the channel fails, the channel is stopped before the
+    /// error is returned.
+    pub fn start(self) -> Result<&'a Channel> {
+        let channel = self
+            .system
+            .create_sound_channel(self.sound, self.channel_group)?;
+
+        let configure = || {
+            if let Some(volume) = self.volume {
+                channel.set_volume(volume)?;
+            }
+            if let Some(position) = self.position {
+                channel.set_position(position)?;
+            }
+            if !self.paused {
+                channel.set_paused(false)?;
+            }
+            Ok(())
+        };
+
+        match configure() {
+            Ok(()) => Ok(channel),
+            Err(err) => {
+                let _ = channel.stop();
+                Err(err)
+            },
+        }
+    }
+}
+
+/// Which entry of a playlist [`System::create_playlist_stream`] opens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PlaylistStart {
+    /// The first entry.
+    #[default]
+    First,
+    /// A randomly chosen entry.
+    Random,
+}
+
+/// A stream opened from a playlist by [`System::create_playlist_stream`].
+#[derive(Debug)]
+pub struct PlaylistStream<'a> {
+    /// The stream of the opened entry.
+    pub stream: Handle<'a, Sound>,
+    /// The file or URL entries of the playlist, in order.
+    pub entries: Vec<String>,
+    /// Index into `entries` of the opened entry.
+    pub index: usize,
+}
+
 /// Additional options for creating a [`Sound`].
 ///
 /// Loading a file from memory:
@@ -611,8 +814,11 @@ impl<'a> CreateSoundEx<'a> {
         self
     }
 
-    /// Callback to notify completion for [`Mode::Nonblocking`], occurs during
+    /// Callback to notify completion for [`Mode::NonBlocking`], occurs during
     /// creation and seeking / restarting streams.
+    ///
+    /// The callback is invoked from FMOD's nonblocking loader thread; see
+    /// [`NonBlockCallback`] for which functions may be called from it.
     pub fn nonblock_callback<F: NonBlockCallback>(mut self) -> Self {
         self.info.nonblockcallback = Some(non_block_callback::<F>);
         self
@@ -700,7 +906,7 @@ impl<'a> CreateSoundEx<'a> {
         self
     }
 
-    /// Thread index to execute [Mode::Nonblocking] loads on for parallel
+    /// Thread index to execute [`Mode::NonBlocking`] loads on for parallel
     /// `Sound` loading.
     pub fn non_block_tread_id(mut self, thread_id: i32) -> Self {
         self.info.nonblockthreadid = thread_id;
@@ -730,8 +936,8 @@ impl fmt::Debug for CreateSoundEx<'_> {
         d!(defaultfrequency, default_frequency);
         d!(format, format);
         d!(decodebuffersize, decode_buffer_size);
-        d!(initialsubsound, initial_sub_sound);
-        d!(numsubsounds, num_sub_sounds);
+        d!(initialsubsound, initial_subsound);
+        d!(numsubsounds, num_subsounds);
         d!(maxpolyphony, max_polyphony);
         d!(suggestedsoundtype, suggested_sound_type);
         d!(filebuffersize, file_buffer_size);
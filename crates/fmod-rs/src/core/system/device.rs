@@ -2,7 +2,7 @@ use {
     crate::utils::fmod_get_string,
     fmod::{raw::*, *},
     smart_default::SmartDefault,
-    std::ptr,
+    std::{mem::MaybeUninit, ptr},
 };
 
 /// # Device selection.
@@ -51,6 +51,7 @@ impl System {
     // getting *just* the name is common, and the name has extra retry
     // requirements to validate non-truncation and UTF-8. This does, however,
     // mean that getting all of the driver info requires an extra FFI call.
+    // get_driver_details is provided to get both with (usually) one FFI call.
 
     /// Retrieves identification information about a sound device specified by
     /// its index, and specific to the selected output mode.
@@ -107,6 +108,48 @@ impl System {
         }
     }
 
+    /// Retrieves the name and identification information about a sound
+    /// device specified by its index, and specific to the selected output
+    /// mode.
+    ///
+    /// Unlike calling both [System::get_driver_name] and
+    /// [System::get_driver_info], this retrieves everything with a single FFI
+    /// call, unless the name is too long to fit in a 256 byte buffer.
+    ///
+    /// <dl>
+    /// <dt>Range</dt><dd>[0, System::get_num_drivers]</dd>
+    /// </dl>
+    pub fn get_driver_details(&self, id: i32) -> Result<Driver> {
+        unsafe {
+            Driver::query(|buf, info| {
+                ffi!(FMOD_System_GetDriverInfo(
+                    self.as_raw(),
+                    id,
+                    buf.as_mut_ptr().cast(),
+                    buf.len() as _,
+                    info.guid.as_raw_mut(),
+                    &mut info.system_rate,
+                    info.speaker_mode.as_raw_mut(),
+                    &mut info.speaker_mode_channels,
+                ))
+            })
+        }
+    }
+
+    /// Enumerates the output drivers available for the selected output type.
+    ///
+    /// This is a convenience for calling [System::get_driver_details] for
+    /// each driver index up to [System::get_num_drivers]. The number of
+    /// drivers is queried once, when this function is called; when the device
+    /// list changes, call this again to get an up-to-date enumeration.
+    ///
+    /// Drivers are yielded in index order, so use [`Iterator::enumerate`] to
+    /// get the id to pass to [System::set_driver].
+    pub fn drivers(&self) -> Result<impl Iterator<Item = Result<Driver>> + '_> {
+        let num_drivers = self.get_num_drivers()?;
+        Ok((0..num_drivers).map(|id| self.get_driver_details(id)))
+    }
+
     /// Sets the output driver for the selected output type.
     ///
     /// When an output type has more than one driver available, this function
@@ -131,6 +174,144 @@ impl System {
         ffi!(FMOD_System_GetDriver(self.as_raw(), &mut driver))?;
         Ok(driver)
     }
+
+    /// Switches to a different output type and driver, restoring the
+    /// previous output and driver if that fails.
+    ///
+    /// This is typically used after [System::init] to change the output when
+    /// the user picks a different device in settings, e.g. switching from
+    /// [OutputType::Wasapi] to an [OutputType::Asio] device. As with
+    /// [System::set_output], only some platforms support changing the output
+    /// after initialization.
+    ///
+    /// If switching fails and the previous configuration is restored, this
+    /// returns [OutputSwitchReport::Reverted] with the error which caused the
+    /// switch to fail. If restoring the previous configuration fails as well,
+    /// the original error is logged and the error from restoring is returned,
+    /// as the system is then in neither configuration.
+    pub fn switch_output(
+        &self,
+        requested: OutputType,
+        driver: DriverSelector,
+    ) -> Result<OutputSwitchReport> {
+        self.switch_output_with(|system| system.set_output(requested), driver)
+    }
+
+    /// Switches to an output plugin and driver, restoring the previous output
+    /// and driver if that fails.
+    ///
+    /// See [System::switch_output]; this is the same, but for outputs selected
+    /// with [System::set_output_by_plugin], such as those loaded with
+    /// [System::load_plugin].
+    pub fn switch_output_by_plugin(
+        &self,
+        requested: PluginHandle,
+        driver: DriverSelector,
+    ) -> Result<OutputSwitchReport> {
+        self.switch_output_with(|system| system.set_output_by_plugin(requested), driver)
+    }
+
+    fn switch_output_with(
+        &self,
+        set_output: impl FnOnce(&System) -> Result,
+        driver: DriverSelector,
+    ) -> Result<OutputSwitchReport> {
+        let previous_output = self.get_output()?;
+        let previous_plugin = self.get_output_by_plugin()?;
+        let previous_driver = self.get_driver()?;
+
+        let select_driver = || -> Result<(i32, DriverInfo)> {
+            let id = self.find_driver(driver)?;
+            self.set_driver(id)?;
+            Ok((id, self.get_driver_info(id)?))
+        };
+        let switched = set_output(self)
+            .map_err(|error| (OutputSwitchStage::Output, error))
+            .and_then(|()| select_driver().map_err(|error| (OutputSwitchStage::Driver, error)));
+
+        let (stage, error) = match switched {
+            Ok((driver, info)) => {
+                return Ok(OutputSwitchReport::Switched {
+                    output: self.get_output()?,
+                    driver,
+                    info,
+                })
+            },
+            Err(failure) => failure,
+        };
+
+        let restored = match previous_output {
+            // third party output plugin, which can only be selected by handle
+            OutputType::Unknown => self.set_output_by_plugin(previous_plugin),
+            output => self.set_output(output),
+        }
+        .and_then(|()| self.set_driver(previous_driver));
+        if let Err(restore_error) = restored {
+            whoops!(no_panic: "failed to switch output ({stage:?}): {error}");
+            whoops!(no_panic: "failed to restore output {previous_output:?}, driver {previous_driver}: {restore_error}");
+            return Err(restore_error);
+        }
+
+        Ok(OutputSwitchReport::Reverted { stage, error })
+    }
+
+    fn find_driver(&self, driver: DriverSelector) -> Result<i32> {
+        match driver {
+            DriverSelector::Default => Ok(0),
+            DriverSelector::ByIndex(id) => Ok(id),
+            DriverSelector::ByGuid(guid) => {
+                for id in 0..self.get_num_drivers()? {
+                    if self.get_driver_info(id)?.guid == guid {
+                        return Ok(id);
+                    }
+                }
+                whoops!(no_panic: "no output driver with GUID {guid}");
+                Err(Error::InvalidParam)
+            },
+        }
+    }
+}
+
+/// Which output driver [System::switch_output] selects.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DriverSelector {
+    /// The default driver for the output type.
+    #[default]
+    Default,
+    /// The driver with this index, see [System::get_num_drivers].
+    ByIndex(i32),
+    /// The driver with this GUID, see [DriverInfo::guid].
+    ByGuid(Guid),
+}
+
+/// The outcome of [System::switch_output].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OutputSwitchReport {
+    /// The output was switched.
+    Switched {
+        /// The output type now in use.
+        output: OutputType,
+        /// The index of the driver now in use.
+        driver: i32,
+        /// Information about the driver now in use.
+        info: DriverInfo,
+    },
+    /// Switching failed, and the previous output and driver were restored.
+    Reverted {
+        /// Which part of switching failed.
+        stage: OutputSwitchStage,
+        /// The error which caused switching to fail.
+        error: Error,
+    },
+}
+
+/// A part of [System::switch_output] which can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum OutputSwitchStage {
+    /// Selecting the output type.
+    Output,
+    /// Finding and selecting the driver.
+    Driver,
 }
 
 fmod_enum! {
@@ -194,6 +375,35 @@ fmod_enum! {
     }
 }
 
+/// Name and identification information about a sound device.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Driver {
+    /// The name of the device.
+    pub name: String,
+    /// Identification information about the device.
+    pub info: DriverInfo,
+}
+
+impl Driver {
+    /// Retrieves a driver's name together with its identification info.
+    ///
+    /// `query` is called with a buffer for the name and the info to fill in,
+    /// and is only called again if the name doesn't fit in the first buffer.
+    ///
+    /// # Safety
+    ///
+    /// On success, `query` must have written a nul-terminated name into the
+    /// provided buffer.
+    pub(crate) unsafe fn query(
+        mut query: impl FnMut(&mut [MaybeUninit<u8>], &mut DriverInfo) -> Result,
+    ) -> Result<Driver> {
+        let mut name = String::new();
+        let mut info = DriverInfo::default();
+        fmod_get_string(&mut name, |buf| query(buf, &mut info))?;
+        Ok(Driver { name, info })
+    }
+}
+
 /// Identification information about a sound device.
 #[derive(Debug, SmartDefault, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct DriverInfo {
@@ -210,3 +420,61 @@ pub struct DriverInfo {
     #[default(DriverState::zeroed())]
     pub state: DriverState,
 }
+
+impl DriverInfo {
+    /// Whether the record device is currently plugged in.
+    pub fn is_connected(&self) -> bool {
+        self.state.is_connected()
+    }
+
+    /// Whether the record device is the user's preferred choice.
+    pub fn is_default(&self) -> bool {
+        self.state.is_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A mock driver query, which writes `name` and a nul terminator if it
+    /// fits and reports truncation otherwise, counting each call.
+    fn mock_query(name: &str, calls: &mut usize) -> Result<Driver> {
+        unsafe {
+            Driver::query(|buf, info| {
+                *calls += 1;
+                info.system_rate = 48000;
+                if buf.len() <= name.len() {
+                    return Err(Error::Truncated);
+                }
+                for (dst, &src) in buf.iter_mut().zip(name.as_bytes().iter().chain(&[0])) {
+                    dst.write(src);
+                }
+                Ok(())
+            })
+        }
+    }
+
+    #[test]
+    fn enumeration_queries_each_driver_once() {
+        let mut calls = 0;
+        let drivers: Vec<Driver> = (0..10)
+            .map(|id| mock_query(&format!("Speakers ({id})"), &mut calls))
+            .collect::<Result<_>>()
+            .unwrap();
+        // separate name and info queries would take 20 calls
+        assert_eq!(calls, 10);
+        assert_eq!(drivers[3].name, "Speakers (3)");
+        assert_eq!(drivers[3].info.system_rate, 48000);
+    }
+
+    #[test]
+    fn long_name_retries_once() {
+        let mut calls = 0;
+        let name = "x".repeat(300);
+        let driver = mock_query(&name, &mut calls).unwrap();
+        assert_eq!(calls, 2);
+        assert_eq!(driver.name, name);
+        assert_eq!(driver.info.system_rate, 48000);
+    }
+}
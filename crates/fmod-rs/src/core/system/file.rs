@@ -92,6 +92,16 @@ impl System {
         Ok(())
     }
 
+    /// Set callbacks to implement all file I/O with futures on an async
+    /// runtime instead of using the platform native method.
+    ///
+    /// This is [`set_file_system_async`](Self::set_file_system_async) with
+    /// the outstanding reads managed by [`file::SpawnAdapter`]; see
+    /// [`SpawnFileSystem`] for details.
+    pub fn set_file_system_spawn<FS: file::SpawnFileSystem>(&self, block_align: i32) -> Result {
+        self.set_file_system_async::<file::SpawnAdapter<FS>>(block_align)
+    }
+
     /// 'Piggyback' on FMOD file reading routines to capture data as it's read.
     ///
     /// This allows users to capture data as FMOD reads it, which may be useful
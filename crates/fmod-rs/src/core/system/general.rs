@@ -1,11 +1,15 @@
 use {
-    crate::utils::catch_user_unwind,
+    crate::{registry::Registry, utils::catch_user_unwind},
     fmod::{raw::*, *},
+    parking_lot::{Mutex, RwLock},
     std::{
         borrow::Cow,
+        cell::RefCell,
         ffi::{c_char, c_void, CStr},
         marker::PhantomData,
         mem::ManuallyDrop,
+        ptr,
+        sync::atomic::{AtomicBool, AtomicU64, Ordering},
     },
 };
 
@@ -35,7 +39,9 @@ impl System {
     ///
     /// The DSP engine must not already be locked when this function is called.
     pub unsafe fn lock_dsp(&self) -> Result {
+        self.check_mixer_running()?;
         ffi!(FMOD_System_LockDSP(self.as_raw()))?;
+        DSP_LOCKS_HELD.with(|held| held.borrow_mut().push(self.as_raw() as usize));
         Ok(())
     }
 
@@ -48,6 +54,12 @@ impl System {
     /// function is called.
     pub unsafe fn unlock_dsp(&self) -> Result {
         ffi!(FMOD_System_UnlockDSP(self.as_raw()))?;
+        DSP_LOCKS_HELD.with(|held| {
+            let mut held = held.borrow_mut();
+            if let Some(i) = held.iter().rposition(|&raw| raw == self.as_raw() as usize) {
+                held.swap_remove(i);
+            }
+        });
         Ok(())
     }
 
@@ -67,10 +79,73 @@ impl System {
             Some(system_callback::<C>),
             mask.into_raw(),
         ))?;
+        if let Some(state) = self.state() {
+            state.callback_generation.fetch_add(1, Ordering::Relaxed);
+        }
         Ok(())
     }
 
-    // set_user_data, get_user_data
+    // set_user_data, get_user_data: the system's userdata holds SystemState
+
+    /// Retrieves the state FMOD.rs keeps for this system.
+    ///
+    /// This is `None` only for systems not created by FMOD.rs.
+    pub(crate) fn state(&self) -> Option<&SystemState> {
+        let mut userdata = ptr::null_mut();
+        unsafe {
+            if FMOD_System_GetUserData(self.as_raw(), &mut userdata) != FMOD_OK {
+                return None;
+            }
+            userdata.cast::<SystemState>().as_ref()
+        }
+    }
+
+    /// Allocates the state FMOD.rs keeps for a newly created system.
+    pub(crate) unsafe fn init_state(raw: *mut FMOD_SYSTEM) -> Result {
+        let state = Box::into_raw(Box::<SystemState>::default());
+        let result = ffi!(FMOD_System_SetUserData(raw, state.cast()));
+        if result.is_err() {
+            drop(Box::from_raw(state));
+        }
+        result
+    }
+
+    /// Takes the state FMOD.rs keeps for a system, to be dropped once the
+    /// system has been released.
+    pub(crate) unsafe fn take_state(raw: *mut FMOD_SYSTEM) -> Option<Box<SystemState>> {
+        let mut userdata = ptr::null_mut();
+        if FMOD_System_GetUserData(raw, &mut userdata) != FMOD_OK || userdata.is_null() {
+            return None;
+        }
+        Some(Box::from_raw(userdata.cast()))
+    }
+}
+
+/// State FMOD.rs keeps for each [System], stored in the system's userdata.
+///
+/// This is freed when the system is released, so anything kept here is
+/// cleaned up with the system, and is found without a global lookup.
+#[derive(Default)]
+pub(crate) struct SystemState {
+    /// Incremented by every [System::set_callback], so that a callback's
+    /// installer can tell whether it has since been replaced.
+    pub(crate) callback_generation: AtomicU64,
+    /// Set while a [MixerSuspendGuard] for the system is alive.
+    pub(crate) mixer_suspended: AtomicBool,
+    /// The installed [DeviceWatcher](util::DeviceWatcher).
+    pub(crate) device_watcher: Mutex<Option<util::WatcherSlot>>,
+    /// Custom rolloff curves FMOD refers to.
+    pub(crate) custom_rolloffs: CustomRolloffs,
+    /// The `max_channels` the system was initialized with, if it is.
+    pub(crate) max_channels: Mutex<Option<i32>>,
+    /// The ports groups are attached to.
+    pub(crate) port_attachments: PortAttachments,
+    /// Objects registered by name, see [`registry`](crate::registry).
+    pub(crate) registry: RwLock<Registry>,
+    /// The closure set with [System::set_3d_rolloff_closure].
+    pub(crate) rolloff_closure: RolloffClosure,
+    /// Memory used by sounds, see [memory::record_sound_memory].
+    pub(crate) sound_memory: memory::SoundMemory,
 }
 
 fmod_struct! {
@@ -475,14 +550,32 @@ fmod_flags! {
     }
 }
 
+thread_local! {
+    /// The systems whose DSP engine is locked by this thread.
+    static DSP_LOCKS_HELD: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
 /// Mutual exclusion lock guard for the FMOD DSP engine.
 ///
 /// The lock is released when this guard is dropped.
 pub struct DspLock<'a> {
-    system: &'a System,
+    pub(crate) system: &'a System,
 }
 
 impl DspLock<'_> {
+    /// Locks the DSP engine, unless this thread has already locked it or the
+    /// mixer is suspended, in which case `None` is returned as the mixer
+    /// isn't running anyway.
+    pub(crate) fn unless_held(system: &System) -> Result<Option<DspLock<'_>>> {
+        let held = DSP_LOCKS_HELD.with(|held| held.borrow().contains(&(system.as_raw() as usize)));
+        match held || system.check_mixer_running().is_err() {
+            true => Ok(None),
+            // SAFETY: this thread doesn't hold the lock, and if another
+            // thread does, locking waits for it to be unlocked
+            false => Ok(Some(unsafe { DspLock::new(system) }?)),
+        }
+    }
+
     /// Mutual exclusion function to lock the FMOD DSP engine (which runs
     /// asynchronously in another thread), so that it will not execute.
     ///
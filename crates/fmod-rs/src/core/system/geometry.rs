@@ -31,7 +31,7 @@ impl System {
             max_vertices,
             &mut geometry,
         ))?;
-        Ok(unsafe { Handle::new(geometry) })
+        Ok(unsafe { Handle::new_derived(self, geometry) })
     }
 
     /// Sets the maximum world size for the geometry engine for performance /
@@ -83,7 +83,7 @@ impl System {
             data.len() as _,
             &mut geometry,
         ))?;
-        Ok(unsafe { Handle::new(geometry) })
+        Ok(unsafe { Handle::new_derived(self, geometry) })
     }
 
     /// Calculates geometry occlusion between a listener and a sound source.
@@ -1,6 +1,6 @@
 use {
     fmod::{raw::*, *},
-    std::ptr,
+    std::{collections::HashSet, fmt, ptr},
 };
 
 /// # Information.
@@ -50,6 +50,38 @@ impl System {
         })
     }
 
+    /// Retrieves the fraction of playing Channels which are real (not
+    /// virtual).
+    ///
+    /// This is 1 when no channels are playing.
+    pub fn channel_usage_ratio(&self) -> Result<f32> {
+        let usage = self.get_channels_playing()?;
+        if usage.all == 0 {
+            return Ok(1.0);
+        }
+        Ok(usage.real as f32 / usage.all as f32)
+    }
+
+    /// Retrieves the maximum number of Channels (both real and virtual)
+    /// which was passed to [System::init].
+    ///
+    /// FMOD doesn't report this itself, so it is recorded when the system is
+    /// initialized. Returns [Error::Uninitialized] if the system is not
+    /// initialized.
+    pub fn get_max_channels(&self) -> Result<i32> {
+        self.state()
+            .and_then(|state| *state.max_channels.lock())
+            .ok_or(Error::Uninitialized)
+    }
+
+    /// Records the `max_channels` of an initialized system, or forgets it
+    /// when the system is closed.
+    pub(crate) fn record_max_channels(&self, max_channels: Option<i32>) {
+        if let Some(state) = self.state() {
+            *state.max_channels.lock() = max_channels;
+        }
+    }
+
     /// Retrieves the amount of CPU used for different parts of the Core engine.
     ///
     /// For readability, the percentage values are smoothed to provide a more
@@ -78,10 +110,49 @@ impl System {
         })
     }
 
+    /// Retrieves a snapshot of the System's resource usage.
+    ///
+    /// This combines [`System::get_channels_playing`],
+    /// [`System::get_cpu_usage`], [`System::get_file_usage`],
+    /// [`memory::get_stats`] (non-blocking), and
+    /// [`System::get_software_format`] for display in e.g. a performance
+    /// overlay.
+    pub fn telemetry(&self) -> Result<Telemetry> {
+        Ok(Telemetry {
+            channels: self.get_channels_playing()?,
+            cpu: self.get_cpu_usage()?,
+            file: self.get_file_usage()?,
+            memory: memory::get_stats(false)?,
+            software_format: self.get_software_format()?,
+        })
+    }
+
+    /// Iterates over the DSP graph depth-first, starting from the
+    /// [head DSP](ChannelControl::get_dsp_head) of the
+    /// [master ChannelGroup](System::get_master_channel_group).
+    ///
+    /// Each DSP is visited once, even if it has multiple outputs. As walking
+    /// the graph uses [`Dsp::get_input`], this flushes the DSP queue and
+    /// should be avoided during time sensitive operations.
+    ///
+    /// Combined with [`Dsp::get_cpu_usage`] and [InitFlags::ProfileMeterAll],
+    /// this can be used to find the most expensive DSPs.
+    pub fn iter_dsp_graph(&self) -> Result<DspGraphIter<'_>> {
+        let head = self.get_master_channel_group()?.get_dsp_head()?;
+        Ok(DspGraphIter {
+            stack: vec![head],
+            visited: HashSet::new(),
+        })
+    }
+
     // TODO: figure out mix matrix API
     // get_default_mix_matrix
 
     /// Retrieves the channel count for a given speaker mode.
+    ///
+    /// For speaker modes other than [SpeakerMode::Default] and
+    /// [SpeakerMode::Raw], which depend on the system's configuration,
+    /// [SpeakerMode::channel_count] gives the same answer without an FFI call.
     pub fn get_speaker_mode_channels(&self, mode: SpeakerMode) -> Result<usize> {
         let mut channels = 0;
         ffi!(FMOD_System_GetSpeakerModeChannels(
@@ -112,3 +183,107 @@ pub struct FileUsage {
     /// Total bytes read for non-audio data such as FMOD Studio banks.
     pub other_bytes_read: i64,
 }
+
+impl FileUsage {
+    /// The bytes read since `previous` was retrieved.
+    ///
+    /// Sampling [System::get_file_usage] once per [System::update] and taking
+    /// the delta from the previous sample gives the bytes read per update.
+    pub fn delta(&self, previous: &FileUsage) -> FileUsage {
+        FileUsage {
+            sample_bytes_read: self.sample_bytes_read - previous.sample_bytes_read,
+            stream_bytes_read: self.stream_bytes_read - previous.stream_bytes_read,
+            other_bytes_read: self.other_bytes_read - previous.other_bytes_read,
+        }
+    }
+
+    /// The total bytes read, of all kinds.
+    pub fn total_bytes_read(&self) -> i64 {
+        self.sample_bytes_read + self.stream_bytes_read + self.other_bytes_read
+    }
+}
+
+/// A snapshot of a [System]'s resource usage, retrieved with
+/// [`System::telemetry`].
+///
+/// The [`Display`](fmt::Display) implementation formats this as multiple
+/// lines of text, suitable for an on-screen overlay.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Telemetry {
+    /// Number of playing channels.
+    pub channels: ChannelUsage,
+    /// CPU usage of the Core engine.
+    pub cpu: CpuUsage,
+    /// Running total of file reads.
+    pub file: FileUsage,
+    /// Memory usage of FMOD.
+    pub memory: memory::Stats,
+    /// Output format of the software mixer.
+    pub software_format: SoftwareFormat,
+}
+
+impl fmt::Display for Telemetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Telemetry {
+            channels,
+            cpu,
+            file,
+            memory,
+            software_format,
+        } = self;
+        writeln!(f, "Channels Playing {} : {}", channels.all, channels.real)?;
+        writeln!(
+            f,
+            "CPU Usage: DSP {:.2}% Stream {:.2}% Geometry {:.2}% Update {:.2}% Convolution {:.2}% {:.2}%",
+            cpu.dsp, cpu.stream, cpu.geometry, cpu.update, cpu.convolution1, cpu.convolution2,
+        )?;
+        writeln!(
+            f,
+            "File Usage: Sample {} Stream {} Other {} bytes",
+            file.sample_bytes_read, file.stream_bytes_read, file.other_bytes_read,
+        )?;
+        writeln!(
+            f,
+            "Memory Usage: {} bytes (Max {} bytes)",
+            memory.current_alloced, memory.max_alloced,
+        )?;
+        write!(
+            f,
+            "Software Format: {} Hz {:?}",
+            software_format.sample_rate, software_format.speaker_mode,
+        )
+    }
+}
+
+/// Depth-first iterator over the DSP graph, created by
+/// [`System::iter_dsp_graph`].
+#[derive(Debug)]
+pub struct DspGraphIter<'a> {
+    stack: Vec<&'a Dsp>,
+    visited: HashSet<*const Dsp>,
+}
+
+impl<'a> Iterator for DspGraphIter<'a> {
+    type Item = Result<&'a Dsp>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let dsp = self.stack.pop()?;
+            if !self.visited.insert(dsp) {
+                continue;
+            }
+            let num_inputs = match dsp.get_num_inputs() {
+                Ok(num_inputs) => num_inputs,
+                Err(error) => return Some(Err(error)),
+            };
+            // push in reverse so that the first input is visited first
+            for index in (0..num_inputs).rev() {
+                match dsp.get_input(index) {
+                    Ok((input, _)) => self.stack.push(input),
+                    Err(error) => return Some(Err(error)),
+                }
+            }
+            return Some(Ok(dsp));
+        }
+    }
+}
@@ -1,7 +1,11 @@
 use {
+    cstr8::CString8,
     fmod::{raw::*, *},
     parking_lot::RwLockUpgradableReadGuard,
-    std::{hint::unreachable_unchecked, ptr},
+    std::{
+        fmt, hint::unreachable_unchecked, marker::PhantomData, mem::ManuallyDrop, ptr,
+        sync::atomic::Ordering, time::Duration,
+    },
 };
 
 /// # Lifetime management.
@@ -108,6 +112,9 @@ impl System {
     /// already being a niche use case, can be relegated to `unsafe` with some
     /// subtle pitfalls. These pitfalls are the same as when using FMOD's API
     /// directly, with the exception of FMOD.rs adding an implicit RAII release.
+    /// With debug assertions enabled, FMOD.rs does check that calls which take
+    /// multiple objects (e.g. [`System::play_sound`] and
+    /// [`ChannelControl::add_dsp`]) are given objects from the same system.
     ///
     /// If you would like to make the release explicit to avoid the implicit
     /// point of `unsafe`ty, you can [`Handle::leak`] all of your systems, and
@@ -138,6 +145,10 @@ impl System {
 
         let mut raw = ptr::null_mut();
         ffi!(FMOD_System_Create(&mut raw, FMOD_VERSION))?;
+        if let Err(error) = System::init_state(raw) {
+            FMOD_System_Release(raw);
+            yeet!(error);
+        }
         *system_count += 1;
         Ok(Handle::new(raw))
     }
@@ -190,10 +201,83 @@ impl System {
             flags,
             extra_driver_data as *mut _,
         ))?;
+        self.record_max_channels(Some(max_channels));
         Ok(())
     }
 
-    // TODO: safe init_ex wrappers for WavWriter[Nrt], PulseAudio
+    /// Initialize the system object, diagnosing common initialization
+    /// failures.
+    ///
+    /// This behaves like [System::init], but if it fails with
+    /// [Error::NetSocketError] while [InitFlags::ProfileEnable] is set, the
+    /// profiler couldn't listen on its
+    /// [port](AdvancedSettings::profile_port), so
+    /// [InitError::ProfilePortInUse] is returned. The most likely cause is
+    /// another FMOD application (e.g. a second instance of the game) already
+    /// listening on it.
+    ///
+    /// When this returns an error, the system is left uninitialized.
+    pub fn init_checked(&self, max_channels: i32, flags: InitFlags) -> Result<(), InitError> {
+        let error = match self.init(max_channels, flags) {
+            Ok(()) => return Ok(()),
+            Err(error) => error,
+        };
+
+        // the profiler is the only thing opening sockets during init, so
+        // this needs no probing of the port, which would race with whatever
+        // else is binding it
+        if error == Error::NetSocketError && flags.is_set(InitFlags::ProfileEnable) {
+            let port = match self.get_advanced_settings() {
+                Ok(settings) => settings.profile_port,
+                Err(_) => AdvancedSettings::default().profile_port,
+            };
+            return Err(InitError::ProfilePortInUse { port });
+        }
+
+        match error {
+            Error::OutputAllocated
+            | Error::OutputCreateBuffer
+            | Error::OutputDriverCall
+            | Error::OutputFormat
+            | Error::OutputInit
+            | Error::OutputNoDrivers => Err(InitError::OutputInit(error)),
+            _ => Err(InitError::Other(error)),
+        }
+    }
+
+    /// Initialize the system object with [OutputType::PulseAudio], showing
+    /// `app_name` as the application's name in the OS audio mixer.
+    ///
+    /// If PulseAudio isn't available, the system is instead initialized with
+    /// [OutputType::AutoDetect] (typically selecting [OutputType::Alsa]),
+    /// which has no use for the name, and [AppNameStatus::Ignored] is
+    /// returned. This overrides any output previously selected with
+    /// [System::set_output].
+    pub fn init_pulseaudio(
+        &self,
+        max_channels: i32,
+        flags: InitFlags,
+        app_name: &CStr8,
+    ) -> Result<AppNameStatus> {
+        if self.set_output(OutputType::PulseAudio).is_ok() {
+            // SAFETY: PulseAudio takes the application name, which is kept
+            // alive for the duration of the call
+            let result = unsafe { self.init_ex(max_channels, flags, app_name.as_ptr().cast()) };
+            match result {
+                Ok(()) => return Ok(AppNameStatus::Applied),
+                Err(Error::OutputInit | Error::OutputNoDrivers | Error::OutputDriverCall) => {},
+                Err(error) => return Err(error),
+            }
+        }
+
+        self.set_output(OutputType::AutoDetect)?;
+        self.init(max_channels, flags)?;
+        Ok(AppNameStatus::Ignored {
+            output: self.get_output()?,
+        })
+    }
+
+    // TODO: safe init_ex wrappers for WavWriter
 
     /// Close the connection to the output and return to an uninitialized state
     /// without releasing the object.
@@ -205,9 +289,63 @@ impl System {
     ///
     /// Closing renders objects created with this System invalid. Make sure any
     /// Sound, ChannelGroup, Geometry and DSP objects are released before
-    /// calling this.
+    /// calling this. Objects [registered](crate::registry) on the system are
+    /// released by this.
     pub unsafe fn close(&self) -> Result {
+        self.check_mixer_running()?;
+        if let Some(state) = self.state() {
+            state.registry.write().release_all()?;
+        }
         ffi!(FMOD_System_Close(self.as_raw()))?;
+        self.record_max_channels(None);
+        Ok(())
+    }
+
+    /// Close the system, returning a [ClosedSystem] which allows changing
+    /// pre-initialize settings before it is [reinitialized](ClosedSystem::reinit).
+    ///
+    /// In debug builds, FMOD.rs tracks the objects created with each system,
+    /// and this returns [CloseError::ObjectsStillAlive] with their count
+    /// rather than closing the system if any of them haven't been released
+    /// (including [leaked](Handle::leak) ones). Objects [registered](crate::registry) on
+    /// the system are owned by it, so they aren't counted, and are released.
+    /// [Handle::reconfigure] is a safe alternative for an owned system.
+    ///
+    /// ```rust,ignore
+    /// // the output device changed its sample rate; release everything, then
+    /// let closed = unsafe { system.close_scope()? };
+    /// closed.set_software_format(SoftwareFormat {
+    ///     sample_rate: new_rate,
+    ///     ..system.get_software_format()?
+    /// })?;
+    /// closed.reinit(max_channels, InitFlags::Normal)?;
+    /// // and recreate sounds, DSPs, and channel groups
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// As with [System::close], all objects created with this System must be
+    /// released first. The check above doesn't cover references to objects
+    /// owned by the system itself, such as the
+    /// [master channel group](System::get_master_channel_group).
+    pub unsafe fn close_scope(&self) -> Result<ClosedSystem<'_>, CloseError> {
+        self.check_no_objects_alive()?;
+        self.close().map_err(CloseError::Other)?;
+        Ok(ClosedSystem { system: self })
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn check_no_objects_alive(&self) -> Result<(), CloseError> {
+        Ok(())
+    }
+
+    #[cfg(debug_assertions)]
+    fn check_no_objects_alive(&self) -> Result<(), CloseError> {
+        let count = crate::handle::count_derived(self.as_raw() as usize);
+        if count != 0 {
+            whoops!(no_panic: "closing {self:?} with {count} objects still alive");
+            return Err(CloseError::ObjectsStillAlive { count });
+        }
         Ok(())
     }
 
@@ -218,11 +356,17 @@ impl System {
         /// [`System::close`] before this function is not necessary.
         pub unsafe fn raw_release(raw: *mut FMOD_SYSTEM) -> FMOD_RESULT {
             let mut system_count = GLOBAL_SYSTEM_STATE.write();
+            let state = System::take_state(raw);
             let result = FMOD_System_Release(raw);
             if result == FMOD_OK {
+                drop(state);
+                #[cfg(debug_assertions)]
+                crate::handle::untrack_all_derived(raw as usize);
                 *system_count -= 1;
                 FMOD_OK
             } else {
+                // the system is still alive and refers to its state
+                let _ = state.map(Box::into_raw);
                 result
             }
         }
@@ -253,10 +397,55 @@ impl System {
     /// stream engine. Combining this with the non realtime output will mean
     /// smoother captured output.
     pub fn update(&self) -> Result {
+        self.check_mixer_running()?;
         ffi!(FMOD_System_Update(self.as_raw()))?;
         Ok(())
     }
 
+    /// Renders `duration` of audio faster than realtime by calling
+    /// [System::update] repeatedly.
+    ///
+    /// The output must be [OutputType::NoSoundNrt] or
+    /// [OutputType::WavWriterNrt], where each update mixes one DSP buffer, so
+    /// the number of updates is derived from the
+    /// [DSP buffer length](System::get_dsp_buffer_size) and the
+    /// [software format](System::get_software_format)'s sample rate. The
+    /// rendered length is rounded up to a whole number of DSP buffers.
+    ///
+    /// The system should be initialized with [InitFlags::StreamFromUpdate],
+    /// otherwise the stream thread cannot keep up with the mixer and streams
+    /// will skip and stutter in the rendered output.
+    ///
+    /// Returns [Error::Unsupported] if the output is not a non-realtime
+    /// output type.
+    ///
+    /// See [`util::render_offline`] to create a system and render a timeline
+    /// of actions to a wav file.
+    pub fn render_offline(&self, duration: Duration) -> Result {
+        for _ in 0..self.offline_blocks(duration)? {
+            self.update()?;
+        }
+        Ok(())
+    }
+
+    /// The number of DSP buffers [System::render_offline] mixes to render
+    /// `duration`, which is also the index of the buffer at whose start
+    /// `duration` into the rendering is reached.
+    pub(crate) fn offline_blocks(&self, duration: Duration) -> Result<u64> {
+        let output = self.get_output()?;
+        if !matches!(output, OutputType::NoSoundNrt | OutputType::WavWriterNrt) {
+            whoops!(no_panic: "System::render_offline called with realtime output {output:?}");
+            yeet!(Error::Unsupported);
+        }
+
+        let (buffer_length, _) = self.get_dsp_buffer_size()?;
+        let sample_rate = self.get_software_format()?.sample_rate;
+        match blocks_for(duration, sample_rate, buffer_length) {
+            Some(blocks) => Ok(blocks),
+            None => Err(Error::Internal),
+        }
+    }
+
     /// Suspend mixer thread and relinquish usage of audio hardware while
     /// maintaining internal state.
     ///
@@ -266,6 +455,8 @@ impl System {
     /// All internal state will be maintained, i.e. created sound and channels
     /// will stay available in memory.
     ///
+    /// See [System::suspend_mixer] for a safe alternative.
+    ///
     /// # Safety
     ///
     /// No FMOD API calls may be made until [System::mixer_resume] is called.
@@ -293,6 +484,261 @@ impl System {
         ffi!(FMOD_System_MixerResume(self.as_raw()))?;
         Ok(())
     }
+
+    /// Suspend the mixer thread and relinquish usage of audio hardware until
+    /// the returned guard is dropped.
+    ///
+    /// This is the safe version of [System::mixer_suspend] and
+    /// [System::mixer_resume]. While the guard is alive, the functions of this
+    /// system which wait on the mixer ([System::update], [System::close],
+    /// [System::lock_dsp], playing or creating channels, and suspending the
+    /// mixer again) fail with [Error::MixerSuspended] rather
+    /// than risking a deadlock. Other systems are unaffected, and releasing
+    /// objects is never rejected. The guard cannot be sent to another thread,
+    /// as the mixer must be resumed on the thread which suspended it.
+    ///
+    /// On mobile platforms, suspend the mixer when the application is
+    /// backgrounded (e.g. Android's `Activity.onPause`) and keep the guard
+    /// until it is foregrounded again (e.g. `Activity.onResume`):
+    ///
+    /// ```rust,ignore
+    /// // on pause
+    /// self.mixer_suspended = Some(system.suspend_mixer()?);
+    /// // on resume
+    /// self.mixer_suspended = None;
+    /// ```
+    pub fn suspend_mixer(&self) -> Result<MixerSuspendGuard<'_>> {
+        self.check_mixer_running()?;
+        unsafe { self.mixer_suspend()? };
+        if let Some(state) = self.state() {
+            state.mixer_suspended.store(true, Ordering::Release);
+        }
+        Ok(MixerSuspendGuard {
+            system: self,
+            _not_send: PhantomData,
+        })
+    }
+
+    /// Fails with [Error::MixerSuspended] while a [MixerSuspendGuard] for this
+    /// system is alive.
+    pub(crate) fn check_mixer_running(&self) -> Result {
+        match self.state() {
+            Some(state) if state.mixer_suspended.load(Ordering::Acquire) => {
+                Err(Error::MixerSuspended)
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Handle<'_, System> {
+    /// Close the system, run `configure` on the uninitialized system, and
+    /// initialize it again.
+    ///
+    /// This is how pre-initialize settings, such as
+    /// [System::set_software_format], are changed at runtime. The system is
+    /// reinitialized even if `configure` fails, in which case its error is
+    /// returned after reinitializing.
+    ///
+    /// Taking the owning handle mutably ensures that nothing borrowed from the
+    /// system, including the
+    /// [master channel group](System::get_master_channel_group), is alive.
+    /// Objects which escape that borrow, such as those created in callbacks,
+    /// are checked as by [System::close_scope] in debug builds: if any haven't
+    /// been released, [CloseError::ObjectsStillAlive] is returned without
+    /// closing the system.
+    ///
+    /// A [leaked](Handle::leak) system can't be reconfigured this way; use
+    /// [System::close_scope] instead.
+    pub fn reconfigure(
+        &mut self,
+        max_channels: i32,
+        flags: InitFlags,
+        configure: impl FnOnce(&System) -> Result,
+    ) -> Result<(), CloseError> {
+        // SAFETY: the exclusive borrow rules out references to objects of the
+        // system, and close_scope checks for objects which aren't borrowed
+        let closed = unsafe { self.close_scope()? };
+        let configured = configure(closed.system);
+        closed
+            .reinit(max_channels, flags)
+            .map_err(CloseError::Other)?;
+        configured.map_err(CloseError::Other)
+    }
+}
+
+/// A [System] which has been closed by [`System::close_scope`], and can be
+/// configured before being initialized again.
+///
+/// If this is dropped without calling [`reinit`](Self::reinit), the system
+/// remains uninitialized.
+#[derive(Debug)]
+pub struct ClosedSystem<'a> {
+    system: &'a System,
+}
+
+impl<'a> ClosedSystem<'a> {
+    /// See [System::set_software_format].
+    pub fn set_software_format(&self, format: SoftwareFormat) -> Result {
+        self.system.set_software_format(format)
+    }
+
+    /// See [System::set_dsp_buffer_size].
+    pub fn set_dsp_buffer_size(&self, buffer_size: DspBufferSize) -> Result {
+        self.system.set_dsp_buffer_size(buffer_size)
+    }
+
+    /// See [System::set_software_channels].
+    pub fn set_software_channels(&self, num_software_channels: i32) -> Result {
+        self.system.set_software_channels(num_software_channels)
+    }
+
+    /// See [System::set_advanced_settings].
+    pub fn set_advanced_settings(&self, advanced_settings: AdvancedSettings) -> Result {
+        self.system.set_advanced_settings(advanced_settings)
+    }
+
+    /// Initialize the system again; see [System::init].
+    pub fn reinit(self, max_channels: i32, flags: InitFlags) -> Result<&'a System> {
+        self.system.init(max_channels, flags)?;
+        Ok(self.system)
+    }
+}
+
+/// Resumes the mixer when dropped, created by [`System::suspend_mixer`].
+#[derive(Debug)]
+pub struct MixerSuspendGuard<'a> {
+    system: &'a System,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl MixerSuspendGuard<'_> {
+    /// Resume the mixer, reporting any error.
+    ///
+    /// Dropping the guard also resumes the mixer, but logs any error instead.
+    pub fn resume(self) -> Result {
+        let this = ManuallyDrop::new(self);
+        this.resume_inner()
+    }
+
+    fn resume_inner(&self) -> Result {
+        if let Some(state) = self.system.state() {
+            state.mixer_suspended.store(false, Ordering::Release);
+        }
+        unsafe { self.system.mixer_resume() }
+    }
+}
+
+impl Drop for MixerSuspendGuard<'_> {
+    fn drop(&mut self) {
+        if let Err(error) = self.resume_inner() {
+            whoops!("Error resuming mixer: {error}");
+        }
+    }
+}
+
+/// Whether the application name passed to [`System::init_pulseaudio`] is
+/// being used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppNameStatus {
+    /// PulseAudio is in use and displays the application name.
+    Applied,
+    /// PulseAudio isn't available, so another output is in use and the
+    /// application name is ignored.
+    Ignored {
+        /// The output in use instead.
+        output: OutputType,
+    },
+}
+
+/// An error returned by [`System::close_scope`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseError {
+    /// Objects created with the system haven't been released, so closing it
+    /// would leave them dangling.
+    ObjectsStillAlive {
+        /// The number of objects still alive.
+        count: usize,
+    },
+    /// Closing failed for another reason.
+    Other(Error),
+}
+
+impl std::error::Error for CloseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CloseError::ObjectsStillAlive { .. } => None,
+            CloseError::Other(error) => Some(error),
+        }
+    }
+}
+
+impl fmt::Display for CloseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CloseError::ObjectsStillAlive { count } => {
+                write!(f, "{count} objects created with the system are still alive")
+            },
+            CloseError::Other(error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+impl From<CloseError> for Error {
+    fn from(error: CloseError) -> Self {
+        match error {
+            CloseError::ObjectsStillAlive { .. } => Error::ObjectsStillAlive,
+            CloseError::Other(error) => error,
+        }
+    }
+}
+
+/// An error diagnosed by [`System::init_checked`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitError {
+    /// [InitFlags::ProfileEnable] was set, but the profiler could not listen
+    /// on its port, most likely because another FMOD application is already
+    /// using it. Initializing without the flag, or with a different
+    /// [AdvancedSettings::profile_port], will succeed.
+    ProfilePortInUse {
+        /// The port the profiler tried to listen on.
+        port: u16,
+    },
+    /// The output device could not be initialized.
+    OutputInit(Error),
+    /// Initialization failed for another reason.
+    Other(Error),
+}
+
+impl std::error::Error for InitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InitError::ProfilePortInUse { .. } => None,
+            InitError::OutputInit(error) | InitError::Other(error) => Some(error),
+        }
+    }
+}
+
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InitError::ProfilePortInUse { port } => write!(
+                f,
+                "profiler port {port} is unavailable; is another FMOD application running?"
+            ),
+            InitError::OutputInit(error) => write!(f, "error initializing output: {error}"),
+            InitError::Other(error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+impl From<InitError> for Error {
+    fn from(error: InitError) -> Self {
+        match error {
+            InitError::ProfilePortInUse { .. } => Error::NetSocketError,
+            InitError::OutputInit(error) | InitError::Other(error) => error,
+        }
+    }
 }
 
 fmod_flags! {
@@ -329,3 +775,293 @@ fmod_flags! {
         MemoryTracking         = FMOD_INIT_MEMORY_TRACKING,
     }
 }
+
+/// A builder to create and initialize a [System].
+///
+/// Several settings must be applied after creating the system but before
+/// calling [System::init], and setting them at the wrong time fails with
+/// [Error::Initialized]. The builder records the settings, then
+/// [creates](System::new) the system and applies them in the correct order
+/// before initializing it.
+///
+/// ```rust,ignore
+/// let system = fmod::SystemBuilder::new()
+///     .software_format(SoftwareFormat {
+///         sample_rate: 44100,
+///         ..Default::default()
+///     })
+///     .software_channels(32)
+///     .init(512, InitFlags::Normal)?;
+/// ```
+///
+/// [System::new] remains available for full manual control.
+#[derive(Debug, Default)]
+pub struct SystemBuilder {
+    output: Option<OutputType>,
+    software_format: Option<SoftwareFormat>,
+    software_channels: Option<i32>,
+    dsp_buffer_size: Option<DspBufferSize>,
+    advanced_settings: Option<AdvancedSettings>,
+    file_system: Option<(SetFileSystem, i32)>,
+    callback: Option<(SetCallback, SystemCallbackType)>,
+    app_name: Option<CString8>,
+}
+
+type SetFileSystem = fn(&System, i32) -> Result;
+type SetCallback = fn(&System, SystemCallbackType) -> Result;
+
+impl SystemBuilder {
+    /// Creates a new builder with FMOD's default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the output type, see [System::set_output].
+    pub fn output(mut self, output: OutputType) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Sets the software mixer format, see [System::set_software_format].
+    pub fn software_format(mut self, format: SoftwareFormat) -> Self {
+        self.software_format = Some(format);
+        self
+    }
+
+    /// Sets the number of real voices, see [System::set_software_channels].
+    pub fn software_channels(mut self, num_software_channels: i32) -> Self {
+        self.software_channels = Some(num_software_channels);
+        self
+    }
+
+    /// Sets the mixer buffer size, see [System::set_dsp_buffer_size].
+    pub fn dsp_buffer_size(mut self, buffer_size: DspBufferSize) -> Self {
+        self.dsp_buffer_size = Some(buffer_size);
+        self
+    }
+
+    /// Sets advanced settings, see [System::set_advanced_settings].
+    pub fn advanced_settings(mut self, advanced_settings: AdvancedSettings) -> Self {
+        self.advanced_settings = Some(advanced_settings);
+        self
+    }
+
+    /// Sets a file system, see [System::set_file_system_sync].
+    pub fn file_system_sync<FS: file::SyncFileSystem>(mut self, block_align: i32) -> Self {
+        self.file_system = Some((
+            |system, block_align| system.set_file_system_sync::<FS>(block_align),
+            block_align,
+        ));
+        self
+    }
+
+    /// Sets the system callback, see [System::set_callback].
+    pub fn callback<C: SystemCallback>(mut self, mask: SystemCallbackType) -> Self {
+        self.callback = Some((|system, mask| system.set_callback::<C>(mask), mask));
+        self
+    }
+
+    /// Sets the application name shown in the OS audio mixer, see
+    /// [System::init_pulseaudio].
+    ///
+    /// This overrides [`output`](Self::output), and is ignored (with a log
+    /// message) if PulseAudio isn't available.
+    pub fn app_name(mut self, app_name: impl Into<CString8>) -> Self {
+        self.app_name = Some(app_name.into());
+        self
+    }
+
+    /// Creates the system, applies the recorded settings, and initializes it.
+    ///
+    /// If FMOD rejects a setting, the returned [SystemBuilderError] identifies
+    /// which one; the partially set up system is released.
+    ///
+    /// See [System::init] for the meaning of the parameters.
+    pub fn init(
+        self,
+        max_channels: i32,
+        flags: InitFlags,
+    ) -> Result<Handle<'static, System>, SystemBuilderError> {
+        macro_rules! apply {
+            ($setting:ident, $result:expr) => {
+                if let Err(error) = $result {
+                    let setting = SystemSetting::$setting;
+                    whoops!(no_panic: "SystemBuilder: FMOD rejected {setting}: {error}");
+                    yeet!(SystemBuilderError { setting, error });
+                }
+            };
+        }
+
+        let system = System::new().map_err(|error| SystemBuilderError {
+            setting: SystemSetting::Create,
+            error,
+        })?;
+
+        if let Some(output) = self.output {
+            apply!(Output, system.set_output(output));
+        }
+        if let Some(format) = self.software_format {
+            apply!(SoftwareFormat, system.set_software_format(format));
+        }
+        if let Some(channels) = self.software_channels {
+            apply!(SoftwareChannels, system.set_software_channels(channels));
+        }
+        if let Some(buffer_size) = self.dsp_buffer_size {
+            apply!(DspBufferSize, system.set_dsp_buffer_size(buffer_size));
+        }
+        if let Some(advanced_settings) = self.advanced_settings {
+            apply!(
+                AdvancedSettings,
+                system.set_advanced_settings(advanced_settings)
+            );
+        }
+        if let Some((set_file_system, block_align)) = self.file_system {
+            apply!(FileSystem, set_file_system(&system, block_align));
+        }
+        if let Some((set_callback, mask)) = self.callback {
+            apply!(Callback, set_callback(&system, mask));
+        }
+        match self.app_name {
+            None => apply!(Init, system.init(max_channels, flags)),
+            Some(app_name) => match system.init_pulseaudio(max_channels, flags, &app_name) {
+                Ok(AppNameStatus::Applied) => {},
+                Ok(AppNameStatus::Ignored { output }) => {
+                    #[cfg(feature = "log")]
+                    log::info!("SystemBuilder: app_name is ignored by {output:?} output");
+                    #[cfg(not(feature = "log"))]
+                    let _ = output;
+                },
+                Err(error) => apply!(Init, Err::<(), _>(error)),
+            },
+        }
+
+        Ok(system)
+    }
+}
+
+/// A setting applied by [SystemBuilder::init].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SystemSetting {
+    /// Creating the system, see [System::new].
+    Create,
+    /// See [SystemBuilder::output].
+    Output,
+    /// See [SystemBuilder::software_format].
+    SoftwareFormat,
+    /// See [SystemBuilder::software_channels].
+    SoftwareChannels,
+    /// See [SystemBuilder::dsp_buffer_size].
+    DspBufferSize,
+    /// See [SystemBuilder::advanced_settings].
+    AdvancedSettings,
+    /// See [SystemBuilder::file_system_sync].
+    FileSystem,
+    /// See [SystemBuilder::callback].
+    Callback,
+    /// Initializing the system, see [System::init].
+    Init,
+}
+
+impl fmt::Display for SystemSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SystemSetting::Create => "create",
+            SystemSetting::Output => "output",
+            SystemSetting::SoftwareFormat => "software_format",
+            SystemSetting::SoftwareChannels => "software_channels",
+            SystemSetting::DspBufferSize => "dsp_buffer_size",
+            SystemSetting::AdvancedSettings => "advanced_settings",
+            SystemSetting::FileSystem => "file_system",
+            SystemSetting::Callback => "callback",
+            SystemSetting::Init => "init",
+        })
+    }
+}
+
+/// An error returned by [SystemBuilder::init], identifying the setting FMOD
+/// rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemBuilderError {
+    /// The setting which failed to apply.
+    pub setting: SystemSetting,
+    /// The error FMOD returned.
+    pub error: Error,
+}
+
+impl std::error::Error for SystemBuilderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl fmt::Display for SystemBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FMOD rejected {}: {}", self.setting, self.error)
+    }
+}
+
+impl From<SystemBuilderError> for Error {
+    fn from(error: SystemBuilderError) -> Self {
+        error.error
+    }
+}
+
+/// The number of `buffer_length` buffers needed to cover `duration` at
+/// `sample_rate`, rounded up. Integer math, so that offline rendering is
+/// deterministic.
+fn blocks_for(duration: Duration, sample_rate: i32, buffer_length: u32) -> Option<u64> {
+    let sample_rate = u128::try_from(sample_rate).ok().filter(|&rate| rate > 0)?;
+    let buffer_length = u128::from(buffer_length);
+    if buffer_length == 0 {
+        return None;
+    }
+    let samples = (duration.as_nanos() * sample_rate).div_ceil(1_000_000_000);
+    u64::try_from(samples.div_ceil(buffer_length)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_blocks_round_up() {
+        let second = Duration::from_secs(1);
+        assert_eq!(blocks_for(Duration::ZERO, 48000, 1024), Some(0));
+        assert_eq!(blocks_for(second, 48000, 1024), Some(47));
+        assert_eq!(blocks_for(second, 48000, 1000), Some(48));
+        assert_eq!(blocks_for(Duration::from_nanos(1), 48000, 1024), Some(1));
+        let block = Duration::from_millis(20);
+        assert_eq!(blocks_for(block, 50000, 1000), Some(1));
+        assert_eq!(
+            blocks_for(block + Duration::from_nanos(1), 50000, 1000),
+            Some(2)
+        );
+        assert_eq!(blocks_for(second, 0, 1024), None);
+        assert_eq!(blocks_for(second, 48000, 0), None);
+    }
+
+    #[test]
+    fn builder_error_identifies_setting() {
+        let error = SystemBuilderError {
+            setting: SystemSetting::SoftwareFormat,
+            error: Error::InvalidParam,
+        };
+        assert!(error
+            .to_string()
+            .starts_with("FMOD rejected software_format: "));
+        assert_eq!(Error::from(error), Error::InvalidParam);
+    }
+
+    #[test]
+    fn close_error_carries_count() {
+        let error = CloseError::ObjectsStillAlive { count: 3 };
+        assert_eq!(
+            error.to_string(),
+            "3 objects created with the system are still alive",
+        );
+        assert_eq!(Error::from(error), Error::ObjectsStillAlive);
+        let error = CloseError::Other(Error::Internal);
+        assert_eq!(Error::from(error), Error::Internal);
+    }
+}
@@ -58,7 +58,7 @@ impl System {
     pub fn get_record_driver_name(&self, id: i32, name: &mut String) -> Result {
         unsafe {
             fmod_get_string(name, |buf| {
-                ffi!(FMOD_System_GetDriverInfo(
+                ffi!(FMOD_System_GetRecordDriverInfo(
                     self.as_raw(),
                     id,
                     buf.as_mut_ptr().cast(),
@@ -67,11 +67,64 @@ impl System {
                     ptr::null_mut(),
                     ptr::null_mut(),
                     ptr::null_mut(),
+                    ptr::null_mut(),
+                ))
+            })
+        }
+    }
+
+    /// Retrieves the name and identification information about an audio
+    /// device specified by its index, and specific to the output mode.
+    ///
+    /// Unlike calling both [System::get_record_driver_name] and
+    /// [System::get_record_driver_info], this retrieves everything with a
+    /// single FFI call, unless the name is too long to fit in a 256 byte
+    /// buffer.
+    pub fn get_record_driver_details(&self, id: i32) -> Result<Driver> {
+        unsafe {
+            Driver::query(|buf, info| {
+                ffi!(FMOD_System_GetRecordDriverInfo(
+                    self.as_raw(),
+                    id,
+                    buf.as_mut_ptr().cast(),
+                    buf.len() as _,
+                    info.guid.as_raw_mut(),
+                    &mut info.system_rate,
+                    info.speaker_mode.as_raw_mut(),
+                    &mut info.speaker_mode_channels,
+                    info.state.as_raw_mut(),
                 ))
             })
         }
     }
 
+    /// Enumerates the available recording devices for this output mode.
+    ///
+    /// This is a convenience for calling [System::get_record_driver_details]
+    /// for each driver index up to the number of
+    /// [available](NumDrivers::available) drivers. The number of drivers is
+    /// queried once, when this function is called; when the device list
+    /// changes, call this again to get an up-to-date enumeration.
+    ///
+    /// Drivers are yielded in index order, so use [`Iterator::enumerate`] to
+    /// get the id to pass to [System::record_start]. Unplugged devices are
+    /// included; check [`DriverInfo::is_connected`] to skip them:
+    ///
+    /// ```rust,no_run
+    /// # let system = fmod::System::new()?;
+    /// for (id, driver) in system.record_drivers()?.enumerate() {
+    ///     let driver = driver?;
+    ///     if driver.info.is_connected() {
+    ///         println!("{id}: {}", driver.name);
+    ///     }
+    /// }
+    /// # Ok::<(), fmod::Error>(())
+    /// ```
+    pub fn record_drivers(&self) -> Result<impl Iterator<Item = Result<Driver>> + '_> {
+        let num_drivers = self.get_record_num_drivers()?.available;
+        Ok((0..num_drivers).map(|id| self.get_record_driver_details(id)))
+    }
+
     /// Retrieves the current recording position of the record buffer in PCM
     /// samples.
     ///
@@ -161,6 +214,18 @@ fmod_flags! {
     }
 }
 
+impl DriverState {
+    /// Whether the device is currently plugged in.
+    pub fn is_connected(self) -> bool {
+        self.is_set(DriverState::Connected)
+    }
+
+    /// Whether the device is the user's preferred choice.
+    pub fn is_default(self) -> bool {
+        self.is_set(DriverState::Default)
+    }
+}
+
 /// Number of recording devices available.
 #[derive(Debug)]
 pub struct NumDrivers {
@@ -1,6 +1,7 @@
 use {
     fmod::{raw::*, *},
-    std::{num::NonZeroU64, ptr},
+    parking_lot::Mutex,
+    std::{collections::HashMap, fmt, ptr},
 };
 
 /// # Runtime control.
@@ -98,6 +99,26 @@ impl System {
         Ok(properties)
     }
 
+    /// Sets parameters for a global reverb instance, which are cleared when
+    /// the returned [ReverbSlot] is dropped.
+    ///
+    /// Clearing the properties of an instance deletes its physical reverb
+    /// [Dsp], freeing its memory and CPU cost. As with
+    /// [System::set_reverb_properties], if a [Reverb3d] using the instance is
+    /// still present, the physical reverb is recreated upon the next
+    /// [System::update].
+    pub fn reserve_reverb_slot(
+        &self,
+        instance: i32,
+        properties: ReverbProperties,
+    ) -> Result<ReverbSlot<'_>> {
+        self.set_reverb_properties(instance, Some(&properties))?;
+        Ok(ReverbSlot {
+            system: self,
+            instance,
+        })
+    }
+
     /// Connect the output of the specified ChannelGroup to an audio port on the
     /// output driver.
     ///
@@ -106,6 +127,24 @@ impl System {
     /// music streams. See the Port Support section (where applicable) of each
     /// platform's getting started guide found in the [platform details] chapter.
     ///
+    /// The meaning of `port_index` depends on the platform and `port_type`,
+    /// see [PortType]. Port types with a single port, such as
+    /// [PortType::Music], take `None`; per-user ports usually take the
+    /// platform's user ID, or the controller number, as a
+    /// [PortIndex::from_index].
+    ///
+    /// ```rust,ignore
+    /// // route voice lines to the speaker of the second controller, on
+    /// // platforms whose output supports controller ports
+    /// let voice = system.create_channel_group(fmod::cstr8!("voice"))?;
+    /// system.attach_channel_group_to_port(
+    ///     PortType::Controller,
+    ///     Some(PortIndex::from_index(1)),
+    ///     &voice,
+    ///     false,
+    /// )?;
+    /// ```
+    ///
     /// [platform details]: https://fmod.com/resources/documentation-api?version=2.02&page=platforms.html
     pub fn attach_channel_group_to_port(
         &self,
@@ -117,13 +156,32 @@ impl System {
         ffi!(FMOD_System_AttachChannelGroupToPort(
             self.as_raw(),
             port_type.into_raw(),
-            port_index.map(PortIndex::into_raw).unwrap_or(0),
+            port_index.unwrap_or(PortIndex::NONE).into_raw(),
             group.as_raw(),
             pass_thru as FMOD_BOOL,
         ))?;
+        if let Some(state) = self.state() {
+            let port_index = port_index.filter(|index| !index.is_none());
+            (state.port_attachments).attach(group.as_raw(), port_type, port_index);
+        }
         Ok(())
     }
 
+    /// Retrieves the audio port that the output of the specified ChannelGroup
+    /// is connected to, if any.
+    ///
+    /// FMOD doesn't provide this, so attachments made with
+    /// [System::attach_channel_group_to_port] are tracked by FMOD.rs. Groups
+    /// attached through the raw API aren't reported.
+    pub fn get_channel_group_port(
+        &self,
+        group: &ChannelGroup,
+    ) -> Result<Option<(PortType, Option<PortIndex>)>> {
+        Ok(self
+            .state()
+            .and_then(|state| state.port_attachments.get(group.as_raw())))
+    }
+
     /// Disconnect the output of the specified ChannelGroup from an audio port
     /// on the output driver.
     ///
@@ -134,10 +192,79 @@ impl System {
             self.as_raw(),
             channel_group.as_raw(),
         ))?;
+        if let Some(state) = self.state() {
+            state.port_attachments.detach(channel_group.as_raw());
+        }
         Ok(())
     }
 }
 
+/// A global reverb instance in use, created by [`System::reserve_reverb_slot`].
+///
+/// When dropped, the instance's properties are cleared, deleting its physical
+/// reverb [Dsp].
+#[derive(Debug)]
+pub struct ReverbSlot<'a> {
+    system: &'a System,
+    instance: i32,
+}
+
+impl ReverbSlot<'_> {
+    /// The index of the reverb instance.
+    pub fn instance(&self) -> i32 {
+        self.instance
+    }
+
+    /// Sets parameters for the reverb instance; see
+    /// [System::set_reverb_properties].
+    pub fn set_properties(&self, properties: &ReverbProperties) -> Result {
+        self.system
+            .set_reverb_properties(self.instance, Some(properties))
+    }
+
+    /// Retrieves the current parameters of the reverb instance.
+    pub fn get_properties(&self) -> Result<ReverbProperties> {
+        self.system.get_reverb_properties(self.instance)
+    }
+}
+
+impl Drop for ReverbSlot<'_> {
+    fn drop(&mut self) {
+        if let Err(error) = self.system.set_reverb_properties(self.instance, None) {
+            whoops!("Error freeing reverb instance {}: {error}", self.instance);
+        }
+    }
+}
+
+/// The ports a system's groups are attached to, see
+/// [System::get_channel_group_port].
+#[derive(Default)]
+pub(crate) struct PortAttachments {
+    ports: Mutex<HashMap<usize, (PortType, Option<PortIndex>)>>,
+}
+
+impl PortAttachments {
+    fn attach(
+        &self,
+        group: *mut FMOD_CHANNELGROUP,
+        port_type: PortType,
+        port_index: Option<PortIndex>,
+    ) {
+        self.ports
+            .lock()
+            .insert(group as usize, (port_type, port_index));
+    }
+
+    fn get(&self, group: *mut FMOD_CHANNELGROUP) -> Option<(PortType, Option<PortIndex>)> {
+        self.ports.lock().get(&(group as usize)).copied()
+    }
+
+    /// Called after detaching or releasing a group.
+    pub(crate) fn detach(&self, group: *mut FMOD_CHANNELGROUP) {
+        self.ports.lock().remove(&(group as usize));
+    }
+}
+
 fmod_struct! {
     /// Structure defining a reverb environment.
     ///
@@ -255,66 +382,127 @@ fmod_struct! {
 }
 
 /// Output type specific index for when there are multiple instances of a port type.
+///
+/// What the index means depends on the platform and [PortType]; typically it
+/// is the platform specific ID of the user whose device is used. Port types
+/// with a single port use [PortIndex::NONE].
 #[repr(transparent)]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct PortIndex(NonZeroU64);
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PortIndex(FMOD_PORT_INDEX);
 
 impl PortIndex {
+    /// No port index, for port types which have a single port.
+    pub const NONE: PortIndex = PortIndex(FMOD_PORT_INDEX_NONE as FMOD_PORT_INDEX);
+
     /// Flag to indicate the intended controller is associated with a VR headset.
     pub const VR_CONTROLLER_MASK: u64 = FMOD_PORT_INDEX_FLAG_VR_CONTROLLER;
 
-    /// Creates a new port index.
+    /// Creates a port index from a plain numeric index, such as a controller
+    /// number.
     ///
     /// # Panics
     ///
-    /// Panics if the index is zero or has any flag bits set (the top 4 bits).
+    /// Panics if the index has any flag bits set (the top 4 bits).
+    pub const fn from_index(index: u64) -> Self {
+        assert!(index < Self::VR_CONTROLLER_MASK);
+        Self(index)
+    }
+
+    /// Creates a port index, flagged for association with a VR headset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has any flag bits set (the top 4 bits).
+    pub const fn vr_controller(index: u64) -> Self {
+        assert!(index < Self::VR_CONTROLLER_MASK);
+        Self(index | Self::VR_CONTROLLER_MASK)
+    }
+
+    /// Creates a new port index.
+    #[deprecated = "use PortIndex::from_index, which also accepts index zero"]
     pub const fn new(index: u64) -> Self {
-        assert!(0 < index && index < Self::VR_CONTROLLER_MASK);
-        Self(unsafe { NonZeroU64::new_unchecked(index) })
+        Self::from_index(index)
     }
 
     /// Creates a new port index, flagged for association with a VR headset.
-    pub const fn new_vr(mut index: u64) -> Self {
-        let _ = Self::new(index);
-        index |= Self::VR_CONTROLLER_MASK;
-        Self(unsafe { NonZeroU64::new_unchecked(index) })
+    #[deprecated = "use PortIndex::vr_controller"]
+    pub const fn new_vr(index: u64) -> Self {
+        Self::vr_controller(index)
+    }
+
+    /// Retrieves if this is [PortIndex::NONE].
+    pub const fn is_none(self) -> bool {
+        self.0 == Self::NONE.0
     }
 
     /// Retrieves if this port is associated with a VR headset.
     pub const fn is_vr(self) -> bool {
-        (self.0.get() & Self::VR_CONTROLLER_MASK) != 0
+        !self.is_none() && (self.0 & Self::VR_CONTROLLER_MASK) != 0
+    }
+
+    /// Retrieves the untagged index value, or `None` for [PortIndex::NONE].
+    pub const fn index(self) -> Option<u64> {
+        if self.is_none() {
+            None
+        } else {
+            Some(self.0 & (Self::VR_CONTROLLER_MASK - 1))
+        }
     }
 
     /// Retrieves the untagged index value.
+    ///
+    /// [PortIndex::NONE] has no index, but this returns the untagged bits of
+    /// its raw value rather than distinguishing it.
+    #[deprecated = "use PortIndex::index, which distinguishes PortIndex::NONE"]
     pub const fn get(self) -> u64 {
-        self.0.get() & (Self::VR_CONTROLLER_MASK - 1)
+        self.0 & (Self::VR_CONTROLLER_MASK - 1)
+    }
+}
+
+impl fmt::Debug for PortIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.index() {
+            None => f.write_str("PortIndex::NONE"),
+            Some(index) if self.is_vr() => write!(f, "PortIndex::vr_controller({index})"),
+            Some(index) => write!(f, "PortIndex({index})"),
+        }
+    }
+}
+
+impl fmt::Display for PortIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.index() {
+            None => f.write_str("none"),
+            Some(index) if self.is_vr() => write!(f, "VR controller {index}"),
+            Some(index) => write!(f, "{index}"),
+        }
     }
 }
 
 impl PortIndex {
     raw! {
-        pub const fn from_raw(raw: FMOD_PORT_INDEX) -> Option<PortIndex> {
-            unsafe { ::std::mem::transmute(raw) }
+        pub const fn from_raw(raw: FMOD_PORT_INDEX) -> PortIndex {
+            PortIndex(raw)
         }
     }
     raw! {
-        pub const fn from_raw_ref(raw: &FMOD_PORT_INDEX) -> &Option<PortIndex> {
-            unsafe { &*(raw as *const FMOD_PORT_INDEX as *const Option<PortIndex> ) }
+        pub const fn from_raw_ref(raw: &FMOD_PORT_INDEX) -> &PortIndex {
+            unsafe { &*(raw as *const FMOD_PORT_INDEX as *const PortIndex) }
         }
     }
     raw! {
-        pub fn from_raw_mut(raw: &mut FMOD_PORT_INDEX) -> &mut Option<PortIndex> {
-            unsafe { &mut *(raw as *mut FMOD_PORT_INDEX as *mut Option<PortIndex> ) }
+        pub fn from_raw_mut(raw: &mut FMOD_PORT_INDEX) -> &mut PortIndex {
+            unsafe { &mut *(raw as *mut FMOD_PORT_INDEX as *mut PortIndex) }
         }
     }
     raw! {
         pub const fn into_raw(self) -> FMOD_PORT_INDEX {
-            unsafe { ::std::mem::transmute(self) }
+            self.0
         }
     }
     raw! {
         pub const fn as_raw(&self) -> &FMOD_PORT_INDEX {
-            unsafe { &*(self as *const PortIndex as *const FMOD_PORT_INDEX ) }
+            &self.0
         }
     }
 }
@@ -413,3 +601,48 @@ impl ReverbProperties {
     pub const SEWER_PIPE: Self =        reverb! {  2800.0,   14.0,  21.0, 5000.0,  14.0,  80.0,  60.0, 250.0, 0.0,  3400.0,  66.0,   1.2 };
     pub const UNDERWATER: Self =        reverb! {  1500.0,    7.0,  11.0, 5000.0,  10.0, 100.0, 100.0, 250.0, 0.0,   500.0,  92.0,   7.0 };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn port_index_values() {
+        assert_eq!(PortIndex::NONE.index(), None);
+        assert_eq!(PortIndex::from_index(0).index(), Some(0));
+        assert_eq!(PortIndex::from_index(3).get(), 3);
+        let vr = PortIndex::vr_controller(2);
+        assert!(vr.is_vr());
+        assert_eq!(vr.index(), Some(2));
+        assert_eq!(vr.get(), 2);
+        assert!(!PortIndex::NONE.is_vr());
+        assert_eq!(format!("{vr:?}"), "PortIndex::vr_controller(2)");
+        assert_eq!(PortIndex::NONE.to_string(), "none");
+    }
+
+    #[test]
+    fn detached_groups_are_forgotten() {
+        // fake addresses, never passed to FMOD
+        let (music, controller) = (0x1010 as *mut FMOD_CHANNELGROUP, 0x1020 as *mut _);
+        let ports = PortAttachments::default();
+        ports.attach(music, PortType::Music, None);
+        ports.attach(
+            controller,
+            PortType::Controller,
+            Some(PortIndex::from_index(1)),
+        );
+        assert_eq!(ports.get(music), Some((PortType::Music, None)));
+
+        // reattaching replaces the previous port
+        ports.attach(music, PortType::Voice, None);
+        assert_eq!(ports.get(music), Some((PortType::Voice, None)));
+
+        ports.detach(music);
+        assert_eq!(ports.get(music), None);
+        assert_eq!(
+            ports.get(controller),
+            Some((PortType::Controller, Some(PortIndex::from_index(1)))),
+        );
+    }
+}
@@ -1,10 +1,13 @@
 use {
+    crate::utils::catch_user_unwind,
     fmod::{raw::*, *},
+    parking_lot::Mutex,
     smart_default::SmartDefault,
     std::{
         borrow::Cow,
         ffi::{c_char, CStr},
         mem, ptr, slice,
+        sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
     },
 };
 
@@ -135,18 +138,7 @@ impl System {
     /// 512, 1024 or 2048 depedning on your latency requirements.
     ///
     /// The values in milliseconds and average latency expected from the
-    /// settings can be calculated using the following code:
-    ///
-    /// ```rust,ignore
-    /// let DspBufferSize { buffer_length, num_buffers } = system.get_dsp_buffer_size()?;
-    /// let SoftwareFormat { sample_rate, .. } = system.get_software_format()?;
-    ///
-    /// let ms = buffer_size.buffer_length as f32 * 1000.0 / software_format.sample_rate as f32;
-    ///
-    /// println!("Mixer blocksize        = {:.02}", ms);
-    /// println!("Mixer Total buffersize = {:.02}", ms * num_buffers);
-    /// println!("Mixer Average Latency  = {:.02}", ms * (num_buffers as f32 - 1.5));
-    /// ```
+    /// settings can be retrieved with [System::mixer_latency].
     pub fn set_dsp_buffer_size(&self, buffer_size: DspBufferSize) -> Result {
         let DspBufferSize {
             buffer_length,
@@ -190,6 +182,21 @@ impl System {
         Ok((bufferlength, numbuffers))
     }
 
+    /// Retrieves the block size, total buffer size, and average latency of
+    /// the FMOD software mixing engine in milliseconds, calculated from
+    /// [System::get_dsp_buffer_size] and [System::get_software_format].
+    pub fn mixer_latency(&self) -> Result<MixerLatency> {
+        let (buffer_length, num_buffers) = self.get_dsp_buffer_size()?;
+        let SoftwareFormat { sample_rate, .. } = self.get_software_format()?;
+
+        let block_ms = buffer_length as f32 * 1000.0 / sample_rate as f32;
+        Ok(MixerLatency {
+            block_ms,
+            total_ms: block_ms * num_buffers as f32,
+            average_ms: block_ms * (num_buffers as f32 - 1.5),
+        })
+    }
+
     /// Sets the default file buffer size for newly opened streams.
     ///
     /// Valid units are [TimeUnit::Ms], [Pcm](TimeUnit::Pcm),
@@ -303,10 +310,54 @@ impl System {
             &mut speaker_position.y,
             &mut active,
         ))?;
-        speaker_position.active = active != 1;
+        speaker_position.active = active != 0;
         Ok(speaker_position)
     }
 
+    /// Sets the positions of multiple speakers for the current speaker mode.
+    ///
+    /// The current positions are retrieved first, and if setting any position
+    /// fails, the positions which were already set are restored before the
+    /// error is returned, so the layout is never left partially applied.
+    ///
+    /// See [SpeakerLayout] for common layouts.
+    pub fn set_speaker_positions(&self, positions: &[(Speaker, SpeakerPosition)]) -> Result {
+        let previous = positions
+            .iter()
+            .map(|&(speaker, _)| Ok((speaker, self.get_speaker_position(speaker)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        for (applied, &(speaker, position)) in positions.iter().enumerate() {
+            if let Err(error) = self.set_speaker_position(speaker, position) {
+                for &(speaker, position) in previous[..applied].iter().rev() {
+                    if let Err(restore_error) = self.set_speaker_position(speaker, position) {
+                        whoops!(no_panic: "failed to restore position of {speaker:?}: {restore_error}");
+                    }
+                }
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Retrieves the positions of the [speakers](SpeakerMode::speakers) of
+    /// `mode`, in channel order.
+    ///
+    /// Positions are those of the current speaker mode, so `mode` should
+    /// usually be the speaker mode of [System::get_software_format]. Speaker
+    /// modes without a fixed layout, such as [SpeakerMode::Raw], have no
+    /// speakers.
+    pub fn get_speaker_positions(
+        &self,
+        mode: SpeakerMode,
+    ) -> Result<Vec<(Speaker, SpeakerPosition)>> {
+        mode.speakers()
+            .iter()
+            .map(|&speaker| Ok((speaker, self.get_speaker_position(speaker)?)))
+            .collect()
+    }
+
     /// Sets the global doppler scale, distance factor and log rolloff scale for
     /// all 3D sound in FMOD.
     ///
@@ -373,12 +424,51 @@ impl System {
     /// [Mode::LinearRolloff3d], [Mode::LinearSquareRolloff3d],
     /// [Mode::InverseTaperedRolloff3d], and [Mode::CustomRolloff3d].
     ///
-    /// Set to `None` to return control of distance attenuation to FMOD.
-    pub fn set_3d_rolloff_callback(&self, callback: Option<Rolloff3dCallback>) -> Result {
+    /// Use [System::clear_3d_rolloff_callback] to return control of distance
+    /// attenuation to FMOD.
+    pub fn set_3d_rolloff_callback<C: Rolloff3dCallback>(&self) -> Result {
         ffi!(FMOD_System_Set3DRolloffCallback(
             self.as_raw(),
-            mem::transmute::<Option<Rolloff3dCallback>, FMOD_3D_ROLLOFF_CALLBACK>(callback),
+            Some(rolloff_3d_callback::<C>),
         ))?;
+        if let Some(state) = self.state() {
+            state.rolloff_closure.replace(None);
+        }
+        Ok(())
+    }
+
+    /// Sets a closure to allow custom calculation of distance attenuation.
+    ///
+    /// This is [System::set_3d_rolloff_callback], but the closure can
+    /// capture state, such as distance scaling configuration. The closure is
+    /// called from the mixer thread, is kept until it is replaced, cleared,
+    /// or the system is released, and if it panics, the channel is played at
+    /// full volume.
+    pub fn set_3d_rolloff_closure(
+        &self,
+        f: impl Fn(&Channel, f32) -> f32 + Send + Sync + 'static,
+    ) -> Result {
+        let Some(state) = self.state() else {
+            whoops!(no_panic: "{self:?} has no FMOD.rs state");
+            return Err(Error::Internal);
+        };
+        // set first, so the callback never finds a stale closure
+        state.rolloff_closure.replace(Some(Box::new(f)));
+        ffi!(FMOD_System_Set3DRolloffCallback(
+            self.as_raw(),
+            Some(rolloff_3d_closure),
+        ))?;
+        Ok(())
+    }
+
+    /// Removes the callback set with [System::set_3d_rolloff_callback] or
+    /// [System::set_3d_rolloff_closure], returning control of distance
+    /// attenuation to FMOD.
+    pub fn clear_3d_rolloff_callback(&self) -> Result {
+        ffi!(FMOD_System_Set3DRolloffCallback(self.as_raw(), None))?;
+        if let Some(state) = self.state() {
+            state.rolloff_closure.replace(None);
+        }
         Ok(())
     }
 }
@@ -555,7 +645,23 @@ fmod_struct! {
 
 impl AdvancedSettings {
     /// ASIO channel names. Only valid after [System::init].
+    ///
+    /// Names which aren't valid UTF-8 are decoded lossily, replacing invalid
+    /// sequences with `U+FFFD`. ASIO drivers typically use the system ANSI
+    /// codepage, so non-English names may be corrupted; use
+    /// [`asio_channel_list_cstr`](Self::asio_channel_list_cstr) to decode
+    /// them with the correct codepage instead.
     pub fn asio_channel_list(&self) -> Option<impl Iterator<Item = Cow<'_, str>>> {
+        Some(
+            self.asio_channel_list_cstr()?
+                .map(CStr::to_bytes)
+                .map(String::from_utf8_lossy),
+        )
+    }
+
+    /// ASIO channel names, as provided by the driver. Only valid after
+    /// [System::init].
+    pub fn asio_channel_list_cstr(&self) -> Option<impl Iterator<Item = &CStr>> {
         if self.asio_channel_list.is_null() {
             None
         } else {
@@ -565,9 +671,7 @@ impl AdvancedSettings {
                 }
                 .iter()
                 .copied()
-                .map(|ptr| unsafe { CStr::from_ptr(ptr) })
-                .map(CStr::to_bytes)
-                .map(String::from_utf8_lossy),
+                .map(|ptr| unsafe { CStr::from_ptr(ptr) }),
             )
         }
     }
@@ -585,7 +689,88 @@ impl AdvancedSettings {
 }
 
 /// Callback to allow custom calculation of distance attenuation.
-pub type Rolloff3dCallback = extern "system" fn(channel: &Channel, distance: f32) -> f32;
+///
+/// The callback is called from the mixer thread for every 3D [Channel] each
+/// time 3D attributes are recalculated, so it must be fast and should not
+/// allocate or lock.
+pub trait Rolloff3dCallback {
+    /// Calculates the volume attenuation, from 0 (silent) to 1 (full volume),
+    /// of `channel` at `distance` from the listener.
+    ///
+    /// If this panics, the channel is played at full volume.
+    fn rolloff(channel: &Channel, distance: f32) -> f32;
+}
+
+unsafe extern "system" fn rolloff_3d_callback<C: Rolloff3dCallback>(
+    channelcontrol: *mut FMOD_CHANNELCONTROL,
+    distance: f32,
+) -> f32 {
+    let channel = Channel::from_raw(channelcontrol.cast());
+    catch_user_unwind(|| Ok(C::rolloff(channel, distance))).unwrap_or(1.0)
+}
+
+type BoxedRolloff = Box<dyn Fn(&Channel, f32) -> f32 + Send + Sync>;
+
+/// The closure set with [System::set_3d_rolloff_closure], kept in the
+/// system's [SystemState].
+///
+/// The callback doesn't lock; a replaced closure is kept until no callback
+/// can still be running it, so that a closure can also replace itself.
+#[derive(Default)]
+pub(crate) struct RolloffClosure {
+    current: AtomicPtr<BoxedRolloff>,
+    /// The number of callbacks which may be running a closure.
+    running: AtomicUsize,
+    /// Replaced closures which a running callback may still be using. Boxed
+    /// again, as callbacks refer to the outer box.
+    #[allow(clippy::vec_box)]
+    retired: Mutex<Vec<Box<BoxedRolloff>>>,
+}
+
+impl RolloffClosure {
+    fn replace(&self, closure: Option<BoxedRolloff>) {
+        let new = closure.map_or(ptr::null_mut(), |closure| Box::into_raw(Box::new(closure)));
+        let old = self.current.swap(new, Ordering::SeqCst);
+        let mut retired = self.retired.lock();
+        if !old.is_null() {
+            retired.push(unsafe { Box::from_raw(old) });
+        }
+        // callbacks starting after this load see the new closure
+        if self.running.load(Ordering::SeqCst) == 0 {
+            retired.clear();
+        }
+    }
+
+    fn call(&self, channel: &Channel, distance: f32) -> f32 {
+        self.running.fetch_add(1, Ordering::SeqCst);
+        let closure = unsafe { self.current.load(Ordering::SeqCst).as_ref() };
+        let volume = catch_user_unwind(|| Ok(closure.map_or(1.0, |f| f(channel, distance))));
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        volume.unwrap_or(1.0)
+    }
+}
+
+impl Drop for RolloffClosure {
+    fn drop(&mut self) {
+        let current = *self.current.get_mut();
+        if !current.is_null() {
+            drop(unsafe { Box::from_raw(current) });
+        }
+    }
+}
+
+unsafe extern "system" fn rolloff_3d_closure(
+    channelcontrol: *mut FMOD_CHANNELCONTROL,
+    distance: f32,
+) -> f32 {
+    let channel = Channel::from_raw(channelcontrol.cast());
+    // FMOD only passes the channel, so find the closure through its system
+    let system = channel.get_system_object().ok();
+    match system.and_then(System::state) {
+        Some(state) => state.rolloff_closure.call(channel, distance),
+        None => 1.0,
+    }
+}
 
 fmod_enum! {
     /// List of interpolation types used for resampling.
@@ -625,6 +810,32 @@ pub struct DspBufferSize {
     pub num_buffers: i32,
 }
 
+/// The latency of the FMOD software mixing engine, from
+/// [System::mixer_latency].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MixerLatency {
+    /// How often the mixer updates, i.e. the duration of one buffer.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Milliseconds</dd>
+    /// </dl>
+    pub block_ms: f32,
+    /// The duration of the whole ring buffer.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Milliseconds</dd>
+    /// </dl>
+    pub total_ms: f32,
+    /// The typical latency of the mixer, (`num_buffers` - 1.5) blocks, as
+    /// one buffer is constantly being written to and the audible cursor is
+    /// partway through another.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Milliseconds</dd>
+    /// </dl>
+    pub average_ms: f32,
+}
+
 /// The global doppler scale, distance factor and log rolloff scale for all 3D
 /// sound in FMOD.
 #[derive(Debug, SmartDefault, Copy, Clone, PartialEq)]
@@ -699,3 +910,122 @@ pub struct SpeakerPosition {
     /// false = ignored.
     pub active: bool,
 }
+
+impl SpeakerPosition {
+    /// An active speaker at `degrees` clockwise from the front of the
+    /// listener, `radius` away.
+    fn at_angle(degrees: f32, radius: f32) -> Self {
+        let (x, y) = degrees.to_radians().sin_cos();
+        SpeakerPosition {
+            x: x * radius,
+            y: y * radius,
+            active: true,
+        }
+    }
+}
+
+/// Common speaker layouts, for [System::set_speaker_positions].
+///
+/// The low frequency speaker isn't positioned, as it doesn't take part in
+/// 3D calculations.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SpeakerLayout {
+    /// The 7.1 layout of ITU-R BS.775: front speakers at ±30°, surround
+    /// (side) speakers at ±90°, and back speakers at ±150°.
+    Itu71,
+    /// A square of front and surround speakers at ±45° and ±135°.
+    Quad,
+    /// The 7.1 speakers evenly spaced clockwise around a circle of `radius`,
+    /// starting with the center speaker in front of the listener.
+    CustomCircle {
+        #[allow(missing_docs)]
+        radius: f32,
+    },
+}
+
+impl SpeakerLayout {
+    /// The speaker positions of this layout.
+    pub fn positions(self) -> Vec<(Speaker, SpeakerPosition)> {
+        let at = SpeakerPosition::at_angle;
+        match self {
+            SpeakerLayout::Itu71 => vec![
+                (Speaker::FrontLeft, at(-30.0, 1.0)),
+                (Speaker::FrontRight, at(30.0, 1.0)),
+                (Speaker::FrontCenter, at(0.0, 1.0)),
+                (Speaker::SurroundLeft, at(-90.0, 1.0)),
+                (Speaker::SurroundRight, at(90.0, 1.0)),
+                (Speaker::BackLeft, at(-150.0, 1.0)),
+                (Speaker::BackRight, at(150.0, 1.0)),
+            ],
+            SpeakerLayout::Quad => vec![
+                (Speaker::FrontLeft, at(-45.0, 1.0)),
+                (Speaker::FrontRight, at(45.0, 1.0)),
+                (Speaker::SurroundLeft, at(-135.0, 1.0)),
+                (Speaker::SurroundRight, at(135.0, 1.0)),
+            ],
+            SpeakerLayout::CustomCircle { radius } => [
+                Speaker::FrontCenter,
+                Speaker::FrontRight,
+                Speaker::SurroundRight,
+                Speaker::BackRight,
+                Speaker::BackLeft,
+                Speaker::SurroundLeft,
+                Speaker::FrontLeft,
+            ]
+            .into_iter()
+            .enumerate()
+            .map(|(i, speaker)| (speaker, at(i as f32 * 360.0 / 7.0, radius)))
+            .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::utils::expect_callback_panic, std::ptr::NonNull};
+
+    /// Never dereferenced by the callbacks under test.
+    fn channel() -> &'static Channel {
+        unsafe { &*NonNull::dangling().as_ptr() }
+    }
+
+    #[test]
+    fn rolloff_panic_plays_at_full_volume() {
+        struct Panicking;
+        impl Rolloff3dCallback for Panicking {
+            fn rolloff(_: &Channel, _: f32) -> f32 {
+                panic!("rolloff");
+            }
+        }
+
+        let volume = expect_callback_panic("rolloff", || unsafe {
+            rolloff_3d_callback::<Panicking>(channel().as_raw().cast(), 10.0)
+        });
+        assert_eq!(volume, 1.0);
+
+        let closure = RolloffClosure::default();
+        closure.replace(Some(Box::new(|_, _| panic!("rolloff closure"))));
+        let volume = expect_callback_panic("rolloff closure", || closure.call(channel(), 10.0));
+        assert_eq!(volume, 1.0);
+        assert_eq!(closure.running.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn rolloff_closure_can_be_replaced_while_running() {
+        let closure = RolloffClosure::default();
+        assert_eq!(closure.call(channel(), 10.0), 1.0);
+        closure.replace(Some(Box::new(|_, distance| 1.0 / distance)));
+        assert_eq!(closure.call(channel(), 10.0), 0.1);
+
+        // as if a callback were running the current closure
+        closure.running.fetch_add(1, Ordering::SeqCst);
+        closure.replace(Some(Box::new(|_, _| 0.5)));
+        assert_eq!(closure.retired.lock().len(), 1);
+        closure.running.fetch_sub(1, Ordering::SeqCst);
+        assert_eq!(closure.call(channel(), 10.0), 0.5);
+
+        closure.replace(None);
+        assert!(closure.retired.lock().is_empty());
+        assert_eq!(closure.call(channel(), 10.0), 1.0);
+    }
+}
@@ -0,0 +1,300 @@
+use {
+    crate::utils::{string_from_utf16be_lossy, string_from_utf16le_lossy},
+    fmod::*,
+};
+
+/// ID3v2 picture type for the front cover.
+const FRONT_COVER: u8 = 3;
+
+/// An embedded picture, such as album art, read by [`extract_artwork`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtworkData {
+    /// MIME type of the image, e.g. `"image/jpeg"`.
+    pub mime: String,
+    /// ID3v2 picture type, e.g. 3 for the front cover.
+    ///
+    /// Pictures without a type (MP4 `covr` atoms) report 3.
+    pub picture_type: u8,
+    /// Description of the picture, often empty.
+    pub description: String,
+    /// The encoded image.
+    pub data: Vec<u8>,
+}
+
+/// Finds the artwork embedded in a sound's metadata tags.
+///
+/// ID3v2 `APIC` (and ID3v2.2 `PIC`) frames and MP4 `covr` atoms are
+/// recognized. If there are multiple pictures, the front cover is preferred,
+/// otherwise the first picture is returned. Pictures which are only linked
+/// by URL, or whose frames are malformed, are skipped.
+///
+/// Returns `Ok(None)` if the sound has no embedded artwork.
+pub fn extract_artwork(sound: &Sound) -> Result<Option<ArtworkData>> {
+    let mut found = None;
+    for index in 0..sound.get_num_tags()? {
+        let tag = sound.get_tag(None, index)?;
+        let Some(data) = tag.data.as_binary() else {
+            continue;
+        };
+        let artwork = match &*tag.name {
+            "APIC" => parse_apic(data),
+            "PIC" => parse_pic(data),
+            "covr" => Some(ArtworkData {
+                mime: sniff_mime(data).to_owned(),
+                picture_type: FRONT_COVER,
+                description: String::new(),
+                data: data.to_vec(),
+            }),
+            _ => continue,
+        };
+        match artwork {
+            Some(artwork) if artwork.picture_type == FRONT_COVER => return Ok(Some(artwork)),
+            Some(artwork) => _ = found.get_or_insert(artwork),
+            None => whoops!(no_panic: "skipping malformed or linked {} picture", tag.name),
+        }
+    }
+    Ok(found)
+}
+
+/// Parses an ID3v2.3/2.4 `APIC` frame:
+/// encoding, NUL-terminated MIME type, picture type, description, image.
+fn parse_apic(frame: &[u8]) -> Option<ArtworkData> {
+    let (&encoding, rest) = frame.split_first()?;
+    let mime_end = rest.iter().position(|&b| b == 0)?;
+    let mime = latin1(&rest[..mime_end]);
+    // a MIME type of "-->" means the data is a URL rather than an image
+    if mime == "-->" {
+        return None;
+    }
+    let (&picture_type, rest) = rest[mime_end + 1..].split_first()?;
+    let (description, data) = split_description(encoding, rest)?;
+    Some(ArtworkData {
+        mime: if mime.is_empty() {
+            sniff_mime(data).to_owned()
+        } else if mime.contains('/') {
+            mime
+        } else {
+            // ID3v2.3 allows "PNG" or "JPG" in place of a MIME type
+            format_to_mime(&mime, data)
+        },
+        picture_type,
+        description,
+        data: data.to_vec(),
+    })
+}
+
+/// Parses an ID3v2.2 `PIC` frame:
+/// encoding, 3 byte image format, picture type, description, image.
+fn parse_pic(frame: &[u8]) -> Option<ArtworkData> {
+    let (&encoding, rest) = frame.split_first()?;
+    if rest.len() < 4 {
+        return None;
+    }
+    let (format, rest) = rest.split_at(3);
+    let format = latin1(format);
+    if format == "-->" {
+        return None;
+    }
+    let (&picture_type, rest) = rest.split_first()?;
+    let (description, data) = split_description(encoding, rest)?;
+    Some(ArtworkData {
+        mime: format_to_mime(&format, data),
+        picture_type,
+        description,
+        data: data.to_vec(),
+    })
+}
+
+/// Splits a terminated ID3v2 string in `encoding` from the data after it.
+fn split_description(encoding: u8, bytes: &[u8]) -> Option<(String, &[u8])> {
+    match encoding {
+        // ISO-8859-1 and UTF-8 are terminated by a single NUL
+        0 | 3 => {
+            let end = bytes.iter().position(|&b| b == 0)?;
+            let text = &bytes[..end];
+            let text = match encoding {
+                0 => latin1(text),
+                _ => String::from_utf8_lossy(text).into_owned(),
+            };
+            Some((text, &bytes[end + 1..]))
+        },
+        // UTF-16 with BOM and UTF-16BE are terminated by an aligned double NUL
+        1 | 2 => {
+            let end = bytes.chunks_exact(2).position(|unit| unit == [0, 0])? * 2;
+            let text = &bytes[..end];
+            let text = match text {
+                [0xFF, 0xFE, text @ ..] => string_from_utf16le_lossy(text),
+                [0xFE, 0xFF, text @ ..] => string_from_utf16be_lossy(text),
+                _ if encoding == 2 => string_from_utf16be_lossy(text),
+                _ => string_from_utf16le_lossy(text),
+            };
+            Some((text, &bytes[end + 2..]))
+        },
+        _ => None,
+    }
+}
+
+fn latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+/// Converts an image format such as `"JPG"` to a MIME type.
+fn format_to_mime(format: &str, data: &[u8]) -> String {
+    match &*format.to_ascii_lowercase() {
+        "jpg" | "jpeg" => "image/jpeg".to_owned(),
+        "" => sniff_mime(data).to_owned(),
+        format => format!("image/{format}"),
+    }
+}
+
+/// Guesses an image's MIME type from its magic bytes.
+fn sniff_mime(data: &[u8]) -> &'static str {
+    match data {
+        [0xFF, 0xD8, 0xFF, ..] => "image/jpeg",
+        [0x89, b'P', b'N', b'G', ..] => "image/png",
+        [b'G', b'I', b'F', b'8', ..] => "image/gif",
+        [b'B', b'M', ..] => "image/bmp",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JPEG: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 1, 2, 3];
+    const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 4, 5, 6];
+
+    fn concat(parts: &[&[u8]]) -> Vec<u8> {
+        parts.concat()
+    }
+
+    #[test]
+    fn apic_latin1() {
+        let frame = concat(&[&[0], b"image/png\0", &[FRONT_COVER], b"Cover\0", PNG]);
+        let artwork = parse_apic(&frame).unwrap();
+        assert_eq!(artwork.mime, "image/png");
+        assert_eq!(artwork.picture_type, FRONT_COVER);
+        assert_eq!(artwork.description, "Cover");
+        assert_eq!(artwork.data, PNG);
+    }
+
+    #[test]
+    fn apic_mime_fallbacks() {
+        // an empty MIME type is sniffed from the data
+        let artwork = parse_apic(&concat(&[&[0], b"\0", &[0], b"\0", JPEG])).unwrap();
+        assert_eq!(artwork.mime, "image/jpeg");
+        // ID3v2.3 image formats in place of a MIME type
+        let artwork = parse_apic(&concat(&[&[0], b"JPG\0", &[0], b"\0", JPEG])).unwrap();
+        assert_eq!(artwork.mime, "image/jpeg");
+        let artwork = parse_apic(&concat(&[&[0], b"PNG\0", &[0], b"\0", PNG])).unwrap();
+        assert_eq!(artwork.mime, "image/png");
+    }
+
+    #[test]
+    fn apic_utf16_descriptions() {
+        // UTF-16 with a little endian BOM, containing a NUL high byte
+        let frame = concat(&[
+            &[1],
+            b"image/jpeg\0",
+            &[FRONT_COVER],
+            &[0xFF, 0xFE, b'A', 0, 0x00, 0x01, 0, 0],
+            JPEG,
+        ]);
+        let artwork = parse_apic(&frame).unwrap();
+        assert_eq!(artwork.description, "A\u{100}");
+        assert_eq!(artwork.data, JPEG);
+
+        // UTF-16 with a big endian BOM
+        let frame = concat(&[
+            &[1],
+            b"image/jpeg\0",
+            &[0],
+            &[0xFE, 0xFF, 0, b'B', 0, 0],
+            JPEG,
+        ]);
+        assert_eq!(parse_apic(&frame).unwrap().description, "B");
+
+        // UTF-16BE without a BOM
+        let frame = concat(&[&[2], b"image/jpeg\0", &[0], &[0, b'C', 0, 0], JPEG]);
+        assert_eq!(parse_apic(&frame).unwrap().description, "C");
+
+        // the terminator is aligned, so "\u{100}" followed by a NUL isn't it
+        let (text, rest) = split_description(2, &[0, b'D', 0x01, 0, 0, 0, 9]).unwrap();
+        assert_eq!(text, "D\u{100}");
+        assert_eq!(rest, [9]);
+    }
+
+    #[test]
+    fn utf8_description() {
+        let (text, rest) = split_description(3, "café\0".as_bytes()).unwrap();
+        assert_eq!(text, "café");
+        assert!(rest.is_empty());
+        let (text, _) = split_description(0, b"caf\xE9\0").unwrap();
+        assert_eq!(text, "café");
+    }
+
+    #[test]
+    fn linked_pictures_are_skipped() {
+        let frame = concat(&[
+            &[0],
+            b"-->\0",
+            &[FRONT_COVER],
+            b"\0",
+            b"http://example.com/a.jpg",
+        ]);
+        assert_eq!(parse_apic(&frame), None);
+        let frame = concat(&[
+            &[0],
+            b"-->",
+            &[FRONT_COVER],
+            b"\0",
+            b"http://example.com/a.jpg",
+        ]);
+        assert_eq!(parse_pic(&frame), None);
+    }
+
+    #[test]
+    fn malformed_frames() {
+        assert_eq!(parse_apic(&[]), None);
+        // unterminated MIME type
+        assert_eq!(parse_apic(b"\0image/png"), None);
+        // missing picture type
+        assert_eq!(parse_apic(b"\0image/png\0"), None);
+        // unterminated description
+        assert_eq!(parse_apic(b"\0image/png\0\x03Cover"), None);
+        // UTF-16 description without an aligned terminator
+        assert_eq!(parse_apic(b"\x01image/png\0\x03\xFF\xFEA\0\0"), None);
+        // unknown text encoding
+        assert_eq!(parse_apic(b"\x04image/png\0\x03\0"), None);
+        assert_eq!(split_description(4, b"\0"), None);
+
+        assert_eq!(parse_pic(&[]), None);
+        assert_eq!(parse_pic(b"\0JP"), None);
+        assert_eq!(parse_pic(b"\0JPG"), None);
+        assert_eq!(parse_pic(b"\0JPG\x03Cover"), None);
+    }
+
+    #[test]
+    fn pic() {
+        let frame = concat(&[&[0], b"JPG", &[FRONT_COVER], b"\0", JPEG]);
+        let artwork = parse_pic(&frame).unwrap();
+        assert_eq!(artwork.mime, "image/jpeg");
+        assert_eq!(artwork.description, "");
+        assert_eq!(artwork.data, JPEG);
+
+        let frame = concat(&[&[0], b"PNG", &[7], b"Band\0", PNG]);
+        let artwork = parse_pic(&frame).unwrap();
+        assert_eq!(artwork.mime, "image/png");
+        assert_eq!(artwork.picture_type, 7);
+        assert_eq!(artwork.description, "Band");
+    }
+
+    #[test]
+    fn empty_image_data() {
+        let artwork = parse_apic(b"\0\0\x03\0").unwrap();
+        assert_eq!(artwork.mime, "application/octet-stream");
+        assert!(artwork.data.is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+use fmod::*;
+
+/// Converts between musical time and DSP clock samples, for scheduling
+/// [`Channel`]s on beat boundaries.
+///
+/// Beats are counted from when the clock was created, and positioned on the
+/// DSP clock of the [`ChannelGroup`], which is the
+/// [parent DSP clock](ChannelControl::get_parent_dsp_clock) of the channels
+/// played in it. Beat positions are always calculated from the tempo's
+/// starting point rather than accumulated beat by beat, so a non-integer
+/// number of samples per beat doesn't drift over time.
+#[derive(Debug)]
+pub struct BeatClock<'a> {
+    group: &'a ChannelGroup,
+    sample_rate: f64,
+    bpm: f64,
+    beats_per_bar: u32,
+    /// Beat at which the current tempo started, and its DSP clock.
+    origin_beat: u64,
+    origin_clock: u64,
+    /// Most recent beat reported by [`BeatClock::on_beat`].
+    last_beat: Option<u64>,
+}
+
+impl<'a> BeatClock<'a> {
+    /// Start counting beats at the current DSP clock of `group`.
+    pub fn new(group: &'a ChannelGroup, bpm: f64, beats_per_bar: u32) -> Result<Self> {
+        if !(bpm > 0.0 && bpm.is_finite()) || beats_per_bar == 0 {
+            whoops!(no_panic: "invalid beat clock tempo {bpm} BPM, {beats_per_bar} beats per bar");
+            return Err(Error::InvalidParam);
+        }
+        let format = group.get_system_object()?.get_software_format()?;
+        Ok(BeatClock {
+            group,
+            sample_rate: format.sample_rate as f64,
+            bpm,
+            beats_per_bar,
+            origin_beat: 0,
+            origin_clock: group.get_dsp_clock()?,
+            last_beat: None,
+        })
+    }
+
+    /// The tempo in beats per minute.
+    pub fn bpm(&self) -> f64 {
+        self.bpm
+    }
+
+    /// The number of beats in each bar.
+    pub fn beats_per_bar(&self) -> u32 {
+        self.beats_per_bar
+    }
+
+    /// The (fractional) number of samples in each beat at the current tempo.
+    pub fn samples_per_beat(&self) -> f64 {
+        self.sample_rate * 60.0 / self.bpm
+    }
+
+    /// Changes the tempo, starting from the most recent beat.
+    ///
+    /// Beats which have already started keep their position, and later
+    /// beats are positioned with the new tempo.
+    pub fn set_bpm(&mut self, bpm: f64) -> Result {
+        if !(bpm > 0.0 && bpm.is_finite()) {
+            whoops!(no_panic: "invalid beat clock tempo {bpm} BPM");
+            return Err(Error::InvalidParam);
+        }
+        let beat = self.beat_at(self.group.get_dsp_clock()?);
+        self.origin_clock = self.beat_to_clock(beat);
+        self.origin_beat = beat;
+        self.bpm = bpm;
+        Ok(())
+    }
+
+    /// The DSP clock at which `beat` starts.
+    pub fn beat_to_clock(&self, beat: u64) -> u64 {
+        if beat < self.origin_beat {
+            // before the current tempo; no longer tracked exactly
+            let samples = (self.origin_beat - beat) as f64 * self.samples_per_beat();
+            return self.origin_clock.saturating_sub(samples.round() as u64);
+        }
+        let samples = (beat - self.origin_beat) as f64 * self.samples_per_beat();
+        self.origin_clock + samples.round() as u64
+    }
+
+    /// The beat which is playing at DSP clock `clock`.
+    pub fn beat_at(&self, clock: u64) -> u64 {
+        if clock < self.origin_clock {
+            return self.origin_beat;
+        }
+        let samples = (clock - self.origin_clock) as f64;
+        let mut beat = self.origin_beat + (samples / self.samples_per_beat()) as u64;
+        // correct for rounding at the edges of beats
+        while beat > self.origin_beat && self.beat_to_clock(beat) > clock {
+            beat -= 1;
+        }
+        while self.beat_to_clock(beat + 1) <= clock {
+            beat += 1;
+        }
+        beat
+    }
+
+    /// The DSP clock of the first beat starting after DSP clock `after`.
+    pub fn next_beat(&self, after: u64) -> u64 {
+        self.beat_to_clock(self.beat_at(after) + 1)
+    }
+
+    /// The beat starting `beat` beats into bar `bar`, both counted from 0.
+    pub fn bar_to_beat(&self, bar: u64, beat: u32) -> u64 {
+        bar * self.beats_per_bar as u64 + beat as u64
+    }
+
+    /// The bar and beat within that bar of `beat`, both counted from 0.
+    pub fn beat_to_bar(&self, beat: u64) -> (u64, u32) {
+        let beats_per_bar = self.beats_per_bar as u64;
+        (beat / beats_per_bar, (beat % beats_per_bar) as u32)
+    }
+
+    /// Delays the start of `channel` until `at_beat`.
+    ///
+    /// The channel should be in this clock's channel group, and is typically
+    /// created paused (with [`System::create_sound_channel`]) so that it can
+    /// be scheduled and then unpaused.
+    pub fn schedule(&self, channel: &Channel, at_beat: u64) -> Result {
+        channel.set_delay(self.beat_to_clock(at_beat).., StopAction::Stop)
+    }
+
+    /// Whether a new beat has started since the last call, for polling from
+    /// the game loop.
+    ///
+    /// If the game loop falls behind by more than a beat, this reports only
+    /// the most recent beat; use [`current_beat`](Self::current_beat) to
+    /// find which one.
+    pub fn on_beat(&mut self) -> Result<bool> {
+        let beat = self.current_beat()?;
+        let new = self.last_beat != Some(beat);
+        self.last_beat = Some(beat);
+        Ok(new)
+    }
+
+    /// The beat which is currently playing.
+    pub fn current_beat(&self) -> Result<u64> {
+        Ok(self.beat_at(self.group.get_dsp_clock()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::ptr::NonNull};
+
+    fn clock(origin_clock: u64, sample_rate: f64, bpm: f64) -> BeatClock<'static> {
+        BeatClock {
+            // never dereferenced by the beat arithmetic
+            group: unsafe { &*NonNull::dangling().as_ptr() },
+            sample_rate,
+            bpm,
+            beats_per_bar: 4,
+            origin_beat: 0,
+            origin_clock,
+            last_beat: None,
+        }
+    }
+
+    #[test]
+    fn ten_minutes_at_173_bpm_does_not_drift() {
+        const SAMPLE_RATE: u64 = 44100;
+        const BPM: u64 = 173;
+        // 10 minutes is a whole number of beats at 173 BPM
+        const BEATS: u64 = BPM * 10;
+
+        for origin in [0, 1 << 40] {
+            let clock = clock(origin, SAMPLE_RATE as f64, BPM as f64);
+            for beat in 0..=BEATS {
+                // exact rational samples per beat, rounded to nearest
+                let samples = (2 * beat * SAMPLE_RATE * 60 + BPM) / (2 * BPM);
+                let at = clock.beat_to_clock(beat);
+                assert_eq!(at, origin + samples, "beat {beat} drifted");
+                assert_eq!(clock.beat_at(at), beat);
+                if beat > 0 {
+                    assert_eq!(clock.beat_at(at - 1), beat - 1);
+                }
+            }
+            let end = origin + 10 * 60 * SAMPLE_RATE;
+            assert_eq!(clock.beat_to_clock(BEATS), end);
+            assert_eq!(clock.beat_at(end), BEATS);
+        }
+    }
+
+    #[test]
+    fn bars_and_beats() {
+        let clock = clock(0, 48000.0, 120.0);
+        assert_eq!(clock.samples_per_beat(), 24000.0);
+        assert_eq!(clock.bar_to_beat(3, 2), 14);
+        assert_eq!(clock.beat_to_bar(14), (3, 2));
+        assert_eq!(clock.next_beat(0), 24000);
+        assert_eq!(clock.next_beat(23999), 24000);
+        assert_eq!(clock.next_beat(24000), 48000);
+    }
+}
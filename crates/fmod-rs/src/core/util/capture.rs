@@ -0,0 +1,412 @@
+use {
+    crate::utils::catch_user_unwind,
+    fmod::{raw::*, *},
+    std::{
+        ffi::c_void,
+        fs::File,
+        io::{self, BufWriter, Write},
+        iter,
+        mem::{self, ManuallyDrop},
+        path::Path,
+        ptr, slice,
+        sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering},
+    },
+};
+
+/// A DSP effect which captures the mix of a [`ChannelGroup`].
+///
+/// The effect passes its input through unchanged, so the group sounds the
+/// same while it is being captured. This records a single submix without
+/// switching the whole [`System`] to [`OutputType::WavWriter`]; attach it to
+/// the [master channel group](System::get_master_channel_group) to capture
+/// the final mix.
+pub enum CaptureDsp {}
+
+impl CaptureDsp {
+    /// Attach a capture effect to the tail of `group`'s DSP chain, buffering
+    /// up to two seconds of audio.
+    ///
+    /// See [`CaptureDsp::attach_with_capacity`].
+    pub fn attach(group: &ChannelGroup) -> Result<Capture<'_>> {
+        let system = group.get_system_object()?;
+        let sample_rate = system.get_software_format()?.sample_rate;
+        Self::attach_with_capacity(group, ix!(sample_rate) * 2)
+    }
+
+    /// Attach a capture effect to the tail of `group`'s DSP chain.
+    ///
+    /// Buffers are copied on the mixer thread into a buffer with room for
+    /// `capacity` frames of the [software format](System::get_software_format)'s
+    /// channel count, allocated up front, less one sample per mixer block to
+    /// record where it ends. Retrieve them with
+    /// [`Capture::samples`] or write them to disk with
+    /// [`Capture::write_wav`] often enough that the buffer doesn't fill up;
+    /// mixer blocks which don't fit are dropped and counted by
+    /// [`Capture::overruns`].
+    pub fn attach_with_capacity(group: &ChannelGroup, capacity: usize) -> Result<Capture<'_>> {
+        let system = group.get_system_object()?;
+        let format = system.get_software_format()?;
+        let fallback_channels = match format.speaker_mode.channel_count() {
+            Some(channels) => channels,
+            None => ix!(format.num_raw_speakers),
+        };
+        let capacity = capacity
+            .checked_mul(fallback_channels.max(1))
+            .ok_or(Error::InvalidParam)?;
+
+        let mut state = Box::new(CaptureState {
+            ring: SampleRing::new(capacity),
+            channels: AtomicUsize::new(0),
+            overruns: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            description: unsafe { mem::zeroed() },
+        });
+
+        let userdata = &*state as *const CaptureState as *mut c_void;
+        let description = &mut state.description;
+        description.pluginsdkversion = FMOD_PLUGIN_SDK_VERSION;
+        for (dst, &src) in description.name.iter_mut().zip(b"FMOD.rs Capture") {
+            *dst = src as _;
+        }
+        description.version = 1;
+        description.numinputbuffers = 1;
+        description.numoutputbuffers = 1;
+        description.read = Some(capture_read);
+        description.userdata = userdata;
+
+        let mut dsp = ptr::null_mut();
+        ffi!(FMOD_System_CreateDSP(
+            system.as_raw(),
+            description,
+            &mut dsp,
+        ))?;
+        let dsp = unsafe { Handle::<Dsp>::new(dsp) };
+        group.add_dsp(ChannelControl::DSP_TAIL, &dsp)?;
+
+        Ok(Capture {
+            group,
+            dsp: Some(dsp),
+            sample_rate: format.sample_rate,
+            fallback_channels,
+            state: ManuallyDrop::new(state),
+        })
+    }
+}
+
+/// A running capture of a [`ChannelGroup`]'s mix, created by
+/// [`CaptureDsp::attach`].
+///
+/// Dropping the capture [stops](Capture::stop) it.
+pub struct Capture<'a> {
+    group: &'a ChannelGroup,
+    dsp: Option<Handle<'a, Dsp>>,
+    sample_rate: i32,
+    fallback_channels: usize,
+    // FMOD refers to the state through the description's userdata for as long
+    // as the DSP exists, so it must outlive `dsp`.
+    state: ManuallyDrop<Box<CaptureState>>,
+}
+
+struct CaptureState {
+    ring: SampleRing,
+    channels: AtomicUsize,
+    overruns: AtomicU64,
+    rejected: AtomicU64,
+    description: FMOD_DSP_DESCRIPTION,
+}
+
+impl Capture<'_> {
+    /// Takes the samples captured so far, one mixer block at a time.
+    ///
+    /// The samples are interleaved, with [`Capture::channels`] channels. The
+    /// iterator ends once it has caught up with the mixer rather than waiting
+    /// for more, and blocks are removed from the buffer as they are yielded.
+    pub fn samples(&self) -> impl Iterator<Item = Vec<f32>> + '_ {
+        // the capture state isn't Sync, so this is the only consumer
+        iter::from_fn(|| self.state.ring.pop())
+    }
+
+    /// Number of interleaved channels in the captured samples.
+    ///
+    /// This is the channel count of the first mixer block captured. Before
+    /// any audio has been captured, this is the channel count of the
+    /// [software format](System::get_software_format).
+    pub fn channels(&self) -> usize {
+        match self.state.channels.load(Ordering::Relaxed) {
+            0 => self.fallback_channels,
+            channels => channels,
+        }
+    }
+
+    /// Sample rate of the captured samples.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Hertz</dd>
+    /// </dl>
+    pub fn sample_rate(&self) -> i32 {
+        self.sample_rate
+    }
+
+    /// Number of mixer blocks dropped because the buffer was full.
+    pub fn overruns(&self) -> u64 {
+        self.state.overruns.load(Ordering::Relaxed)
+    }
+
+    /// Number of mixer blocks dropped because their channel count differed
+    /// from the first block captured.
+    pub fn rejected(&self) -> u64 {
+        self.state.rejected.load(Ordering::Relaxed)
+    }
+
+    /// Stop capturing, removing the capture effect from the group.
+    ///
+    /// Samples captured before stopping can still be retrieved.
+    pub fn stop(&mut self) -> Result {
+        if let Some(dsp) = self.dsp.take() {
+            unsafe { self.group.remove_dsp(&dsp)? };
+            dsp.release()?;
+        }
+        Ok(())
+    }
+
+    /// Write the samples captured so far to a 32-bit float WAV file.
+    ///
+    /// This takes the samples like [`Capture::samples`] does, so samples
+    /// already taken are not included. WAV files are limited to 4 GiB of
+    /// data; an [`io::ErrorKind::InvalidInput`] error is returned for more.
+    pub fn write_wav(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let samples: Vec<f32> = self.samples().flatten().collect();
+        let file = BufWriter::new(File::create(path)?);
+        write_wav(file, self.channels(), self.sample_rate, &samples)
+    }
+}
+
+impl Drop for Capture<'_> {
+    fn drop(&mut self) {
+        match self.stop() {
+            Ok(()) => unsafe { ManuallyDrop::drop(&mut self.state) },
+            // FMOD may still read through the state, so it is leaked
+            Err(error) => whoops!(no_panic: "Error stopping capture: {error}"),
+        }
+    }
+}
+
+/// A bounded single-producer single-consumer queue of sample blocks.
+///
+/// The mixer thread pushes and the [`Capture`] owner pops, so the mixer
+/// thread never allocates or waits on a lock. Each block is stored as its
+/// length followed by its samples.
+struct SampleRing {
+    buffer: Box<[AtomicU32]>,
+    /// Total samples pushed, written only by the producer.
+    head: AtomicUsize,
+    /// Total samples popped, written only by the consumer.
+    tail: AtomicUsize,
+}
+
+impl SampleRing {
+    fn new(capacity: usize) -> Self {
+        SampleRing {
+            buffer: (0..capacity).map(|_| AtomicU32::new(0)).collect(),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Pushes `samples` as one block, or nothing if it doesn't fit.
+    fn push(&self, samples: &[f32]) -> bool {
+        let Ok(len) = u32::try_from(samples.len()) else {
+            return false;
+        };
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        let free = self.buffer.len() - head.wrapping_sub(tail);
+        if samples.len() >= free {
+            return false;
+        }
+        self.buffer[head % self.buffer.len()].store(len, Ordering::Relaxed);
+        for (i, sample) in samples.iter().enumerate() {
+            let slot = head.wrapping_add(i + 1) % self.buffer.len();
+            self.buffer[slot].store(sample.to_bits(), Ordering::Relaxed);
+        }
+        self.head
+            .store(head.wrapping_add(samples.len() + 1), Ordering::Release);
+        true
+    }
+
+    /// Pops the oldest block, if any.
+    fn pop(&self) -> Option<Vec<f32>> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        let len = self.buffer[tail % self.buffer.len()].load(Ordering::Relaxed) as usize;
+        let samples = (0..len)
+            .map(|i| {
+                let slot = tail.wrapping_add(i + 1) % self.buffer.len();
+                f32::from_bits(self.buffer[slot].load(Ordering::Relaxed))
+            })
+            .collect();
+        self.tail
+            .store(tail.wrapping_add(len + 1), Ordering::Release);
+        Some(samples)
+    }
+}
+
+fn write_wav(
+    mut w: impl Write,
+    channels: usize,
+    sample_rate: i32,
+    samples: &[f32],
+) -> io::Result<()> {
+    const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+    let invalid = || io::Error::from(io::ErrorKind::InvalidInput);
+
+    let channels: u16 = channels.try_into().map_err(|_| invalid())?;
+    let sample_rate: u32 = sample_rate.try_into().map_err(|_| invalid())?;
+    let block_align = channels
+        .checked_mul(mem::size_of::<f32>() as u16)
+        .ok_or_else(invalid)?;
+    let byte_rate = sample_rate
+        .checked_mul(block_align as u32)
+        .ok_or_else(invalid)?;
+    let data_len: u32 = mem::size_of_val(samples)
+        .try_into()
+        .map_err(|_| invalid())?;
+    let riff_len = data_len.checked_add(36).ok_or_else(invalid)?;
+
+    w.write_all(b"RIFF")?;
+    w.write_all(&riff_len.to_le_bytes())?;
+    w.write_all(b"WAVE")?;
+
+    w.write_all(b"fmt ")?;
+    w.write_all(&16u32.to_le_bytes())?;
+    w.write_all(&WAVE_FORMAT_IEEE_FLOAT.to_le_bytes())?;
+    w.write_all(&channels.to_le_bytes())?;
+    w.write_all(&sample_rate.to_le_bytes())?;
+    w.write_all(&byte_rate.to_le_bytes())?;
+    w.write_all(&block_align.to_le_bytes())?;
+    w.write_all(&32u16.to_le_bytes())?;
+
+    w.write_all(b"data")?;
+    w.write_all(&data_len.to_le_bytes())?;
+    for sample in samples {
+        w.write_all(&sample.to_le_bytes())?;
+    }
+    w.flush()
+}
+
+unsafe extern "system" fn capture_read(
+    state: *mut FMOD_DSP_STATE,
+    inbuffer: *mut f32,
+    outbuffer: *mut f32,
+    length: u32,
+    inchannels: i32,
+    outchannels: *mut i32,
+) -> FMOD_RESULT {
+    catch_user_unwind(|| {
+        let functions = (*state).functions;
+        let get_userdata = (*functions).getuserdata.ok_or(Error::Internal)?;
+        let mut userdata = ptr::null_mut();
+        Error::from_raw(get_userdata(state, &mut userdata))?;
+        let capture = &*userdata.cast::<CaptureState>();
+
+        let channels = ix!(inchannels);
+        let len = ix!(length) * channels;
+        *outchannels = inchannels;
+        if len == 0 {
+            return Ok(());
+        }
+        ptr::copy(inbuffer, outbuffer, len);
+
+        // the format is recorded from the first block; interleaving blocks
+        // with a different channel count would garble the capture
+        let recorded = match capture.channels.compare_exchange(
+            0,
+            channels,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => channels,
+            Err(recorded) => recorded,
+        };
+        if recorded != channels {
+            capture.rejected.fetch_add(1, Ordering::Relaxed);
+            return Ok(());
+        }
+
+        let input = slice::from_raw_parts(outbuffer, len);
+        if !capture.ring.push(input) {
+            capture.overruns.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(())
+    })
+    .into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_round_trips_in_order() {
+        let ring = SampleRing::new(8);
+        let mut out = Vec::new();
+        for block in 0..10 {
+            let samples = [block as f32, block as f32 + 0.5];
+            assert!(ring.push(&samples));
+            out.extend(ring.pop());
+        }
+        assert_eq!(ring.pop(), None);
+        let expected: Vec<Vec<f32>> = (0..10)
+            .map(|block| vec![block as f32, block as f32 + 0.5])
+            .collect();
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn ring_keeps_block_boundaries() {
+        let ring = SampleRing::new(8);
+        assert!(ring.push(&[1.0, 2.0, 3.0]));
+        assert!(ring.push(&[]));
+        assert!(ring.push(&[4.0]));
+        assert_eq!(ring.pop(), Some(vec![1.0, 2.0, 3.0]));
+        assert_eq!(ring.pop(), Some(vec![]));
+        assert_eq!(ring.pop(), Some(vec![4.0]));
+        assert_eq!(ring.pop(), None);
+    }
+
+    #[test]
+    fn ring_drops_blocks_which_do_not_fit() {
+        let ring = SampleRing::new(7);
+        assert!(ring.push(&[1.0, 2.0]));
+        assert!(ring.push(&[3.0, 4.0]));
+        assert!(!ring.push(&[5.0, 6.0]));
+
+        assert_eq!(ring.pop(), Some(vec![1.0, 2.0]));
+        assert!(ring.push(&[5.0, 6.0]));
+        assert_eq!(ring.pop(), Some(vec![3.0, 4.0]));
+        assert_eq!(ring.pop(), Some(vec![5.0, 6.0]));
+    }
+
+    #[test]
+    fn wav_header() {
+        let mut wav = Vec::new();
+        write_wav(&mut wav, 2, 48000, &[0.0; 4]).unwrap();
+        assert_eq!(wav.len(), 44 + 16);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(wav[4..8], 52u32.to_le_bytes());
+        assert_eq!(wav[22..24], 2u16.to_le_bytes());
+        assert_eq!(wav[28..32], (48000u32 * 8).to_le_bytes());
+        assert_eq!(wav[40..44], 16u32.to_le_bytes());
+    }
+
+    #[test]
+    fn wav_rejects_invalid_format() {
+        let result = write_wav(io::sink(), 0x1_0000, 48000, &[]);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        let result = write_wav(io::sink(), 2, -1, &[]);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,520 @@
+use {
+    fmod::{raw::*, *},
+    parking_lot::Mutex,
+    std::{
+        fmt,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            mpsc,
+        },
+    },
+};
+
+/// How a [`DeviceWatcher`] selects an output driver when the list of devices
+/// changes.
+#[allow(clippy::type_complexity)]
+pub enum DevicePolicy {
+    /// Select driver 0, the system default device, whenever it changes.
+    FollowSystemDefault,
+    /// Select the device with this GUID whenever it is available, and the
+    /// system default device otherwise. Useful to return to a device the
+    /// player chose once it is plugged back in.
+    PreferGuid(Guid),
+    /// Select the driver returned by the closure, which is given the current
+    /// list of drivers, or leave the current driver if it returns `None`.
+    ///
+    /// The closure is called without any FMOD.rs locks held, so it may use
+    /// the system freely.
+    Custom(Box<dyn FnMut(&System, &[Driver]) -> Option<i32> + Send>),
+}
+
+impl fmt::Debug for DevicePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FollowSystemDefault => f.write_str("FollowSystemDefault"),
+            Self::PreferGuid(guid) => f.debug_tuple("PreferGuid").field(guid).finish(),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// A change observed or made by a [`DeviceWatcher`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceEvent {
+    /// The enumerated list of output devices changed.
+    ListChanged {
+        /// The output devices now available.
+        drivers: Vec<Driver>,
+    },
+    /// The watcher selected a new output driver with [`System::set_driver`].
+    DriverSelected {
+        /// Index of the selected driver.
+        id: i32,
+        /// GUID of the selected device.
+        guid: Guid,
+    },
+    /// Every output device disappeared, so the watcher switched to
+    /// [`OutputType::NoSound`] to keep the mixer running. The previous output
+    /// type is restored once a device becomes available again.
+    AllDevicesLost,
+    /// An output device was reinitialized by FMOD.
+    Reinitialized {
+        /// Output type of the reinitialized device.
+        output: OutputType,
+        /// Index of the reinitialized driver.
+        id: i32,
+    },
+    /// Handling a device change failed.
+    Error(Error),
+}
+
+/// Handles output device insertion and removal for a [`System`].
+///
+/// Installing a watcher replaces FMOD's automatic device handling with the
+/// chosen [`DevicePolicy`]: when the device list changes the drivers are
+/// re-enumerated and a driver is selected with [`System::set_driver`]. If
+/// every device disappears, the output is switched to
+/// [`OutputType::NoSound`] until a device returns.
+///
+/// Device changes are handled from [`System::update`], and reported as
+/// [`DeviceEvent`]s which can be drained with [`DeviceWatcher::changes`].
+///
+/// To test hot-plug handling manually, play a looping sound with the watcher
+/// installed, then unplug and replug a headset (or change the default output
+/// device in the OS settings) while calling [`System::update`] and logging
+/// [`DeviceWatcher::changes`].
+///
+/// The watcher uses [`System::set_callback`] for
+/// [`SystemCallbackType::DeviceListChanged`] and
+/// [`SystemCallbackType::DeviceReinitialize`], so it cannot be combined with
+/// another [`SystemCallback`] on the same system. Dropping the watcher
+/// removes the callback, unless another callback has been set since.
+pub struct DeviceWatcher<'a> {
+    system: &'a System,
+    id: u64,
+    callback_generation: u64,
+    receiver: mpsc::Receiver<DeviceEvent>,
+}
+
+/// The [`DeviceWatcher`] installed on a system, kept in its state.
+pub(crate) struct WatcherSlot {
+    id: u64,
+    sender: mpsc::Sender<DeviceEvent>,
+    /// `None` while the state is taken to handle a device change.
+    state: Option<WatcherState>,
+}
+
+struct WatcherState {
+    policy: DevicePolicy,
+    sender: mpsc::Sender<DeviceEvent>,
+    restore_output: Option<OutputType>,
+    selected: Option<Guid>,
+}
+
+static NEXT_WATCHER_ID: AtomicU64 = AtomicU64::new(0);
+
+impl<'a> DeviceWatcher<'a> {
+    /// Install a device watcher on `system` using `policy`.
+    ///
+    /// Only one watcher may be installed on a system at a time; installing
+    /// a second one fails with [`Error::InvalidParam`].
+    pub fn install(system: &'a System, policy: DevicePolicy) -> Result<Self> {
+        let Some(state) = system.state() else {
+            whoops!(no_panic: "{system:?} has no FMOD.rs state");
+            yeet!(Error::Internal);
+        };
+        let id = NEXT_WATCHER_ID.fetch_add(1, Ordering::Relaxed);
+        let driver = system.get_driver()?;
+        let selected = match system.get_num_drivers()? {
+            0 => None,
+            _ => Some(system.get_driver_info(driver)?.guid),
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        {
+            let mut slot = state.device_watcher.lock();
+            if slot.is_some() {
+                yeet!(Error::InvalidParam);
+            }
+            *slot = Some(WatcherSlot {
+                id,
+                sender: sender.clone(),
+                state: Some(WatcherState {
+                    policy,
+                    sender,
+                    restore_output: None,
+                    selected,
+                }),
+            });
+        }
+
+        let mask = SystemCallbackType::DeviceListChanged | SystemCallbackType::DeviceReinitialize;
+        if let Err(error) = system.set_callback::<DeviceWatcherCallback>(mask) {
+            *state.device_watcher.lock() = None;
+            yeet!(error);
+        }
+        let callback_generation = state.callback_generation.load(Ordering::Relaxed);
+
+        Ok(DeviceWatcher {
+            system,
+            id,
+            callback_generation,
+            receiver,
+        })
+    }
+
+    /// Takes the device events which have occurred so far, without waiting
+    /// for more.
+    pub fn changes(&self) -> impl Iterator<Item = DeviceEvent> + '_ {
+        self.receiver.try_iter()
+    }
+}
+
+impl Drop for DeviceWatcher<'_> {
+    fn drop(&mut self) {
+        let Some(state) = self.system.state() else {
+            return;
+        };
+        {
+            let mut slot = state.device_watcher.lock();
+            if slot.as_ref().is_some_and(|slot| slot.id == self.id) {
+                *slot = None;
+            }
+        }
+
+        // only remove the callback if it hasn't been replaced since
+        if state.callback_generation.load(Ordering::Relaxed) != self.callback_generation {
+            return;
+        }
+        if let Err(error) = ffi!(FMOD_System_SetCallback(self.system.as_raw(), None, 0)) {
+            whoops!(no_panic: "Error removing device watcher callback: {error}");
+        }
+    }
+}
+
+enum DeviceWatcherCallback {}
+
+impl SystemCallback for DeviceWatcherCallback {
+    fn device_list_changed(system: &System) -> Result {
+        if let Some(state) = system.state() {
+            handle_device_list_changed(&state.device_watcher, system);
+        }
+        Ok(())
+    }
+
+    fn device_reinitialize(system: &System, output: OutputType, id: i32) -> Result {
+        let Some(state) = system.state() else {
+            return Ok(());
+        };
+        if let Some(watcher) = &*state.device_watcher.lock() {
+            let _ = watcher
+                .sender
+                .send(DeviceEvent::Reinitialized { output, id });
+        }
+        Ok(())
+    }
+}
+
+/// Runs the policy of the watcher in `slot`, if there is one.
+fn handle_device_list_changed(slot: &Mutex<Option<WatcherSlot>>, system: &impl OutputDevices) {
+    // take the state out, so that the policy runs without the lock held
+    let (id, mut watcher) = {
+        let mut slot = slot.lock();
+        let Some(slot) = slot.as_mut() else {
+            return;
+        };
+        // already handling a change further up the stack
+        let Some(watcher) = slot.state.take() else {
+            return;
+        };
+        (slot.id, watcher)
+    };
+
+    if let Err(error) = watcher.device_list_changed(system) {
+        let _ = watcher.sender.send(DeviceEvent::Error(error));
+    }
+
+    // put the state back, unless the watcher was dropped in the meantime
+    if let Some(slot) = slot.lock().as_mut().filter(|slot| slot.id == id) {
+        slot.state = Some(watcher);
+    }
+}
+
+/// The output device functions of [`System`] used by a [`DeviceWatcher`].
+trait OutputDevices {
+    fn get_output(&self) -> Result<OutputType>;
+    fn set_output(&self, output: OutputType) -> Result;
+    fn get_num_drivers(&self) -> Result<i32>;
+    fn get_driver_details(&self, id: i32) -> Result<Driver>;
+    fn set_driver(&self, id: i32) -> Result;
+    /// Calls a [`DevicePolicy::Custom`] closure.
+    fn select(
+        &self,
+        select: &mut (dyn FnMut(&System, &[Driver]) -> Option<i32> + Send),
+        drivers: &[Driver],
+    ) -> Option<i32>;
+}
+
+impl OutputDevices for System {
+    fn get_output(&self) -> Result<OutputType> {
+        System::get_output(self)
+    }
+
+    fn set_output(&self, output: OutputType) -> Result {
+        System::set_output(self, output)
+    }
+
+    fn get_num_drivers(&self) -> Result<i32> {
+        System::get_num_drivers(self)
+    }
+
+    fn get_driver_details(&self, id: i32) -> Result<Driver> {
+        System::get_driver_details(self, id)
+    }
+
+    fn set_driver(&self, id: i32) -> Result {
+        System::set_driver(self, id)
+    }
+
+    fn select(
+        &self,
+        select: &mut (dyn FnMut(&System, &[Driver]) -> Option<i32> + Send),
+        drivers: &[Driver],
+    ) -> Option<i32> {
+        select(self, drivers)
+    }
+}
+
+impl WatcherState {
+    fn device_list_changed(&mut self, system: &impl OutputDevices) -> Result {
+        if let Some(output) = self.restore_output {
+            // NoSound always has a driver, so probe the output we replaced
+            system.set_output(output)?;
+            if system.get_num_drivers()? == 0 {
+                system.set_output(OutputType::NoSound)?;
+                return Ok(());
+            }
+            self.restore_output = None;
+        }
+
+        let drivers = (0..system.get_num_drivers()?)
+            .map(|id| system.get_driver_details(id))
+            .collect::<Result<Vec<_>>>()?;
+        let _ = self.sender.send(DeviceEvent::ListChanged {
+            drivers: drivers.clone(),
+        });
+
+        if drivers.is_empty() {
+            self.restore_output = Some(system.get_output()?);
+            self.selected = None;
+            system.set_output(OutputType::NoSound)?;
+            let _ = self.sender.send(DeviceEvent::AllDevicesLost);
+            return Ok(());
+        }
+
+        let id = match &mut self.policy {
+            DevicePolicy::FollowSystemDefault => Some(0),
+            DevicePolicy::PreferGuid(guid) => Some(
+                drivers
+                    .iter()
+                    .position(|driver| driver.info.guid == *guid)
+                    .map_or(0, |id| id as i32),
+            ),
+            DevicePolicy::Custom(select) => system.select(&mut **select, &drivers),
+        };
+        let Some(id) = id else { return Ok(()) };
+        let driver = usize::try_from(id).ok().and_then(|id| drivers.get(id));
+        let guid = driver.ok_or(Error::InvalidParam)?.info.guid;
+        if self.selected != Some(guid) {
+            system.set_driver(id)?;
+            self.selected = Some(guid);
+            let _ = self.sender.send(DeviceEvent::DriverSelected { id, guid });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::cell::{Cell, RefCell},
+    };
+
+    /// A system with an ALSA output whose devices can be plugged in and out.
+    struct MockSystem {
+        output: Cell<OutputType>,
+        drivers: RefCell<Vec<Driver>>,
+        driver: Cell<Option<i32>>,
+    }
+
+    impl MockSystem {
+        fn new(drivers: Vec<Driver>) -> Self {
+            MockSystem {
+                output: Cell::new(OutputType::Alsa),
+                drivers: RefCell::new(drivers),
+                driver: Cell::new(None),
+            }
+        }
+    }
+
+    impl OutputDevices for MockSystem {
+        fn get_output(&self) -> Result<OutputType> {
+            Ok(self.output.get())
+        }
+
+        fn set_output(&self, output: OutputType) -> Result {
+            self.output.set(output);
+            Ok(())
+        }
+
+        fn get_num_drivers(&self) -> Result<i32> {
+            match self.output.get() {
+                OutputType::NoSound => Ok(1),
+                _ => Ok(self.drivers.borrow().len() as i32),
+            }
+        }
+
+        fn get_driver_details(&self, id: i32) -> Result<Driver> {
+            assert_ne!(self.output.get(), OutputType::NoSound);
+            let drivers = self.drivers.borrow();
+            drivers.get(id as usize).cloned().ok_or(Error::InvalidParam)
+        }
+
+        fn set_driver(&self, id: i32) -> Result {
+            self.driver.set(Some(id));
+            Ok(())
+        }
+
+        fn select(
+            &self,
+            _: &mut (dyn FnMut(&System, &[Driver]) -> Option<i32> + Send),
+            _: &[Driver],
+        ) -> Option<i32> {
+            unreachable!("custom policies need a real system")
+        }
+    }
+
+    fn driver(n: u32) -> Driver {
+        Driver {
+            name: format!("device {n}"),
+            info: DriverInfo {
+                guid: guid(n),
+                ..DriverInfo::default()
+            },
+        }
+    }
+
+    fn guid(n: u32) -> Guid {
+        Guid {
+            data1: n,
+            ..Guid::default()
+        }
+    }
+
+    fn watcher(policy: DevicePolicy) -> (Mutex<Option<WatcherSlot>>, mpsc::Receiver<DeviceEvent>) {
+        let (sender, receiver) = mpsc::channel();
+        let slot = WatcherSlot {
+            id: 0,
+            sender: sender.clone(),
+            state: Some(WatcherState {
+                policy,
+                sender,
+                restore_output: None,
+                selected: None,
+            }),
+        };
+        (Mutex::new(Some(slot)), receiver)
+    }
+
+    fn selected(events: &mpsc::Receiver<DeviceEvent>) -> Vec<(i32, Guid)> {
+        events
+            .try_iter()
+            .filter_map(|event| match event {
+                DeviceEvent::DriverSelected { id, guid } => Some((id, guid)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn follow_system_default() {
+        let system = MockSystem::new(vec![driver(1), driver(2)]);
+        let (slot, events) = watcher(DevicePolicy::FollowSystemDefault);
+
+        handle_device_list_changed(&slot, &system);
+        assert_eq!(selected(&events), [(0, guid(1))]);
+        assert_eq!(system.driver.get(), Some(0));
+
+        // another device is plugged in; the default is unchanged
+        system.drivers.borrow_mut().push(driver(3));
+        handle_device_list_changed(&slot, &system);
+        assert_eq!(selected(&events), []);
+
+        // the default device is unplugged
+        system.drivers.borrow_mut().remove(0);
+        handle_device_list_changed(&slot, &system);
+        assert_eq!(selected(&events), [(0, guid(2))]);
+    }
+
+    #[test]
+    fn prefer_guid() {
+        let system = MockSystem::new(vec![driver(1), driver(2)]);
+        let (slot, events) = watcher(DevicePolicy::PreferGuid(guid(2)));
+
+        handle_device_list_changed(&slot, &system);
+        assert_eq!(selected(&events), [(1, guid(2))]);
+
+        // the preferred device is unplugged, so fall back to the default
+        system.drivers.borrow_mut().remove(1);
+        handle_device_list_changed(&slot, &system);
+        assert_eq!(selected(&events), [(0, guid(1))]);
+
+        // and return to it once it is plugged back in
+        system.drivers.borrow_mut().insert(0, driver(2));
+        handle_device_list_changed(&slot, &system);
+        assert_eq!(selected(&events), [(0, guid(2))]);
+        assert_eq!(system.driver.get(), Some(0));
+    }
+
+    #[test]
+    fn all_devices_lost_and_restored() {
+        let system = MockSystem::new(vec![driver(1)]);
+        let (slot, events) = watcher(DevicePolicy::FollowSystemDefault);
+        handle_device_list_changed(&slot, &system);
+        assert_eq!(selected(&events), [(0, guid(1))]);
+
+        system.drivers.borrow_mut().clear();
+        handle_device_list_changed(&slot, &system);
+        assert_eq!(system.output.get(), OutputType::NoSound);
+        let events_now: Vec<_> = events.try_iter().collect();
+        assert_eq!(
+            events_now,
+            [
+                DeviceEvent::ListChanged { drivers: vec![] },
+                DeviceEvent::AllDevicesLost,
+            ]
+        );
+
+        // still no devices; keep the mixer running on NoSound
+        handle_device_list_changed(&slot, &system);
+        assert_eq!(system.output.get(), OutputType::NoSound);
+        assert_eq!(events.try_iter().count(), 0);
+
+        // the device returns, so the replaced output is restored
+        system.drivers.borrow_mut().push(driver(1));
+        handle_device_list_changed(&slot, &system);
+        assert_eq!(system.output.get(), OutputType::Alsa);
+        assert_eq!(selected(&events), [(0, guid(1))]);
+    }
+
+    #[test]
+    fn removed_watcher_is_not_run() {
+        let system = MockSystem::new(vec![driver(1)]);
+        let (slot, events) = watcher(DevicePolicy::FollowSystemDefault);
+        *slot.lock() = None;
+        handle_device_list_changed(&slot, &system);
+        assert_eq!(system.driver.get(), None);
+        assert_eq!(events.try_iter().count(), 0);
+    }
+}
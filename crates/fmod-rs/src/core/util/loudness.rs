@@ -0,0 +1,119 @@
+use {
+    fmod::{effect::LoudnessMeter, *},
+    std::mem,
+};
+
+/// Which loudness measurements [`LoudnessMonitor::reset`] clears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoudnessReset {
+    /// Reset all measurements except the max true peak.
+    Integrated,
+    /// Reset only the max true peak.
+    MaxPeak,
+    /// Reset all measurements.
+    All,
+}
+
+/// Measures the loudness of a [`ChannelGroup`]'s output.
+///
+/// This attaches a [`DspType::LoudnessMeter`] to the head of the group's DSP
+/// chain, so the measurement includes the group's volume and effects.
+/// Loudness is reported in LUFS and peaks in dBTP, as described by
+/// ITU-R BS.1770.
+///
+/// Dropping the monitor removes and releases the meter.
+#[derive(Debug)]
+pub struct LoudnessMonitor<'a> {
+    group: &'a ChannelGroup,
+    dsp: Handle<'a, Dsp>,
+}
+
+impl<'a> LoudnessMonitor<'a> {
+    /// Attach a loudness meter to `group` and start analyzing.
+    pub fn attach(group: &'a ChannelGroup) -> Result<Self> {
+        let system = group.get_system_object()?;
+        let dsp = system.create_dsp_by_type(DspType::LoudnessMeter)?;
+        dsp.set_parameter(LoudnessMeter::State, LoudnessMeter::StateType::Analyzing)?;
+        group.add_dsp(ChannelControl::DSP_HEAD, &dsp)?;
+        Ok(LoudnessMonitor { group, dsp })
+    }
+
+    /// Retrieves the full metering information.
+    ///
+    /// This is copied while the DSP engine is locked, as described in
+    /// [`Dsp::get_parameter_data`].
+    pub fn info(&self) -> Result<LoudnessMeter::MeterInfoType> {
+        let size = mem::size_of::<LoudnessMeter::MeterInfoType>();
+        let index = LoudnessMeter::MeterInfo.into();
+        self.dsp.with_dsp_locked(|| {
+            let (data, length) = self.dsp.parameter_data(index)?;
+            // later FMOD versions may add fields, so only too little is an error
+            if length < size {
+                whoops!(no_panic: "loudness meter info has unexpected length {length}");
+                yeet!(Error::Internal);
+            }
+            unsafe { read_parameter_data(data, size) }
+        })
+    }
+
+    /// Loudness over the last 400ms.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>LUFS</dd>
+    /// </dl>
+    pub fn momentary(&self) -> Result<f32> {
+        Ok(self.info()?.momentary_loudness)
+    }
+
+    /// Loudness over the last 3 seconds.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>LUFS</dd>
+    /// </dl>
+    pub fn short_term(&self) -> Result<f32> {
+        Ok(self.info()?.short_term_loudness)
+    }
+
+    /// Loudness since the meter was attached or last reset.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>LUFS</dd>
+    /// </dl>
+    pub fn integrated(&self) -> Result<f32> {
+        Ok(self.info()?.integrated_loudness)
+    }
+
+    /// Highest true peak since the meter was attached or last reset.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>dBTP</dd>
+    /// </dl>
+    pub fn max_true_peak(&self) -> Result<f32> {
+        Ok(self.info()?.max_true_peak)
+    }
+
+    /// Reset the measurements selected by `kind`, then continue analyzing.
+    pub fn reset(&self, kind: LoudnessReset) -> Result {
+        let state = match kind {
+            LoudnessReset::Integrated => LoudnessMeter::StateType::ResetIntegrated,
+            LoudnessReset::MaxPeak => LoudnessMeter::StateType::ResetMaxPeak,
+            LoudnessReset::All => LoudnessMeter::StateType::ResetAll,
+        };
+        self.dsp.set_parameter(LoudnessMeter::State, state)?;
+        self.dsp
+            .set_parameter(LoudnessMeter::State, LoudnessMeter::StateType::Analyzing)
+    }
+
+    /// The loudness meter DSP.
+    pub fn dsp(&self) -> &Dsp {
+        &self.dsp
+    }
+}
+
+impl Drop for LoudnessMonitor<'_> {
+    fn drop(&mut self) {
+        if let Err(error) = unsafe { self.group.remove_dsp(&self.dsp) } {
+            whoops!(no_panic: "Error removing loudness meter: {error}");
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//! Utilities built on top of the FMOD Core API.
+//!
+//! These are not part of the FMOD API itself, but wrap common patterns which
+//! otherwise require writing against the plugin and callback APIs by hand.
+
+mod artwork;
+mod beat;
+mod capture;
+mod device;
+mod loudness;
+mod offline;
+mod pump;
+mod starvation;
+
+pub use self::{
+    artwork::*, beat::*, capture::*, device::*, loudness::*, offline::*, pump::*, starvation::*,
+};
@@ -0,0 +1,197 @@
+use {
+    fmod::*,
+    std::{ffi::CString, fmt, path::PathBuf, time::Duration},
+};
+
+/// Output settings for [`render_offline`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfflineRenderConfig {
+    /// Path of the wav file to write.
+    pub output: PathBuf,
+    /// Sample rate of the rendered file.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Hertz</dd>
+    /// <dt>Default</dt><dd>48000</dd>
+    /// </dl>
+    pub sample_rate: i32,
+    /// Speaker setup of the rendered file.
+    ///
+    /// <dl>
+    /// <dt>Default</dt><dd>[SpeakerMode::Stereo]</dd>
+    /// </dl>
+    pub speaker_mode: SpeakerMode,
+    /// Maximum number of [Channel]s available for playback.
+    ///
+    /// <dl>
+    /// <dt>Default</dt><dd>64</dd>
+    /// </dl>
+    pub max_channels: i32,
+    /// Length at which rendering stops, even if the script hasn't completed.
+    ///
+    /// <dl>
+    /// <dt>Default</dt><dd>10 minutes</dd>
+    /// </dl>
+    pub max_duration: Duration,
+}
+
+impl OfflineRenderConfig {
+    /// Render to the wav file at `output` with the default settings.
+    pub fn new(output: impl Into<PathBuf>) -> Self {
+        OfflineRenderConfig {
+            output: output.into(),
+            sample_rate: 48000,
+            speaker_mode: SpeakerMode::Stereo,
+            max_channels: 64,
+            max_duration: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+/// An action performed by [`render_offline`] at a point in the rendered
+/// timeline.
+pub enum RenderAction<'a> {
+    /// Play a sound, in a channel group or the master channel group.
+    Play {
+        #[allow(missing_docs)]
+        sound: &'a Sound,
+        #[allow(missing_docs)]
+        group: Option<&'a ChannelGroup>,
+    },
+    /// Set a float parameter of a DSP by index.
+    SetParameter {
+        #[allow(missing_docs)]
+        dsp: &'a Dsp,
+        #[allow(missing_docs)]
+        index: i32,
+        #[allow(missing_docs)]
+        value: f32,
+    },
+    /// Stop all channels playing in a channel group.
+    Stop(&'a ChannelGroup),
+    /// Call a function with the rendering system.
+    Call(Box<dyn FnOnce(&System) -> Result + 'a>),
+}
+
+impl fmt::Debug for RenderAction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Play { sound, group } => f
+                .debug_struct("Play")
+                .field("sound", sound)
+                .field("group", group)
+                .finish(),
+            Self::SetParameter { dsp, index, value } => f
+                .debug_struct("SetParameter")
+                .field("dsp", dsp)
+                .field("index", index)
+                .field("value", value)
+                .finish(),
+            Self::Stop(group) => f.debug_tuple("Stop").field(group).finish(),
+            Self::Call(_) => f.write_str("Call(..)"),
+        }
+    }
+}
+
+/// A timeline of [`RenderAction`]s for [`render_offline`] to perform.
+#[derive(Debug, Default)]
+pub struct RenderScript<'a> {
+    actions: Vec<(Duration, RenderAction<'a>)>,
+}
+
+impl<'a> RenderScript<'a> {
+    /// Create an empty script.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Perform `action` at `time` into the rendered timeline.
+    ///
+    /// Actions are performed at the start of the first mix block at or after
+    /// `time`. Actions at the same time are performed in the order they were
+    /// added.
+    pub fn at(mut self, time: Duration, action: RenderAction<'a>) -> Self {
+        self.actions.push((time, action));
+        self
+    }
+}
+
+/// Render audio to a wav file faster than realtime.
+///
+/// This creates a [System] using [OutputType::WavWriterNrt], and calls
+/// `setup` to create sounds and DSPs and to return the [`RenderScript`] to
+/// perform. Objects created in `setup` can be [leaked](Handle::leak) to be
+/// referenced by the script; they are released along with the system once
+/// rendering has finished.
+///
+/// Time advances the same way as with [System::render_offline]: each
+/// [System::update] mixes one DSP buffer, and times are rounded up to a whole
+/// number of buffers, so rendering is deterministic: the same script and
+/// assets produce the same file. Rendering continues until every action has
+/// been performed and no channels are playing, or until
+/// [`max_duration`](OfflineRenderConfig::max_duration).
+///
+/// As only one system may exist at a time, this fails with
+/// [Error::Initialized] if another system already exists.
+///
+/// To render with a system you have already set up, use
+/// [System::render_offline] instead.
+pub fn render_offline(
+    config: OfflineRenderConfig,
+    setup: impl for<'a> FnOnce(&'a System) -> Result<RenderScript<'a>>,
+) -> Result<PathBuf> {
+    let Some(output) = config.output.to_str() else {
+        whoops!(no_panic: "offline render path {:?} is not UTF-8", config.output);
+        return Err(Error::FileBad);
+    };
+    let output = CString::new(output).map_err(|_| Error::FileBad)?;
+
+    let system = System::new()?;
+    system.set_output(OutputType::WavWriterNrt)?;
+    system.set_software_format(SoftwareFormat {
+        sample_rate: config.sample_rate,
+        speaker_mode: config.speaker_mode,
+        num_raw_speakers: 0,
+    })?;
+    // SAFETY: WavWriterNrt takes the output file name
+    unsafe {
+        system.init_ex(
+            config.max_channels,
+            InitFlags::StreamFromUpdate | InitFlags::MixFromUpdate,
+            output.as_ptr().cast(),
+        )?;
+    }
+
+    let mut actions = setup(&system)?
+        .actions
+        .into_iter()
+        .map(|(time, action)| Ok((system.offline_blocks(time)?, action)))
+        .collect::<Result<Vec<_>>>()?;
+    // stable, so actions at the same time keep their order
+    actions.sort_by_key(|&(block, _)| block);
+    let mut actions = actions.into_iter().peekable();
+
+    for block in 0..system.offline_blocks(config.max_duration)? {
+        while let Some((_, action)) = actions.next_if(|&(at, _)| at <= block) {
+            match action {
+                RenderAction::Play { sound, group } => {
+                    system.play_sound(sound, group)?;
+                },
+                RenderAction::SetParameter { dsp, index, value } => {
+                    dsp.set_parameter::<f32>(index, value)?;
+                },
+                RenderAction::Stop(group) => group.stop()?,
+                RenderAction::Call(f) => f(&system)?,
+            }
+        }
+        if actions.peek().is_none() && system.get_channels_playing()?.all == 0 {
+            break;
+        }
+
+        system.update()?;
+    }
+
+    drop(actions);
+    drop(system); // finishes writing the file
+    Ok(config.output)
+}
@@ -0,0 +1,279 @@
+use {fmod::*, std::time::Duration};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// How often [`UpdatePump`] calls [System::update].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UpdateRate {
+    /// Once per mix block, i.e. every
+    /// [DSP buffer length](System::get_dsp_buffer_size) samples at the
+    /// [software format](System::get_software_format)'s sample rate.
+    ///
+    /// This is the rate the mixer runs at with [InitFlags::MixFromUpdate] or
+    /// a non-realtime output, where each update mixes one block.
+    PerMixBlock,
+    /// A fixed number of times per second.
+    Hz(f32),
+}
+
+/// Calls [System::update] at a steady rate, independent of how often the
+/// game loop runs.
+///
+/// With [InitFlags::StreamFromUpdate] or [InitFlags::MixFromUpdate], and
+/// with the non-realtime outputs [OutputType::NoSoundNrt] and
+/// [OutputType::WavWriterNrt], FMOD's streaming and mixing are driven by
+/// updates: updating too quickly makes streams skip and stutter, and
+/// updating too slowly starves them. The pump keeps a fixed timestep
+/// accumulator so that updates happen at the [`UpdateRate`] on average.
+///
+/// Update counts are calculated from the total time passed to the pump, so
+/// rounding doesn't accumulate over time.
+#[derive(Debug)]
+pub struct UpdatePump<'a> {
+    system: &'a System,
+    timestep: Timestep,
+}
+
+impl<'a> UpdatePump<'a> {
+    /// Create a pump which updates `system` at `rate`.
+    ///
+    /// For [UpdateRate::PerMixBlock], the DSP buffer size and software format
+    /// are retrieved now, so create a new pump if they change.
+    pub fn new(system: &'a System, rate: UpdateRate) -> Result<Self> {
+        let timestep = match rate {
+            UpdateRate::PerMixBlock => {
+                let (buffer_length, _) = system.get_dsp_buffer_size()?;
+                let sample_rate = system.get_software_format()?.sample_rate;
+                Timestep::per_block(buffer_length, sample_rate).ok_or(Error::Internal)?
+            },
+            UpdateRate::Hz(hz) => match Timestep::hz(hz) {
+                Some(timestep) => timestep,
+                None => {
+                    whoops!(no_panic: "invalid update rate {hz} Hz");
+                    yeet!(Error::InvalidParam);
+                },
+            },
+        };
+        Ok(UpdatePump { system, timestep })
+    }
+
+    /// Sets the most updates a single [`tick`](Self::tick) runs.
+    ///
+    /// If the game loop stalls, catching up on every missed update at once
+    /// would only stall it further, so updates beyond this are dropped.
+    ///
+    /// <dl>
+    /// <dt>Default</dt><dd>4</dd>
+    /// </dl>
+    pub fn with_max_per_tick(mut self, max_per_tick: u32) -> Self {
+        self.timestep.max_per_tick = max_per_tick.max(1);
+        self
+    }
+
+    /// The time between updates.
+    pub fn interval(&self) -> Duration {
+        self.timestep.interval()
+    }
+
+    /// Advance by `real_dt` of wall clock time, running the updates which
+    /// have become due, and returning how many were run.
+    ///
+    /// At most [`max_per_tick`](Self::with_max_per_tick) updates are run;
+    /// any further updates which are due are dropped rather than carried
+    /// over to later ticks.
+    pub fn tick(&mut self, real_dt: Duration) -> Result<u32> {
+        let run = self.timestep.tick(real_dt);
+        for _ in 0..run {
+            self.system.update()?;
+            self.timestep.ran();
+        }
+        Ok(run)
+    }
+
+    /// Advance by `sim_time` of rendered audio, running exactly the number
+    /// of updates which cover it, and returning how many were run.
+    ///
+    /// This is for non-realtime outputs, where each update mixes one block;
+    /// use [UpdateRate::PerMixBlock] so that the rendered audio matches
+    /// `sim_time`. Time which doesn't fill an update is carried over to the
+    /// next call.
+    ///
+    /// Returns [Error::Unsupported] if the output is not a non-realtime
+    /// output type.
+    pub fn advance(&mut self, sim_time: Duration) -> Result<u64> {
+        let output = self.system.get_output()?;
+        if !matches!(output, OutputType::NoSoundNrt | OutputType::WavWriterNrt) {
+            whoops!(no_panic: "UpdatePump::advance called with realtime output {output:?}");
+            yeet!(Error::Unsupported);
+        }
+
+        let due = self.timestep.advance(sim_time);
+        for _ in 0..due {
+            self.system.update()?;
+            self.timestep.ran();
+        }
+        Ok(due)
+    }
+}
+
+/// The fixed timestep accumulator of an [`UpdatePump`], which counts the
+/// updates due without running them.
+///
+/// Updates which are due but haven't [run](Timestep::ran) yet, such as after
+/// [System::update] fails, stay due for the next tick.
+#[derive(Debug)]
+struct Timestep {
+    /// The update interval is `interval_num / interval_den` seconds.
+    interval_num: u128,
+    interval_den: u128,
+    max_per_tick: u32,
+    elapsed_nanos: u128,
+    updates: u128,
+}
+
+impl Timestep {
+    fn new(interval_num: u128, interval_den: u128) -> Self {
+        Timestep {
+            interval_num,
+            interval_den,
+            max_per_tick: 4,
+            elapsed_nanos: 0,
+            updates: 0,
+        }
+    }
+
+    /// One update per block of `buffer_length` samples at `sample_rate`.
+    fn per_block(buffer_length: u32, sample_rate: i32) -> Option<Self> {
+        if buffer_length == 0 || sample_rate <= 0 {
+            return None;
+        }
+        Some(Timestep::new(buffer_length as u128, sample_rate as u128))
+    }
+
+    /// `hz` updates per second, rounded to a whole number of nanoseconds.
+    fn hz(hz: f32) -> Option<Self> {
+        if !(hz > 0.0 && hz.is_finite()) {
+            return None;
+        }
+        let nanos = (NANOS_PER_SEC as f64 / hz as f64).round().max(1.0);
+        Some(Timestep::new(nanos as u128, NANOS_PER_SEC))
+    }
+
+    fn interval(&self) -> Duration {
+        let nanos = self.interval_num * NANOS_PER_SEC / self.interval_den;
+        Duration::from_nanos(nanos as u64)
+    }
+
+    /// The number of updates due after `elapsed_nanos` in total.
+    fn updates_due(&self, elapsed_nanos: u128) -> u128 {
+        elapsed_nanos * self.interval_den / (self.interval_num * NANOS_PER_SEC)
+    }
+
+    /// Advance by `real_dt`, returning how many updates to run, at most
+    /// `max_per_tick`.
+    fn tick(&mut self, real_dt: Duration) -> u32 {
+        self.elapsed_nanos += real_dt.as_nanos();
+        let due = self.updates_due(self.elapsed_nanos) - self.updates;
+        let run = due.min(self.max_per_tick as u128);
+        // drop the backlog, so a stall doesn't cause a burst of updates later
+        self.updates += due - run;
+        run as u32
+    }
+
+    /// Advance by `sim_time`, returning how many updates to run.
+    fn advance(&mut self, sim_time: Duration) -> u64 {
+        self.elapsed_nanos += sim_time.as_nanos();
+        (self.updates_due(self.elapsed_nanos) - self.updates) as u64
+    }
+
+    /// Records that one of the due updates has run.
+    fn ran(&mut self) {
+        self.updates += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs every update `tick` returns, returning how many that was.
+    fn tick(timestep: &mut Timestep, real_dt: Duration) -> u32 {
+        let run = timestep.tick(real_dt);
+        for _ in 0..run {
+            timestep.ran();
+        }
+        run
+    }
+
+    #[test]
+    fn rejects_invalid_rates() {
+        assert!(Timestep::hz(0.0).is_none());
+        assert!(Timestep::hz(-60.0).is_none());
+        assert!(Timestep::hz(f32::NAN).is_none());
+        assert!(Timestep::hz(f32::INFINITY).is_none());
+        assert!(Timestep::per_block(0, 48000).is_none());
+        assert!(Timestep::per_block(1024, 0).is_none());
+    }
+
+    #[test]
+    fn interval() {
+        let timestep = Timestep::hz(60.0).unwrap();
+        assert_eq!(timestep.interval(), Duration::from_nanos(16_666_667));
+        let timestep = Timestep::per_block(1024, 48000).unwrap();
+        assert_eq!(timestep.interval(), Duration::from_nanos(21_333_333));
+    }
+
+    #[test]
+    fn stall_is_capped_per_tick() {
+        let mut timestep = Timestep::hz(100.0).unwrap();
+        assert_eq!(tick(&mut timestep, Duration::from_secs(1)), 4);
+        // the rest of the backlog was dropped, not carried over
+        assert_eq!(tick(&mut timestep, Duration::ZERO), 0);
+        assert_eq!(tick(&mut timestep, Duration::from_millis(10)), 1);
+
+        timestep.max_per_tick = 1;
+        for _ in 0..100 {
+            assert!(tick(&mut timestep, Duration::from_millis(250)) <= 1);
+        }
+    }
+
+    #[test]
+    fn total_updates_match_elapsed_time() {
+        let mut timestep = Timestep::hz(60.0).unwrap();
+        let mut total = 0;
+        // 10 seconds of uneven frames, all shorter than the cap
+        for frame in 0..1000 {
+            total += tick(&mut timestep, Duration::from_millis(7 + frame % 7));
+        }
+        let elapsed = (0..1000).map(|frame| 7 + frame % 7).sum::<u64>();
+        assert_eq!(elapsed, 9_997);
+        // 9.997s / 16.666667ms, rounded down
+        assert_eq!(total, 599);
+    }
+
+    #[test]
+    fn advance_covers_exact_blocks() {
+        let mut timestep = Timestep::per_block(1024, 48000).unwrap();
+        let mut total = 0;
+        for _ in 0..10_000 {
+            let due = timestep.advance(Duration::from_millis(1));
+            for _ in 0..due {
+                timestep.ran();
+            }
+            total += due;
+        }
+        // 10 seconds is 480000 samples, 468.75 blocks
+        assert_eq!(total, 468);
+        // the partial block is carried over
+        assert_eq!(timestep.advance(Duration::from_nanos(5_333_334)), 1);
+    }
+
+    #[test]
+    fn updates_which_did_not_run_stay_due() {
+        let mut timestep = Timestep::hz(100.0).unwrap();
+        assert_eq!(timestep.tick(Duration::from_millis(30)), 3);
+        timestep.ran();
+        // the second update failed, so two are still due
+        assert_eq!(timestep.tick(Duration::ZERO), 2);
+    }
+}
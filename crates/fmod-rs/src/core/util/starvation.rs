@@ -0,0 +1,188 @@
+use {fmod::*, std::cell::Cell};
+
+/// Pauses a streaming [`Channel`] while its stream is starving.
+///
+/// When a stream (typically a [`System::create_stream`] of a network URL)
+/// decodes faster than data arrives, the decode buffer loops on itself and
+/// replays old data. Call [`StarvationGuard::tick`] once per frame to pause
+/// the channel while starving and resume it once enough data is buffered.
+///
+/// The channel is resumed only once the stream buffer has refilled to the
+/// [resume threshold](StarvationGuard::with_resume_threshold), rather than as
+/// soon as it stops starving, so that a barely keeping up stream doesn't
+/// flap between paused and playing every frame. A channel which was already
+/// paused by the application is left alone.
+#[derive(Debug)]
+pub struct StarvationGuard<'a> {
+    channel: &'a Channel,
+    sound: &'a Sound,
+    resume_percent: u32,
+    paused_for_starvation: Cell<bool>,
+}
+
+/// The health of a stream as reported by [`StarvationGuard::tick`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StreamHealth {
+    /// Open state of the sound.
+    pub open_state: OpenState,
+    /// Filled percentage of the stream's file buffer.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Percent</dd>
+    /// <dt>Range</dt><dd>[0, 100]</dd>
+    /// </dl>
+    pub buffered_percent: u32,
+    /// Whether the channel is currently paused by the guard.
+    pub paused_for_starvation: bool,
+    /// Whether the disk is currently being accessed for the sound.
+    pub disk_busy: bool,
+}
+
+impl<'a> StarvationGuard<'a> {
+    /// Guard `channel`, which is playing the stream `sound`.
+    pub fn new(channel: &'a Channel, sound: &'a Sound) -> Self {
+        StarvationGuard {
+            channel,
+            sound,
+            resume_percent: 50,
+            paused_for_starvation: Cell::new(false),
+        }
+    }
+
+    /// Sets how full the stream buffer must be before a starved channel is
+    /// resumed.
+    ///
+    /// <dl>
+    /// <dt>Units</dt><dd>Percent</dd>
+    /// <dt>Range</dt><dd>[0, 100]</dd>
+    /// <dt>Default</dt><dd>50</dd>
+    /// </dl>
+    pub fn with_resume_threshold(self, percent: u32) -> Self {
+        StarvationGuard {
+            resume_percent: percent.min(100),
+            ..self
+        }
+    }
+
+    /// Check the stream's state, pausing or resuming the channel as needed.
+    pub fn tick(&self) -> Result<StreamHealth> {
+        let info = self.sound.get_open_state_info()?;
+        let was_paused = self.paused_for_starvation.get();
+        let paused = starvation_paused(was_paused, &info, self.resume_percent);
+
+        if paused && !was_paused {
+            if self.channel.get_paused()? {
+                // paused by the application; not ours to resume
+                return Ok(self.health(&info));
+            }
+            self.channel.set_paused(true)?;
+        } else if !paused && was_paused {
+            self.channel.set_paused(false)?;
+        }
+        self.paused_for_starvation.set(paused);
+
+        Ok(self.health(&info))
+    }
+
+    fn health(&self, info: &OpenStateInfo) -> StreamHealth {
+        StreamHealth {
+            open_state: info.state,
+            buffered_percent: info.percent_buffered,
+            paused_for_starvation: self.paused_for_starvation.get(),
+            disk_busy: info.disk_busy,
+        }
+    }
+}
+
+/// Whether a channel should be paused for starvation, given whether it
+/// currently is. Starting to starve pauses immediately, but resuming waits
+/// until the buffer has refilled to `resume_percent`.
+fn starvation_paused(paused: bool, info: &OpenStateInfo, resume_percent: u32) -> bool {
+    if info.starving {
+        true
+    } else if paused {
+        info.percent_buffered < resume_percent
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(percent_buffered: u32, starving: bool) -> OpenStateInfo {
+        OpenStateInfo {
+            state: OpenState::Playing,
+            percent_buffered,
+            starving,
+            disk_busy: false,
+        }
+    }
+
+    /// Runs `script` of (percent buffered, starving) ticks through
+    /// [`starvation_paused`], returning the paused state after each tick.
+    fn run(script: &[(u32, bool)], resume_percent: u32) -> Vec<bool> {
+        let mut paused = false;
+        script
+            .iter()
+            .map(|&(percent, starving)| {
+                paused = starvation_paused(paused, &info(percent, starving), resume_percent);
+                paused
+            })
+            .collect()
+    }
+
+    #[test]
+    fn healthy_stream_is_never_paused() {
+        let script = [(100, false), (40, false), (0, false), (100, false)];
+        assert_eq!(run(&script, 50), [false; 4]);
+    }
+
+    #[test]
+    fn starving_pauses_immediately() {
+        let script = [(80, false), (90, true)];
+        assert_eq!(run(&script, 50), [false, true]);
+    }
+
+    #[test]
+    fn resume_waits_for_threshold() {
+        let script = [
+            (10, true),  // starts starving
+            (20, false), // no longer starving, but below threshold
+            (49, false),
+            (50, false), // reached threshold
+            (30, false), // dipping below threshold doesn't pause again
+            (5, true),   // only starving does
+            (60, false),
+        ];
+        assert_eq!(
+            run(&script, 50),
+            [true, true, true, false, false, true, false],
+        );
+    }
+
+    #[test]
+    fn starving_recurs_before_threshold() {
+        let script = [
+            (10, true),
+            (30, false),
+            (20, true),
+            (40, false),
+            (70, false),
+        ];
+        assert_eq!(run(&script, 60), [true, true, true, true, false]);
+    }
+
+    #[test]
+    fn zero_threshold_resumes_as_soon_as_not_starving() {
+        let script = [(0, true), (0, false), (0, true), (0, false)];
+        assert_eq!(run(&script, 0), [true, false, true, false]);
+    }
+
+    #[test]
+    fn full_threshold_requires_full_buffer() {
+        let script = [(0, true), (99, false), (100, false)];
+        assert_eq!(run(&script, 100), [true, true, false]);
+    }
+}
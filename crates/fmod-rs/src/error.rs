@@ -1,6 +1,8 @@
 use {
+    crate::utils::AbortOnUnwind,
     fmod::{raw::*, *},
-    std::{error::Error as _, fmt, io, num::NonZeroI32},
+    parking_lot::Mutex,
+    std::{any::Any, error::Error as _, fmt, io, mem, num::NonZeroI32},
 };
 
 macro_rules! error_enum_struct {
@@ -51,7 +53,7 @@ macro_rules! error_enum_struct {
         }
 
         static_assert! {
-            [$($Name::$Variant),*].len() == (FMOD_ERR_TOOMANYSAMPLES + 1) as usize,
+            [$($Name::$Variant),*].len() == (FMOD_ERR_TOOMANYSAMPLES + 3) as usize,
             "fmod::Error is missing some variant(s)",
         }
 
@@ -236,9 +238,140 @@ error_enum_struct! {
 
         /// Rust code panicked in an FMOD callback.
         RustPanicked = -1,
+        /// A [`System`] function which can't be used while the mixer is
+        /// suspended was called while suspended by [`System::suspend_mixer`].
+        MixerSuspended = -2,
+        /// A [`System`] couldn't be closed because objects created with it
+        /// haven't been released.
+        /// [`CloseError::ObjectsStillAlive`] reports how many.
+        ObjectsStillAlive = -3,
     }
 }
 
+impl Error {
+    /// Retrieves the raw `FMOD_RESULT` code for this error.
+    ///
+    /// This is primarily useful for logging and telemetry, where the numeric
+    /// code is more stable than the human-readable description.
+    pub const fn code(self) -> i32 {
+        self.raw.get()
+    }
+
+    /// Creates an error from a raw `FMOD_RESULT` code.
+    ///
+    /// Returns `None` if the code is `FMOD_OK` (zero).
+    pub const fn from_code(code: i32) -> Option<Error> {
+        match NonZeroI32::new(code) {
+            Some(raw) => Some(Error { raw }),
+            None => None,
+        }
+    }
+
+    /// Whether this error is transient, such that retrying the operation
+    /// at a later time may succeed.
+    ///
+    /// Transient errors are [`Error::NotReady`], [`Error::NetWouldBlock`],
+    /// and [`Error::Truncated`]. All other errors are considered fatal to
+    /// the operation which produced them.
+    pub fn is_recoverable(self) -> bool {
+        matches!(
+            self,
+            Error::NotReady | Error::NetWouldBlock | Error::Truncated
+        )
+    }
+
+    /// Whether this error indicates that a [`Channel`] is no longer playing.
+    ///
+    /// FMOD invalidates [`Channel`] handles when the channel stops or is
+    /// stolen by the virtual voice system, so [`Error::InvalidHandle`] and
+    /// [`Error::ChannelStolen`] are expected results from channel calls.
+    /// See also [`Channel::try_call`].
+    pub fn is_channel_gone(self) -> bool {
+        matches!(self, Error::InvalidHandle | Error::ChannelStolen)
+    }
+
+    /// Whether this error is one of the `FILE_*` errors, such as
+    /// [`Error::FileNotFound`] or [`Error::FileBad`].
+    pub fn is_file_error(self) -> bool {
+        matches!(
+            self,
+            Error::FileBad
+                | Error::FileCouldNotSeek
+                | Error::FileDiskEjected
+                | Error::FileEof
+                | Error::FileEndOfData
+                | Error::FileNotFound
+        )
+    }
+
+    /// Whether this error is one of the `HTTP_*` errors, such as
+    /// [`Error::HttpTimeout`].
+    pub fn is_http_error(self) -> bool {
+        matches!(
+            self,
+            Error::Http
+                | Error::HttpAccess
+                | Error::HttpProxyAuth
+                | Error::HttpServerError
+                | Error::HttpTimeout
+        )
+    }
+
+    /// Whether this error is one of the `HTTP_*` or `NET_*` errors, such as
+    /// [`Error::NetConnect`].
+    ///
+    /// Network errors from netstreams are often worth retrying, whereas
+    /// errors such as [`Error::Format`] are not.
+    pub fn is_network_error(self) -> bool {
+        self.is_http_error()
+            || matches!(
+                self,
+                Error::NetConnect | Error::NetSocketError | Error::NetUrl | Error::NetWouldBlock
+            )
+    }
+
+    /// Whether this error is [`Error::NotReady`], returned when an operation
+    /// was attempted on a resource which is still loading, such as a
+    /// [`Mode::NonBlocking`] sound.
+    pub fn is_not_ready(self) -> bool {
+        self == Error::NotReady
+    }
+}
+
+/// The payload of the most recent panic caught in an FMOD callback.
+static CALLBACK_PANIC: Mutex<Option<Box<dyn Any + Send>>> = Mutex::new(None);
+
+/// Retrieves the payload of the most recent panic caught in an FMOD
+/// callback, if any, and clears it.
+///
+/// Panics in callbacks can't unwind into FMOD, so they are caught and FMOD
+/// is given an error instead ([`Error::RustPanicked`], or an error specific
+/// to the callback, such as [`Error::FileBad`] for file system callbacks).
+/// Callbacks typically run on FMOD's threads, so check this from the main
+/// thread (e.g. after [`System::update`]) to observe them.
+pub fn take_callback_panic() -> Option<Box<dyn Any + Send>> {
+    CALLBACK_PANIC.lock().take()
+}
+
+/// Resumes unwinding the most recent panic caught in an FMOD callback, if
+/// any, from the calling thread.
+///
+/// This makes a panic in a callback behave like a panic on the calling
+/// thread, at a point where unwinding is safe.
+pub fn resume_callback_panic() {
+    if let Some(payload) = take_callback_panic() {
+        std::panic::resume_unwind(payload);
+    }
+}
+
+pub(crate) fn record_callback_panic(payload: Box<dyn Any + Send>) {
+    let previous = CALLBACK_PANIC.lock().replace(payload);
+    // dropping a payload can itself panic, which mustn't unwind into FMOD
+    let guard = AbortOnUnwind;
+    drop(previous);
+    mem::forget(guard);
+}
+
 /// Type alias for FMOD function results.
 pub type Result<T = (), E = Error> = std::result::Result<T, E>;
 
@@ -247,6 +380,12 @@ impl std::error::Error for Error {
         if *self == Error::RustPanicked {
             return "Rust code panicked (attempted unwind).";
         }
+        if *self == Error::MixerSuspended {
+            return "FMOD API called while the mixer is suspended.";
+        }
+        if *self == Error::ObjectsStillAlive {
+            return "Objects created with the system are still alive.";
+        }
 
         // SAFETY: FMOD_ErrorString is a C `static` function which thus isn't
         // bindgen'd, but hand implemented in fmod-core-sys. As such, we're
@@ -285,14 +424,16 @@ impl ResultExt for Result<(), Error> {
     }
 }
 
+/// Maps FMOD errors onto the closest [`io::ErrorKind`], falling back to
+/// [`io::ErrorKind::Other`]. The FMOD error is kept as the inner error.
 impl From<Error> for io::Error {
     fn from(err: Error) -> Self {
-        match err {
+        let kind = match err {
             | Error::DspNotFound
             | Error::TagNotFound
             | Error::FileNotFound
-            | Error::EventNotFound => io::ErrorKind::NotFound.into(),
-            | Error::NetWouldBlock => io::ErrorKind::WouldBlock.into(),
+            | Error::EventNotFound => io::ErrorKind::NotFound,
+            | Error::NetWouldBlock => io::ErrorKind::WouldBlock,
             | Error::InvalidFloat
             | Error::InvalidHandle
             | Error::InvalidParam
@@ -300,14 +441,20 @@ impl From<Error> for io::Error {
             | Error::InvalidSpeaker
             | Error::InvalidSyncPoint
             | Error::InvalidThread
-            | Error::InvalidVector => io::ErrorKind::InvalidInput.into(),
+            | Error::InvalidVector
+            | Error::NetUrl => io::ErrorKind::InvalidInput,
+            | Error::FileBad | Error::Format => io::ErrorKind::InvalidData,
+            | Error::FileEof | Error::FileEndOfData => io::ErrorKind::UnexpectedEof,
             | Error::BadCommand
             | Error::DspType
-            | Error::Format
+            | Error::FileCouldNotSeek
             | Error::Unimplemented
-            | Error::Unsupported => io::ErrorKind::Unsupported.into(),
-            | Error::HttpTimeout | Error::EventLiveUpdateTimeout => io::ErrorKind::TimedOut.into(),
-            err => io::Error::new(io::ErrorKind::Other, err),
-        }
+            | Error::Unsupported => io::ErrorKind::Unsupported,
+            | Error::NetConnect => io::ErrorKind::ConnectionRefused,
+            | Error::Memory => io::ErrorKind::OutOfMemory,
+            | Error::HttpTimeout | Error::EventLiveUpdateTimeout => io::ErrorKind::TimedOut,
+            _ => io::ErrorKind::Other,
+        };
+        io::Error::new(kind, err)
     }
 }
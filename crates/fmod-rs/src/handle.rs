@@ -1,11 +1,14 @@
 use {
-    parking_lot::RwLock,
+    parking_lot::{Mutex, RwLock},
     std::{
+        any::TypeId,
         fmt,
+        marker::PhantomData,
         mem::ManuallyDrop,
         ops::Deref,
         panic::{RefUnwindSafe, UnwindSafe},
         ptr,
+        sync::atomic::{AtomicBool, AtomicU64, Ordering},
     },
 };
 
@@ -112,6 +115,19 @@ impl<'a, T: ?Sized + Resource> Handle<'a, T> {
         this
     }
 
+    /// Like [`Handle::new`], for resources created by `system`, so that
+    /// [`System::close_scope`](fmod::System::close_scope) can check for them
+    /// in debug builds.
+    pub(crate) unsafe fn new_derived(system: &fmod::System, raw: *mut T::Raw) -> Self {
+        #[cfg(debug_assertions)]
+        LIVE_DERIVED
+            .lock()
+            .insert(raw as usize, system.as_raw() as usize);
+        #[cfg(not(debug_assertions))]
+        let _ = system;
+        Self::new(raw)
+    }
+
     /// Manually release this FMOD resource.
     ///
     /// If an error occurs, the resource is leaked for convenience with `?`. If
@@ -135,6 +151,256 @@ impl<'a, T: ?Sized + Resource> Handle<'a, T> {
     pub unsafe fn unleak(this: &'a T) -> Self {
         Self::from_raw(this.as_raw())
     }
+
+    /// Create a non-owning [`WeakHandle`] to this FMOD resource.
+    pub fn downgrade(this: &Self) -> WeakHandle<T> {
+        let addr = this.as_raw() as usize;
+        let mut weak = LIVE_WEAK.lock();
+        let id = match weak.live.iter().find(|entry| entry.addr == addr) {
+            Some(entry) => entry.id,
+            None => {
+                let id = NEXT_WEAK_ID.fetch_add(1, Ordering::Relaxed);
+                weak.live.push(WeakEntry { addr, id, pins: 0 });
+                TRACKING_WEAK.store(true, Ordering::Relaxed);
+                id
+            },
+        };
+        WeakHandle {
+            raw: this.as_raw(),
+            id,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// The state of [weak handles](WeakHandle). The lock is only held briefly,
+/// never while user code runs:
+///
+/// - [`WeakHandle::upgrade`] pins the resource in its entry, and the
+///   [`WeakGuard`] unpins it when dropped.
+/// - Releasing a resource, or the system which created it, while it is pinned
+///   defers the release until the last guard is dropped, instead of waiting
+///   for the guards (which could deadlock).
+/// - While a release is deferred or in progress, no upgrade can succeed.
+///
+/// Which system created a resource isn't tracked, so a system counts as the
+/// creator of every resource.
+static LIVE_WEAK: Mutex<WeakState> = Mutex::new(WeakState {
+    live: Vec::new(),
+    releasing: Vec::new(),
+    deferred: Vec::new(),
+});
+static NEXT_WEAK_ID: AtomicU64 = AtomicU64::new(0);
+/// Skips locking [`LIVE_WEAK`] on release if no weak handles were ever made.
+static TRACKING_WEAK: AtomicBool = AtomicBool::new(false);
+
+struct WeakState {
+    /// Resources which have been [downgraded](Handle::downgrade) and not yet
+    /// released.
+    live: Vec<WeakEntry>,
+    /// Addresses of resources being released, and whether they are systems.
+    releasing: Vec<(usize, bool)>,
+    /// Releases waiting for guards to be dropped, by address.
+    deferred: Vec<DeferredRelease>,
+}
+
+/// The address of a resource, whether it is a system, and how to release it.
+type DeferredRelease = (usize, bool, fn(usize));
+
+struct WeakEntry {
+    addr: usize,
+    /// Unique, as addresses can be reused.
+    id: u64,
+    /// The number of live [`WeakGuard`]s.
+    pins: usize,
+}
+
+impl WeakState {
+    /// Whether releasing `addr` must wait for a guard, of the resource itself
+    /// or, if it is a system, of any resource.
+    fn is_pinned(&self, addr: usize, system: bool) -> bool {
+        self.live
+            .iter()
+            .any(|entry| entry.pins != 0 && (system || entry.addr == addr))
+    }
+}
+
+/// Whether releasing a `T` releases the resources created with it.
+fn is_system<T: ?Sized + 'static>() -> bool {
+    TypeId::of::<T>() == TypeId::of::<fmod::System>()
+}
+
+/// Called by [`Resource::release`] before releasing a `T`. Returns `false`
+/// if the release has been deferred to `release`, called with `addr` once no
+/// [`WeakGuard`]s pin it.
+pub(crate) fn begin_release<T: ?Sized + 'static>(addr: usize, release: fn(usize)) -> bool {
+    if !TRACKING_WEAK.load(Ordering::Relaxed) {
+        return true;
+    }
+    let system = is_system::<T>();
+    let mut weak = LIVE_WEAK.lock();
+    if !weak.releasing.contains(&(addr, system)) {
+        weak.releasing.push((addr, system));
+    }
+    if weak.is_pinned(addr, system) {
+        weak.deferred.push((addr, system, release));
+        return false;
+    }
+    true
+}
+
+/// Called by [`Resource::release`] after releasing a `T`. Once it has been
+/// released successfully, its weak handles, and if it is a system, those of
+/// all resources, can no longer be upgraded; if releasing failed, they are
+/// still valid.
+pub(crate) fn end_release<T: ?Sized + 'static>(addr: usize, released: bool) {
+    if !TRACKING_WEAK.load(Ordering::Relaxed) {
+        return;
+    }
+    let system = is_system::<T>();
+    let mut weak = LIVE_WEAK.lock();
+    if released {
+        weak.live.retain(|entry| !system && entry.addr != addr);
+    }
+    weak.releasing
+        .retain(|&releasing| releasing != (addr, system));
+}
+
+/// Resources created by each system and not yet released, by address, with
+/// the address of their system. This costs a global lock for every create
+/// and release, so it is only tracked in debug builds.
+#[cfg(debug_assertions)]
+static LIVE_DERIVED: Mutex<std::collections::BTreeMap<usize, usize>> =
+    Mutex::new(std::collections::BTreeMap::new());
+
+/// Called by [`Resource::release`] before releasing a resource.
+#[cfg(debug_assertions)]
+pub(crate) fn untrack_derived(addr: usize) {
+    LIVE_DERIVED.lock().remove(&addr);
+}
+
+/// Called after releasing a system, which releases all of its resources.
+#[cfg(debug_assertions)]
+pub(crate) fn untrack_all_derived(system: usize) {
+    LIVE_DERIVED.lock().retain(|_, owner| *owner != system);
+}
+
+/// The number of resources created by `system` which haven't been released.
+#[cfg(debug_assertions)]
+pub(crate) fn count_derived(system: usize) -> usize {
+    let live = LIVE_DERIVED.lock();
+    live.values().filter(|&&owner| owner == system).count()
+}
+
+/// A non-owning reference to an FMOD resource owned by a [`Handle`].
+///
+/// Create with [`Handle::downgrade`]. Unlike a plain `&T`, this can't
+/// dangle: once the resource is released, [`upgrade`](Self::upgrade) returns
+/// `None`. This isn't free; every release takes a global lock once any weak
+/// handle has been created.
+///
+/// Releasing a [`System`](fmod::System) invalidates the weak handles to the
+/// resources created from it, as it releases all of them. If releasing a
+/// resource fails, its weak handles remain valid.
+pub struct WeakHandle<T: ?Sized + Resource> {
+    raw: *mut T::Raw,
+    id: u64,
+    _marker: PhantomData<fn() -> T>,
+}
+
+unsafe impl<T: ?Sized + Resource> Send for WeakHandle<T> where T: Sync {}
+unsafe impl<T: ?Sized + Resource> Sync for WeakHandle<T> where T: Sync {}
+
+impl<T: ?Sized + Resource> Copy for WeakHandle<T> {}
+impl<T: ?Sized + Resource> Clone for WeakHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized + Resource> fmt::Debug for WeakHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WeakHandle").field(&self.raw).finish()
+    }
+}
+
+impl<T: ?Sized + Resource> WeakHandle<T> {
+    /// Access the resource, if it hasn't been released.
+    ///
+    /// The resource can't be released while the returned guard is alive;
+    /// releasing it (or its system) in the meantime succeeds, but the release
+    /// is only performed once the last guard is dropped, and no further
+    /// upgrades succeed. Errors from such deferred releases are logged.
+    pub fn upgrade(&self) -> Option<WeakGuard<'_, T>> {
+        let mut weak = LIVE_WEAK.lock();
+        let weak = &mut *weak;
+        let addr = self.raw as usize;
+        let entry = weak
+            .live
+            .iter_mut()
+            .find(|entry| entry.addr == addr && entry.id == self.id)?;
+        if weak
+            .releasing
+            .iter()
+            .any(|&(releasing, system)| system || releasing == entry.addr)
+        {
+            return None;
+        }
+        entry.pins += 1;
+        Some(WeakGuard {
+            addr,
+            this: unsafe { T::from_raw(self.raw) },
+        })
+    }
+
+    /// Whether the resource has been released.
+    pub fn is_released(&self) -> bool {
+        self.upgrade().is_none()
+    }
+}
+
+/// A resource accessed through a [`WeakHandle`], which can't be released
+/// while this guard is alive.
+pub struct WeakGuard<'a, T: ?Sized + Resource> {
+    addr: usize,
+    this: &'a T,
+}
+
+impl<T: ?Sized + Resource> Drop for WeakGuard<'_, T> {
+    fn drop(&mut self) {
+        let mut weak = LIVE_WEAK.lock();
+        if let Some(entry) = weak.live.iter_mut().find(|entry| entry.addr == self.addr) {
+            entry.pins -= 1;
+        }
+        let mut ready = Vec::new();
+        let mut deferred = std::mem::take(&mut weak.deferred);
+        deferred.retain(|&(addr, system, release)| {
+            let pinned = weak.is_pinned(addr, system);
+            if !pinned {
+                ready.push((addr, release));
+            }
+            pinned
+        });
+        weak.deferred = deferred;
+        drop(weak);
+        for (addr, release) in ready {
+            release(addr);
+        }
+    }
+}
+
+impl<T: ?Sized + Resource> fmt::Debug for WeakGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.this.fmt(f)
+    }
+}
+
+impl<T: ?Sized + Resource> Deref for WeakGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.this
+    }
 }
 
 // Using references is scary to me, but required for ergonomics, and almost
@@ -188,14 +454,20 @@ pub trait HandleExt<T: ?Sized + Resource>: Sealed {
 impl<T: ?Sized + Resource> Sealed for Option<Handle<'_, T>> {}
 impl<T: ?Sized + Resource> HandleExt<T> for Option<Handle<'_, T>> {
     fn release(&mut self) -> fmod::Result {
+        // formatted up front, as Debug can inspect the live resource
+        #[cfg(feature = "log")]
+        let name = match self {
+            Some(this) if log::log_enabled!(log::Level::Trace) => format!("{this:?}"),
+            _ => String::new(),
+        };
         let result = match self {
             Some(this) => unsafe { T::release(this.as_raw()) },
             None => yeet!(fmod::Error::InvalidHandle),
         };
         if result.is_ok() {
-            let this = ManuallyDrop::new(self.take().unwrap());
+            let _ = ManuallyDrop::new(self.take().unwrap());
             #[cfg(feature = "log")]
-            log::trace!("Released {this:?}");
+            log::trace!("Released {name}");
         };
         result
     }
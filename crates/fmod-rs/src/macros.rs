@@ -57,6 +57,29 @@ macro_rules! whoops {
     }};
 }
 
+/// Mixing objects from different systems in one call is UB that FMOD doesn't
+/// diagnose, so with debug assertions calls taking multiple objects check it.
+macro_rules! debug_assert_same_system {
+    ($a:expr, $b:expr $(,)?) => {
+        debug_assert_same_system!(@ $a.get_system_object(), $a, $b);
+    };
+    (system: $system:expr, $b:expr $(,)?) => {
+        debug_assert_same_system!(@ Ok::<_, Error>($system), $system, $b);
+    };
+    (@ $a_system:expr, $a:expr, $b:expr) => {
+        if cfg!(debug_assertions) {
+            if let (Ok(a_system), Ok(b_system)) = ($a_system, $b.get_system_object()) {
+                if !::std::ptr::eq(a_system, b_system) {
+                    whoops!(
+                        "objects from different systems used together: {:?} (of {:p}) and {:?} (of {:p})",
+                        $a, a_system, $b, b_system,
+                    );
+                }
+            }
+        }
+    };
+}
+
 macro_rules! opaque_type {
     {
         $(#[$meta:meta])*
@@ -90,7 +113,7 @@ macro_rules! raw {
     ($mac:ident! { $(#[$meta:meta])* pub $($tt:tt)* }) => {
         $mac! {
             #[allow(clippy::missing_safety_doc, missing_docs)]
-            #[doc(cfg(feature = "raw"))]
+            #[cfg_attr(feature = "unstable", doc(cfg(feature = "raw")))]
             $(#[$meta])* pub $($tt)*
         }
     };
@@ -217,15 +240,29 @@ macro_rules! fmod_class {
 
             #[allow(clippy::redundant_closure_call)]
             unsafe fn release(this: *mut Self::Raw) -> fmod::Result {
+                let deferred = |this: usize| unsafe {
+                    if let Err(error) = <Self as ::fmod::Resource>::release(this as *mut Self::Raw) {
+                        whoops!(no_panic: "Error releasing {}: {error}", stringify!($Name));
+                    }
+                };
+                if !$crate::handle::begin_release::<Self>(this as usize, deferred) {
+                    return Ok(());
+                }
                 ::std::ptr::drop_in_place(Self::from_raw(this) as *const Self as *mut Self);
-                ffi!(($release)(this))?;
-                Ok(())
+                #[cfg(debug_assertions)]
+                $crate::handle::untrack_derived(this as usize);
+                let result = ffi!(($release)(this));
+                $crate::handle::end_release::<Self>(this as usize, result.is_ok());
+                result
             }
         }
 
         impl ::std::fmt::Debug for $Name {
             fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
-                write!(f, concat!($prefix, stringify!($Name), "({:p})"), self)
+                // classes with live introspection shadow this with an inherent method
+                #[allow(unused_imports)]
+                use $crate::utils::NoIntrospection as _;
+                self.fmt_introspect(f, format_args!(concat!($prefix, stringify!($Name), "({:p})"), self))
             }
         }
     };
@@ -428,6 +465,11 @@ macro_rules! fmod_flags {
             pub fn is_set(self, variant: Self) -> bool {
                 self & variant == variant
             }
+
+            /// Check whether *any* flags of the argument are set.
+            pub fn intersects(self, variant: Self) -> bool {
+                self & variant != Self::zeroed()
+            }
         }
 
         fmod_flags_ops!($Name: std::ops::BitAnd bitand & std::ops::BitAndAssign bitand_assign);
@@ -693,10 +735,10 @@ macro_rules! fmod_enum {
                 dsp.set_parameter::<$Raw>(index, value.into_raw())
             }
 
-            // fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<Self> {
-            //     let value = dsp.get_parameter::<$Raw>(index)?;
-            //     Self::try_from_raw(value)
-            // }
+            fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<Self> {
+                let value = dsp.get_parameter::<$Raw>(index)?;
+                Self::try_from_raw(value)
+            }
 
             fn get_dsp_parameter_string<'a>(dsp: &Dsp, index: i32, bytes: &'a mut [u8]) -> Result<&'a str> {
                 <$Raw>::get_dsp_parameter_string(dsp, index, bytes)
@@ -874,15 +916,17 @@ macro_rules! fmod_struct {
                 <[u8; ::std::mem::size_of::<Self>()]>::set_dsp_parameter(dsp, index, ::bytemuck::cast_ref(value))
             }
 
-            // fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<Self> {
-            //     let value = <[u8; ::std::mem::size_of::<Self>()]>::get_dsp_parameter(dsp, index)?;
-            //     Ok(::bytemuck::cast(value))
-            // }
+            fn get_dsp_parameter(dsp: &Dsp, index: i32) -> Result<Self> {
+                let value = <[u8; ::std::mem::size_of::<Self>()]>::get_dsp_parameter(dsp, index)?;
+                Ok(::bytemuck::cast(value))
+            }
 
             fn get_dsp_parameter_string<'a>(dsp: &Dsp, index: i32, bytes: &'a mut [u8]) -> Result<&'a str> {
                 <[u8; ::std::mem::size_of::<Self>()]>::get_dsp_parameter_string(dsp, index, bytes)
             }
         }
+
+        impl ::fmod::effect::DspParamData for $Name {}
     };
     {
         #![fmod_no_pod]
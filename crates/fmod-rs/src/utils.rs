@@ -4,12 +4,38 @@ use {
         borrow::Cow,
         ffi::c_char,
         ffi::CStr,
+        fmt,
         mem::{self, MaybeUninit},
         panic::AssertUnwindSafe,
         ptr,
     },
 };
 
+/// The [`Debug`](fmt::Debug) formatting of FMOD classes, which is just the
+/// class name and pointer. With the `debug-introspection` feature, some
+/// classes shadow this with an inherent `fmt_introspect` which also shows a
+/// snapshot of their live state.
+pub(crate) trait NoIntrospection {
+    fn fmt_introspect(&self, f: &mut fmt::Formatter<'_>, name: fmt::Arguments<'_>) -> fmt::Result {
+        f.write_fmt(name)
+    }
+}
+
+impl<T: ?Sized> NoIntrospection for T {}
+
+/// Formats a class whose live state couldn't be retrieved.
+#[cfg(feature = "debug-introspection")]
+pub(crate) fn fmt_introspect_error(
+    f: &mut fmt::Formatter<'_>,
+    name: fmt::Arguments<'_>,
+    error: Error,
+) -> fmt::Result {
+    match error {
+        Error::InvalidHandle | Error::ChannelStolen => write!(f, "{name} <invalid handle>"),
+        _ => f.write_fmt(name),
+    }
+}
+
 /// Decode a UTF-16LE–encoded slice `v` into a `String`, replacing
 /// invalid data with [the replacement character (`U+FFFD`)][U+FFFD].
 ///
@@ -149,7 +175,23 @@ pub const fn decode_sbcd_u16(encoded: u16) -> u16 {
         + (((encoded >> (SHIFT * 3)) & MASK) * 1000)
 }
 
+/// Run user code from an FMOD callback, catching any panic so that it
+/// doesn't unwind into FMOD.
+///
+/// A panic is logged, stored to be retrieved with
+/// [`take_callback_panic`](fmod::take_callback_panic), and turned into
+/// [`Error::RustPanicked`].
 pub fn catch_user_unwind<F, R>(f: F) -> Result<R>
+where
+    F: FnOnce() -> Result<R>,
+{
+    catch_user_unwind_as(Error::RustPanicked, f)
+}
+
+/// [`catch_user_unwind`], but turning a panic into `on_panic`, for callbacks
+/// where FMOD expects a specific error (e.g. [`Error::FileBad`] from file
+/// system callbacks).
+pub fn catch_user_unwind_as<F, R>(on_panic: Error, f: F) -> Result<R>
 where
     F: FnOnce() -> Result<R>,
 {
@@ -161,14 +203,63 @@ where
         } else {
             whoops!(no_panic: "FMOD.rs panicked in {callback}");
         }
-        Err(Error::RustPanicked)
+        fmod::error::record_callback_panic(err);
+        Err(on_panic)
     })
 }
 
+/// Runs `f`, which should catch a panic with `message` in a callback, and
+/// checks that the panic was recorded. Tests using this are serialized, as
+/// the recorded panic is global.
+#[cfg(test)]
+pub(crate) fn expect_callback_panic<R>(message: &str, f: impl FnOnce() -> R) -> R {
+    static LOCK: parking_lot::Mutex<()> = parking_lot::Mutex::new(());
+    let _lock = LOCK.lock();
+    drop(fmod::take_callback_panic());
+    let result = f();
+    let payload = fmod::take_callback_panic().expect("no panic was recorded");
+    assert_eq!(cool_asserts::get_panic_message(&payload), Some(message));
+    result
+}
+
+/// Aborts the process if dropped, i.e. if a panic unwinds past it. Used
+/// where unwinding would cross into FMOD, such as running a destructor of a
+/// panic payload, which could itself panic.
+pub struct AbortOnUnwind;
+
+impl Drop for AbortOnUnwind {
+    fn drop(&mut self) {
+        whoops!(no_panic: "FMOD.rs panicked while unwinding; aborting");
+        std::process::abort();
+    }
+}
+
 pub unsafe fn str_from_nonnull_unchecked<'a>(ptr: ptr::NonNull<c_char>) -> &'a str {
     CStr::from_ptr(ptr.as_ptr()).to_str().unwrap_unchecked()
 }
 
+/// The largest buffer that [`fmod_get_string`] will try before giving up.
+///
+/// FMOD never legitimately returns strings anywhere near this long, so a
+/// callee which still reports truncation at this size is misbehaving.
+pub const FMOD_GET_STRING_MAX_SIZE: usize = 64 * 1024;
+
+/// Retrieve a string from FMOD, retrying with larger buffers on truncation.
+///
+/// The `retry` closure is called with a buffer to write a nul-terminated
+/// string into, and should return [`Error::Truncated`] if the buffer was too
+/// small. The first attempt uses a 256 byte stack buffer; after that, the
+/// buffer grows geometrically on the heap up to
+/// [`FMOD_GET_STRING_MAX_SIZE`], after which [`Error::Truncated`] is
+/// returned to the caller.
+///
+/// The string is always decoded the same way: it ends at the first nul byte,
+/// and invalid UTF-8 is replaced with [`char::REPLACEMENT_CHARACTER`].
+///
+/// # Safety
+///
+/// On success, `retry` must have written a nul-terminated string into the
+/// provided buffer.
 pub unsafe fn fmod_get_string(
     s: &mut String,
     mut retry: impl FnMut(&mut [MaybeUninit<u8>]) -> Result,
@@ -205,8 +296,12 @@ pub unsafe fn fmod_get_string(
         match retry(buf.spare_capacity_mut()) {
             Ok(()) => break,
             Err(Error::Truncated) => {
-                // keep trying with larger buffers
-                buf.reserve(buf.capacity() * 2);
+                // keep trying with larger buffers, up to the cap
+                let capacity = buf.capacity();
+                if capacity >= FMOD_GET_STRING_MAX_SIZE {
+                    return Err(Error::Truncated);
+                }
+                buf.reserve(Ord::min(capacity * 2, FMOD_GET_STRING_MAX_SIZE));
             },
             Err(err) => yeet!(err),
         }
@@ -224,3 +319,127 @@ pub unsafe fn fmod_get_string(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panics_become_errors() {
+        let result = expect_callback_panic("callback", || {
+            catch_user_unwind(|| -> Result {
+                panic!("callback");
+            })
+        });
+        assert_eq!(result, Err(Error::RustPanicked));
+
+        let result = expect_callback_panic("file callback", || {
+            catch_user_unwind_as(Error::FileBad, || -> Result {
+                panic!("file callback");
+            })
+        });
+        assert_eq!(result, Err(Error::FileBad));
+    }
+
+    #[test]
+    fn results_pass_through() {
+        assert_eq!(catch_user_unwind_as(Error::FileBad, || Ok(1)), Ok(1));
+        assert_eq!(
+            catch_user_unwind_as(Error::FileBad, || -> Result { Err(Error::FileEof) }),
+            Err(Error::FileEof),
+        );
+    }
+
+    /// A mock FMOD string getter, which writes `src` and a nul terminator if
+    /// it fits and reports truncation otherwise, recording buffer sizes.
+    fn mock_get_string(src: &[u8], sizes: &mut Vec<usize>) -> Result<String> {
+        let mut s = String::new();
+        unsafe {
+            fmod_get_string(&mut s, |buf| {
+                sizes.push(buf.len());
+                if buf.len() <= src.len() {
+                    return Err(Error::Truncated);
+                }
+                for (dst, &src) in buf.iter_mut().zip(src.iter().chain(&[0])) {
+                    dst.write(src);
+                }
+                Ok(())
+            })?;
+        }
+        Ok(s)
+    }
+
+    #[test]
+    fn short_string_uses_stack_buffer() {
+        let mut sizes = Vec::new();
+        let s = mock_get_string(b"Primary Sound Driver", &mut sizes).unwrap();
+        assert_eq!(s, "Primary Sound Driver");
+        assert_eq!(sizes, [256]);
+    }
+
+    #[test]
+    fn truncation_grows_buffer() {
+        let src = "x".repeat(3000);
+        let mut sizes = Vec::new();
+        let s = mock_get_string(src.as_bytes(), &mut sizes).unwrap();
+        assert_eq!(s, src);
+        assert_eq!(sizes[0], 256);
+        assert!(sizes.windows(2).all(|w| w[1] >= w[0] * 2));
+        assert!(*sizes.last().unwrap() > src.len());
+    }
+
+    #[test]
+    fn exact_fit_needs_room_for_nul() {
+        let src = "x".repeat(256);
+        let mut sizes = Vec::new();
+        let s = mock_get_string(src.as_bytes(), &mut sizes).unwrap();
+        assert_eq!(s, src);
+        assert_eq!(sizes.len(), 2);
+    }
+
+    #[test]
+    fn truncation_is_capped() {
+        let mut sizes = Vec::new();
+        let mut s = String::new();
+        let result = unsafe {
+            fmod_get_string(&mut s, |buf| {
+                sizes.push(buf.len());
+                Err(Error::Truncated)
+            })
+        };
+        assert_eq!(result, Err(Error::Truncated));
+        let &last = sizes.last().unwrap();
+        assert!(last >= FMOD_GET_STRING_MAX_SIZE);
+        assert!(last < FMOD_GET_STRING_MAX_SIZE * 2);
+        assert!(sizes.len() < 16);
+    }
+
+    #[test]
+    fn other_errors_are_returned() {
+        let mut s = String::from("stale");
+        let result = unsafe { fmod_get_string(&mut s, |_| Err(Error::InvalidHandle)) };
+        assert_eq!(result, Err(Error::InvalidHandle));
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn invalid_utf8_is_replaced() {
+        let mut sizes = Vec::new();
+        let s = mock_get_string(b"a\xFFb", &mut sizes).unwrap();
+        assert_eq!(s, "a\u{FFFD}b");
+
+        // same on the heap buffer path
+        let mut src = vec![b'a'; 1000];
+        src[500] = 0xFF;
+        let s = mock_get_string(&src, &mut sizes).unwrap();
+        assert_eq!(s.len(), 999 + '\u{FFFD}'.len_utf8());
+        assert!(s.contains('\u{FFFD}'));
+    }
+
+    #[test]
+    fn string_ends_at_first_nul() {
+        let mut sizes = Vec::new();
+        let s = mock_get_string(b"before\0after", &mut sizes).unwrap();
+        assert_eq!(s, "before");
+    }
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use {
+    fmod::{util::CaptureDsp, *},
+    std::{fs, thread, time::Duration},
+};
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes(bytes[at..at + 2].try_into().unwrap())
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+#[test]
+fn capture_writes_a_valid_wav() {
+    let dir = std::env::temp_dir().join(format!("fmod-rs-capture-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("capture.wav");
+
+    common::with_system(|system| {
+        let master = system.get_master_channel_group().unwrap();
+        let capture = CaptureDsp::attach(master).unwrap();
+        let oscillator = system.create_dsp_by_type(DspType::Oscillator).unwrap();
+        system.play_dsp(&oscillator, None).unwrap();
+
+        // the NoSound output mixes in realtime on its own thread
+        for _ in 0..20 {
+            system.update().unwrap();
+            thread::sleep(Duration::from_millis(10));
+        }
+        capture.write_wav(&path).unwrap();
+        assert_eq!(capture.overruns(), 0);
+        assert_eq!(capture.rejected(), 0);
+
+        let wav = fs::read(&path).unwrap();
+        let channels = capture.channels();
+        let block_align = channels * 4;
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32_at(&wav, 4) as usize, wav.len() - 8);
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u16_at(&wav, 20), 3); // IEEE float
+        assert_eq!(u16_at(&wav, 22) as usize, channels);
+        assert_eq!(u32_at(&wav, 24) as i32, capture.sample_rate());
+        assert_eq!(u16_at(&wav, 32) as usize, block_align);
+        assert_eq!(u16_at(&wav, 34), 32);
+        assert_eq!(&wav[36..40], b"data");
+        let data = &wav[44..];
+        assert_eq!(u32_at(&wav, 40) as usize, data.len());
+        assert!(!data.is_empty());
+        assert_eq!(data.len() % block_align, 0);
+
+        let peak = data
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()).abs())
+            .fold(0.0, f32::max);
+        assert!(peak > 0.1, "peak {peak}");
+    });
+    fs::remove_dir_all(&dir).unwrap();
+}
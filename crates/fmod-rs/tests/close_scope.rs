@@ -0,0 +1,96 @@
+mod common;
+
+use fmod::*;
+
+#[test]
+#[cfg_attr(
+    not(debug_assertions),
+    ignore = "live objects are tracked in debug builds"
+)]
+fn close_scope_counts_live_objects() {
+    common::with_system(|system| {
+        let group = system.create_channel_group(cstr8!("group")).unwrap();
+        let dsp = system.create_dsp_by_type(DspType::Oscillator).unwrap();
+        let error = unsafe { system.close_scope() }.unwrap_err();
+        assert_eq!(error, CloseError::ObjectsStillAlive { count: 2 });
+        drop((group, dsp));
+        let closed = unsafe { system.close_scope() }.unwrap();
+        closed.reinit(32, InitFlags::Normal).unwrap();
+    });
+}
+
+#[test]
+fn output_sample_rate_change() {
+    common::with_system(|system| {
+        let format = system.get_software_format().unwrap();
+        let sample_rate = if format.sample_rate == 44100 {
+            48000
+        } else {
+            44100
+        };
+
+        let closed = unsafe { system.close_scope() }.unwrap();
+        closed
+            .set_software_format(SoftwareFormat {
+                sample_rate,
+                ..format
+            })
+            .unwrap();
+        let system = closed.reinit(32, InitFlags::Normal).unwrap();
+
+        assert_eq!(
+            system.get_software_format().unwrap().sample_rate,
+            sample_rate
+        );
+        let dsp = system.create_dsp_by_type(DspType::Oscillator).unwrap();
+        system.play_dsp(&dsp, None).unwrap();
+        system.update().unwrap();
+    });
+}
+
+#[test]
+fn reconfigure_changes_software_format() {
+    let _lock = common::lock();
+    let mut system = common::new_system(OutputType::NoSound);
+    let format = system.get_software_format().unwrap();
+    let sample_rate = if format.sample_rate == 44100 {
+        48000
+    } else {
+        44100
+    };
+
+    system
+        .reconfigure(32, InitFlags::Normal, |system| {
+            system.set_software_format(SoftwareFormat {
+                sample_rate,
+                ..format
+            })
+        })
+        .unwrap();
+    assert_eq!(
+        system.get_software_format().unwrap().sample_rate,
+        sample_rate
+    );
+
+    // a failed configuration still reinitializes the system
+    let error = system.reconfigure(32, InitFlags::Normal, |_| Err(Error::InvalidParam));
+    assert_eq!(error, Err(CloseError::Other(Error::InvalidParam)));
+    system.update().unwrap();
+}
+
+#[test]
+fn reconfigure_rejects_escaped_objects() {
+    let _lock = common::lock();
+    let mut system = common::new_system(OutputType::NoSound);
+    // leaking through a raw pointer escapes the borrow of the system
+    let group = Handle::leak(system.create_channel_group(cstr8!("group")).unwrap());
+    let group = group as *const ChannelGroup;
+
+    let error = system.reconfigure(32, InitFlags::Normal, |_| Ok(()));
+    assert_eq!(error, Err(CloseError::ObjectsStillAlive { count: 1 }));
+
+    unsafe { Handle::unleak(&*group) }.release().unwrap();
+    system
+        .reconfigure(32, InitFlags::Normal, |_| Ok(()))
+        .unwrap();
+}
@@ -0,0 +1,35 @@
+//! Setup shared by the tests which run against the FMOD runtime.
+
+#![allow(dead_code)]
+
+use {
+    fmod::*,
+    parking_lot::{Mutex, MutexGuard},
+};
+
+/// Only one system may exist at a time, so tests creating one are serialized.
+static SYSTEM_LOCK: Mutex<()> = Mutex::new(());
+
+/// Serializes a test which creates a system itself.
+pub fn lock() -> MutexGuard<'static, ()> {
+    SYSTEM_LOCK.lock()
+}
+
+/// Creates a system initialized with `output`. Hold [`lock`] while it lives.
+pub fn new_system(output: OutputType) -> Handle<'static, System> {
+    let system = System::new().unwrap();
+    system.set_output(output).unwrap();
+    system.init(32, InitFlags::Normal).unwrap();
+    system
+}
+
+/// Runs `f` with a freshly created system, initialized with `output`.
+pub fn with_output<R>(output: OutputType, f: impl FnOnce(&System) -> R) -> R {
+    let _lock = lock();
+    f(&new_system(output))
+}
+
+/// Runs `f` with a freshly created system which doesn't output audio.
+pub fn with_system<R>(f: impl FnOnce(&System) -> R) -> R {
+    with_output(OutputType::NoSound, f)
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use fmod::*;
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic = "called with invalid mode")]
+fn invalid_mode_is_invalid_param() {
+    common::with_system(|system| {
+        let result = system.create_sound(cstr8!("missing.wav"), Mode::D2 | Mode::D3);
+        assert_eq!(result.err(), Some(Error::InvalidParam));
+        let result = system.create_stream(cstr8!("missing.wav"), Mode::OpenUser);
+        assert_eq!(result.err(), Some(Error::InvalidParam));
+    });
+}
+
+#[test]
+fn valid_modes_reach_fmod() {
+    common::with_system(|system| {
+        let result = system.create_sound(cstr8!("missing.wav"), Mode::Default);
+        assert_eq!(result.err(), Some(Error::FileNotFound));
+        let result = system.create_stream(cstr8!("missing.wav"), Mode::LoopNormal | Mode::D3);
+        assert_eq!(result.err(), Some(Error::FileNotFound));
+    });
+}
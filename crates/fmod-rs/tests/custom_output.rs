@@ -0,0 +1,101 @@
+mod common;
+
+use {
+    fmod::{output::*, *},
+    parking_lot::Mutex,
+    std::sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Mixes into memory on each update.
+struct Loopback {
+    length: usize,
+}
+
+static MIXED: Mutex<Vec<f32>> = Mutex::new(Vec::new());
+static LOOPBACK_CLOSED: AtomicBool = AtomicBool::new(false);
+
+impl OutputDescription for Loopback {
+    fn init(config: &mut OutputConfig) -> Result<Self> {
+        config.speaker_mode = SpeakerMode::Stereo;
+        Ok(Loopback {
+            length: config.dsp_buffer_length() as usize,
+        })
+    }
+
+    fn update(&self, state: OutputState<'_>) -> Result {
+        let mut buffer = vec![0.0; self.length * state.channels()];
+        state.read_from_mixer(&mut buffer)?;
+        MIXED.lock().extend(buffer);
+        Ok(())
+    }
+
+    fn close(self, _: OutputState<'_>) -> Result {
+        LOOPBACK_CLOSED.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[test]
+fn loopback_output_receives_the_mix() {
+    let _lock = common::lock();
+    let system = System::new().unwrap();
+    let plugin = system
+        .register_output::<Loopback>(cstr8!("loopback"))
+        .unwrap();
+    system.set_output_by_plugin(plugin).unwrap();
+    system.init(32, InitFlags::Normal).unwrap();
+
+    let dsp = system.create_dsp_by_type(DspType::Oscillator).unwrap();
+    dsp.set_parameter(
+        effect::Oscillator::Type,
+        effect::Oscillator::Waveform::Square,
+    )
+    .unwrap();
+    let channel = system.play_dsp(&dsp, None).unwrap();
+    channel.set_volume(0.5).unwrap();
+    for _ in 0..16 {
+        system.update().unwrap();
+    }
+
+    let mixed = std::mem::take(&mut *MIXED.lock());
+    assert!(!mixed.is_empty());
+    assert_eq!(mixed.len() % 2, 0);
+    let peak = mixed
+        .iter()
+        .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+    assert!(peak > 0.25 && peak <= 1.0, "peak {peak}");
+
+    drop(dsp);
+    drop(system);
+    assert!(LOOPBACK_CLOSED.load(Ordering::SeqCst));
+}
+
+/// Chooses a raw speaker mode without any channels.
+struct NoChannels;
+
+static NO_CHANNELS_CLOSED: AtomicBool = AtomicBool::new(false);
+
+impl OutputDescription for NoChannels {
+    fn init(config: &mut OutputConfig) -> Result<Self> {
+        config.speaker_mode = SpeakerMode::Raw;
+        config.channels = 0;
+        Ok(NoChannels)
+    }
+
+    fn close(self, _: OutputState<'_>) -> Result {
+        NO_CHANNELS_CLOSED.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[test]
+fn rejected_config_closes_the_plugin() {
+    let _lock = common::lock();
+    let system = System::new().unwrap();
+    let plugin = system
+        .register_output::<NoChannels>(cstr8!("no channels"))
+        .unwrap();
+    system.set_output_by_plugin(plugin).unwrap();
+    assert!(system.init(32, InitFlags::Normal).is_err());
+    assert!(NO_CHANNELS_CLOSED.load(Ordering::SeqCst));
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use fmod::*;
+
+fn curve() -> Vec<Vector> {
+    vec![
+        Vector::new(0.0, 1.0, 0.0),
+        Vector::new(10.0, 0.5, 0.0),
+        Vector::new(20.0, 0.0, 0.0),
+    ]
+}
+
+#[test]
+fn channel_curve_survives_until_the_channel_ends() {
+    common::with_system(|system| {
+        let dsp = system.create_dsp_by_type(DspType::Oscillator).unwrap();
+        let channel = system.play_dsp(&dsp, None).unwrap();
+        channel.set_mode(Mode::D3 | Mode::CustomRolloff3d).unwrap();
+        channel.set_3d_custom_rolloff(curve()).unwrap();
+        system.update().unwrap();
+        let shared = channel.get_3d_custom_rolloff().unwrap().unwrap();
+        assert_eq!(&shared[..], &curve()[..]);
+        let again = channel.get_3d_custom_rolloff().unwrap().unwrap();
+        assert!(std::sync::Arc::ptr_eq(&shared, &again));
+
+        // replacing and clearing the curve
+        channel
+            .set_3d_custom_rolloff(curve()[..2].to_vec())
+            .unwrap();
+        assert_eq!(
+            channel.get_3d_custom_rolloff().unwrap().as_deref(),
+            Some(&curve()[..2]),
+        );
+        channel.set_3d_custom_rolloff(Vec::new()).unwrap();
+        assert_eq!(channel.get_3d_custom_rolloff().unwrap(), None);
+
+        // the curve of the ended channel is freed when another is set
+        channel.set_3d_custom_rolloff(curve()).unwrap();
+        channel.stop().unwrap();
+        system.update().unwrap();
+        assert!(channel.validate().is_err());
+        let other = system.play_dsp(&dsp, None).unwrap();
+        other.set_3d_custom_rolloff(curve()).unwrap();
+        // the shared curve outlives the channel
+        assert_eq!(&shared[..], &curve()[..]);
+    });
+}
+
+#[test]
+fn channel_curve_is_freed_by_a_user_callback() {
+    struct Callback;
+    impl ChannelCallback for Callback {}
+
+    common::with_system(|system| {
+        let dsp = system.create_dsp_by_type(DspType::Oscillator).unwrap();
+        let channel = system.play_dsp(&dsp, None).unwrap();
+        channel.set_callback::<Callback>().unwrap();
+        channel.set_3d_custom_rolloff(curve()).unwrap();
+        system.update().unwrap();
+        assert_eq!(
+            channel.get_3d_custom_rolloff().unwrap().as_deref(),
+            Some(&curve()[..]),
+        );
+        channel.stop().unwrap();
+        system.update().unwrap();
+    });
+}
+
+#[test]
+fn group_curve_survives_until_release() {
+    common::with_system(|system| {
+        let group = system.create_channel_group(cstr8!("group")).unwrap();
+        group.set_3d_custom_rolloff(curve()).unwrap();
+        system.update().unwrap();
+        assert_eq!(
+            group.get_3d_custom_rolloff().unwrap().as_deref(),
+            Some(&curve()[..]),
+        );
+        drop(group);
+        system.update().unwrap();
+    });
+}
+
+#[test]
+fn unsorted_curve_is_rejected() {
+    common::with_system(|system| {
+        let group = system.create_channel_group(cstr8!("group")).unwrap();
+        let mut curve = curve();
+        curve.reverse();
+        assert_eq!(group.set_3d_custom_rolloff(curve), Err(Error::InvalidParam),);
+    });
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use fmod::{effect::*, *};
+
+#[test]
+fn oscillator_round_trip() {
+    common::with_system(|system| {
+        let dsp = system.create_dsp_by_type(DspType::Oscillator).unwrap();
+        dsp.set_parameter(Oscillator::Type, Oscillator::Waveform::Triangle)
+            .unwrap();
+        dsp.set_parameter(Oscillator::Rate, 440.0).unwrap();
+        assert_eq!(
+            dsp.get_parameter(Oscillator::Type).unwrap(),
+            Oscillator::Waveform::Triangle,
+        );
+        assert_eq!(dsp.get_parameter(Oscillator::Rate).unwrap(), 440.0);
+    });
+}
+
+#[test]
+fn multiband_eq_round_trip() {
+    common::with_system(|system| {
+        let dsp = system.create_dsp_by_type(DspType::MultibandEq).unwrap();
+        dsp.set_parameter(MultibandEq::B::Filter, MultibandEq::FilterType::Peaking)
+            .unwrap();
+        dsp.set_parameter(MultibandEq::B::Frequency, 1000.0)
+            .unwrap();
+        dsp.set_parameter(MultibandEq::B::Gain, -6.0).unwrap();
+        assert_eq!(
+            dsp.get_parameter(MultibandEq::B::Filter).unwrap(),
+            MultibandEq::FilterType::Peaking,
+        );
+        assert_eq!(
+            dsp.get_parameter(MultibandEq::B::Frequency).unwrap(),
+            1000.0
+        );
+        assert_eq!(dsp.get_parameter(MultibandEq::B::Gain).unwrap(), -6.0);
+        // other bands are untouched
+        assert_eq!(
+            dsp.get_parameter(MultibandEq::C::Filter).unwrap(),
+            MultibandEq::FilterType::Disabled,
+        );
+    });
+}
+
+#[test]
+fn fft_round_trip() {
+    common::with_system(|system| {
+        let dsp = system.create_dsp_by_type(DspType::Fft).unwrap();
+        dsp.set_parameter(Fft::WindowSize, 1024).unwrap();
+        dsp.set_parameter(Fft::WindowType, Fft::Window::Hanning)
+            .unwrap();
+        assert_eq!(dsp.get_parameter(Fft::WindowSize).unwrap(), 1024);
+        assert_eq!(
+            dsp.get_parameter(Fft::WindowType).unwrap(),
+            Fft::Window::Hanning,
+        );
+    });
+}
+
+#[test]
+fn pan_round_trip() {
+    common::with_system(|system| {
+        let dsp = system.create_dsp_by_type(DspType::Pan).unwrap();
+        dsp.set_parameter(Pan::Mode, Pan::ModeType::Surround)
+            .unwrap();
+        dsp.set_parameter(Pan::OverrideRange, true).unwrap();
+        assert_eq!(
+            dsp.get_parameter(Pan::Mode).unwrap(),
+            Pan::ModeType::Surround,
+        );
+        assert!(dsp.get_parameter(Pan::OverrideRange).unwrap());
+        dsp.set_parameter(Pan::OverrideRange, false).unwrap();
+        assert!(!dsp.get_parameter(Pan::OverrideRange).unwrap());
+
+        let mut position = Attributes3dMulti {
+            num_listeners: 1,
+            ..Attributes3dMulti::default()
+        };
+        position.relative[0].position = Vector::new(1.0, 0.0, 2.0);
+        position.weight[0] = 1.0;
+        position.absolute.position = Vector::new(1.0, 0.0, 2.0);
+        dsp.set_parameter(Pan::Position3d, position).unwrap();
+        assert_eq!(dsp.get_parameter(Pan::Position3d).unwrap(), position);
+    });
+}
+
+#[test]
+fn data_parameter_size_must_match() {
+    common::with_system(|system| {
+        let dsp = system.create_dsp_by_type(DspType::Pan).unwrap();
+        let index = i32::from(Pan::OverallGain);
+        let size = std::mem::size_of::<OverallGain>();
+        assert_eq!(size, 8);
+
+        // smaller and larger than the data
+        let error = dsp.get_parameter::<[u8; 4]>(index).unwrap_err();
+        assert_eq!(error, Error::InvalidParam);
+        let error = dsp.get_parameter::<[u8; 16]>(index).unwrap_err();
+        assert_eq!(error, Error::InvalidParam);
+
+        // exactly the size of the data
+        let bytes = dsp.get_parameter::<[u8; 8]>(index).unwrap();
+        assert_eq!(dsp.get_parameter_data::<[u8]>(index).unwrap(), bytes);
+        dsp.get_parameter(Pan::OverallGain).unwrap();
+
+        let unlocked = dsp.get_parameter_data_unlocked(Pan::OverallGain).unwrap();
+        assert_eq!(unlocked.len(), size);
+
+        // getting data doesn't deadlock while this thread holds the lock
+        let lock = unsafe { DspLock::new(system) }.unwrap();
+        let locked = dsp.get_parameter_data(Pan::OverallGain).unwrap();
+        dsp.get_parameter::<[u8; 8]>(index).unwrap();
+        lock.unlock().unwrap();
+        assert_eq!(locked.len(), size);
+    });
+}
+
+#[test]
+fn data_parameter_of_exact_size() {
+    common::with_system(|system| {
+        let dsp = system.create_dsp_by_type(DspType::Compressor).unwrap();
+        let index = i32::from(Compressor::UseSidechain);
+        let enabled = 1i32.to_ne_bytes();
+        dsp.set_parameter::<[u8; 4]>(index, enabled).unwrap();
+        assert_eq!(dsp.get_parameter::<[u8; 4]>(index).unwrap(), enabled);
+        assert!(
+            dsp.get_parameter(Compressor::UseSidechain)
+                .unwrap()
+                .sidechainenable
+        );
+    });
+}
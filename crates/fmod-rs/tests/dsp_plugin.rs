@@ -0,0 +1,125 @@
+mod common;
+
+use {
+    fmod::*,
+    std::sync::atomic::{AtomicU32, Ordering},
+};
+
+/// Scales the signal by its gain parameter.
+struct Gain {
+    gain: AtomicU32,
+}
+
+impl DspDescription for Gain {
+    const PARAMETERS: &'static [DspParameterDesc] = &[DspParameterDesc {
+        name: cstr8!("Gain"),
+        label: cstr8!(""),
+        description: cstr8!("Linear gain."),
+        kind: DspParameterKind::Float {
+            min: 0.0,
+            max: 1.0,
+            default: 1.0,
+        },
+    }];
+
+    fn create() -> Result<Self> {
+        Ok(Gain {
+            gain: AtomicU32::new(1.0f32.to_bits()),
+        })
+    }
+
+    fn read(&self, input: &[f32], output: &mut [f32], _: usize) -> Result {
+        let gain = f32::from_bits(self.gain.load(Ordering::Relaxed));
+        for (output, input) in output.iter_mut().zip(input) {
+            *output = input * gain;
+        }
+        Ok(())
+    }
+
+    fn set_parameter_float(&self, index: i32, value: f32) -> Result {
+        match index {
+            0 => {
+                self.gain.store(value.to_bits(), Ordering::Relaxed);
+                Ok(())
+            },
+            _ => Err(Error::InvalidParam),
+        }
+    }
+
+    fn get_parameter_float(&self, index: i32) -> Result<f32> {
+        match index {
+            0 => Ok(f32::from_bits(self.gain.load(Ordering::Relaxed))),
+            _ => Err(Error::InvalidParam),
+        }
+    }
+}
+
+/// The peak level seen by [`Probe`] since it was last taken.
+static PEAK: AtomicU32 = AtomicU32::new(0);
+
+/// Passes the signal through, recording its peak level.
+struct Probe;
+
+impl DspDescription for Probe {
+    fn create() -> Result<Self> {
+        Ok(Probe)
+    }
+
+    fn read(&self, input: &[f32], output: &mut [f32], _: usize) -> Result {
+        output.copy_from_slice(input);
+        let peak = input
+            .iter()
+            .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        // non-negative floats order the same as their bits
+        PEAK.fetch_max(peak.to_bits(), Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+fn take_peak() -> f32 {
+    f32::from_bits(PEAK.swap(0, Ordering::Relaxed))
+}
+
+#[test]
+fn gain_dsp_scales_the_output() {
+    common::with_output(OutputType::NoSoundNrt, |system| {
+        let gain = system.register_dsp::<Gain>(cstr8!("gain")).unwrap();
+        let gain = system.create_dsp_by_plugin(gain).unwrap();
+        let probe = system.register_dsp::<Probe>(cstr8!("probe")).unwrap();
+        let probe = system.create_dsp_by_plugin(probe).unwrap();
+        // the probe is at the head, so it sees the output of the gain
+        let master = system.get_master_channel_group().unwrap();
+        master.add_dsp(0, &gain).unwrap();
+        master.add_dsp(0, &probe).unwrap();
+
+        let oscillator = system.create_dsp_by_type(DspType::Oscillator).unwrap();
+        oscillator
+            .set_parameter(
+                effect::Oscillator::Type,
+                effect::Oscillator::Waveform::Square,
+            )
+            .unwrap();
+        system.play_dsp(&oscillator, None).unwrap();
+
+        let mix = || {
+            for _ in 0..8 {
+                system.update().unwrap();
+            }
+            take_peak()
+        };
+        mix();
+        let unscaled = mix();
+        gain.set_parameter(0, 0.25f32).unwrap();
+        mix();
+        let scaled = mix();
+
+        assert!(unscaled > 0.1, "unscaled peak {unscaled}");
+        let ratio = scaled / unscaled;
+        assert!((ratio - 0.25).abs() < 0.01, "scaled by {ratio}");
+
+        unsafe {
+            master.remove_dsp(&probe).unwrap();
+            master.remove_dsp(&gain).unwrap();
+        }
+    });
+}
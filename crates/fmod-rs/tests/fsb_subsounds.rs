@@ -0,0 +1,156 @@
+#![cfg(all(feature = "fsbank", feature = "raw"))]
+
+mod common;
+
+use {
+    cstr8::CString8,
+    fmod::{raw::*, *},
+    std::{
+        fs,
+        path::{Path, PathBuf},
+        ptr,
+    },
+};
+
+const RATE: u32 = 8000;
+
+/// Lengths of the subsounds in the bank, so they can be told apart.
+const FRAMES: [u32; 2] = [2000, 6000];
+
+/// A 16-bit mono WAV file of a sine at `pitch`.
+fn tone_wav(frames: u32, pitch: f32) -> Vec<u8> {
+    let data_len = frames * 2;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // channels
+    wav.extend_from_slice(&RATE.to_le_bytes());
+    wav.extend_from_slice(&(RATE * 2).to_le_bytes()); // bytes per second
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for i in 0..frames {
+        let t = i as f32 / RATE as f32;
+        let sample = (t * pitch * std::f32::consts::TAU).sin() * 0.5 * i16::MAX as f32;
+        wav.extend_from_slice(&(sample as i16).to_le_bytes());
+    }
+    wav
+}
+
+fn path_cstr(path: &Path) -> CString8 {
+    CString8::new(path.to_str().unwrap()).unwrap()
+}
+
+/// Builds a PCM FSB in `dir` with one subsound per entry of [`FRAMES`].
+fn build_bank(dir: &Path) -> PathBuf {
+    let sources: Vec<CString8> = FRAMES
+        .iter()
+        .enumerate()
+        .map(|(i, &frames)| {
+            let path = dir.join(format!("tone{i}.wav"));
+            fs::write(&path, tone_wav(frames, 220.0 * (i + 1) as f32)).unwrap();
+            path_cstr(&path)
+        })
+        .collect();
+    let file_names: Vec<*const std::ffi::c_char> = sources
+        .iter()
+        .map(|source| source.as_ptr().cast())
+        .collect();
+    let subsounds: Vec<FSBANK_SUBSOUND> = file_names
+        .iter()
+        .map(|file_name| FSBANK_SUBSOUND {
+            fileNames: file_name,
+            fileData: ptr::null(),
+            fileDataLengths: ptr::null(),
+            numFiles: 1,
+            overrideFlags: FSBANK_BUILD_DEFAULT,
+            overrideQuality: 0,
+            desiredSampleRate: 0.0,
+            percentOptimizedRate: 0.0,
+        })
+        .collect();
+
+    let cache = path_cstr(dir);
+    let bank = dir.join("tones.fsb");
+    let output = path_cstr(&bank);
+    unsafe {
+        let result = FSBank_Init(
+            FSBANK_FSBVERSION_FSB5,
+            FSBANK_INIT_NORMAL | FSBANK_INIT_DONTLOADCACHEFILES,
+            1,
+            cache.as_ptr().cast(),
+        );
+        assert_eq!(result, FSBANK_OK, "FSBank_Init");
+        let result = FSBank_Build(
+            subsounds.as_ptr(),
+            subsounds.len() as u32,
+            FSBANK_FORMAT_PCM,
+            FSBANK_BUILD_DEFAULT,
+            0,
+            ptr::null(),
+            output.as_ptr().cast(),
+        );
+        FSBank_Release();
+        assert_eq!(result, FSBANK_OK, "FSBank_Build");
+    }
+    bank
+}
+
+/// Runs `f` with a freshly built bank, cleaning it up afterwards.
+fn with_bank(name: &str, f: impl FnOnce(&System, &CString8)) {
+    let dir = std::env::temp_dir().join(format!("fmod-rs-{name}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let bank = path_cstr(&build_bank(&dir));
+    common::with_system(|system| f(system, &bank));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn play_second_subsound() {
+    with_bank("fsb-subsound", |system, bank| {
+        let sound = system.create_sound(bank, Mode::Default).unwrap();
+        assert_eq!(sound.get_num_sub_sounds().unwrap(), 2);
+
+        let sub_sound = sound.get_sub_sound(1).unwrap();
+        let parent = sub_sound.get_sub_sound_parent().unwrap().unwrap();
+        assert!(ptr::eq(parent, &*sound));
+        assert_eq!(sub_sound.get_length(TimeUnit::Pcm).unwrap(), FRAMES[1]);
+
+        let channel = system.play_sound(sub_sound, None).unwrap();
+        system.update().unwrap();
+        assert!(channel.is_playing().unwrap());
+        let current = channel.get_current_sound().unwrap().unwrap();
+        assert!(ptr::eq(current, sub_sound));
+    });
+}
+
+#[test]
+fn play_each_subsound_independently() {
+    with_bank("fsb-subsounds", |system, bank| {
+        let sound = system.create_sound(bank, Mode::CreateSample).unwrap();
+        let channels: Vec<&Channel> = (0..2)
+            .map(|index| {
+                let sub_sound = sound.get_sub_sound(index).unwrap();
+                let frames = FRAMES[index as usize];
+                assert_eq!(sub_sound.get_length(TimeUnit::Pcm).unwrap(), frames);
+                system.play_sound(sub_sound, None).unwrap()
+            })
+            .collect();
+        system.update().unwrap();
+
+        for (index, channel) in (0..2).zip(&channels) {
+            assert!(channel.is_playing().unwrap());
+            let current = channel.get_current_sound().unwrap().unwrap();
+            assert!(ptr::eq(current, sound.get_sub_sound(index).unwrap()));
+        }
+
+        // stopping one subsound leaves the other playing
+        channels[0].stop().unwrap();
+        system.update().unwrap();
+        assert!(channels[1].is_playing().unwrap());
+    });
+}
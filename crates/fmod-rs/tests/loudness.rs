@@ -0,0 +1,55 @@
+mod common;
+
+use fmod::{util::*, *};
+
+#[test]
+fn monitor_reads_meter_info() {
+    // mix only on update, so the measurements don't change between reads
+    common::with_output(OutputType::NoSoundNrt, |system| {
+        let master = system.get_master_channel_group().unwrap();
+        let monitor = LoudnessMonitor::attach(master).unwrap();
+        let tone = system.create_dsp_by_type(DspType::Oscillator).unwrap();
+        system.play_dsp(&tone, None).unwrap();
+        for _ in 0..10 {
+            system.update().unwrap();
+        }
+
+        let info = monitor.info().unwrap();
+        assert_eq!(monitor.integrated().unwrap(), info.integrated_loudness);
+        // the meter info is exactly the size the typed getter requires
+        let meter_info = effect::LoudnessMeter::MeterInfo;
+        monitor.dsp().get_parameter(meter_info).unwrap();
+
+        monitor.reset(LoudnessReset::All).unwrap();
+    });
+}
+
+#[test]
+fn sine_at_minus_20_dbfs_measures_plausibly() {
+    use effect::Oscillator;
+
+    common::with_output(OutputType::NoSoundNrt, |system| {
+        let master = system.get_master_channel_group().unwrap();
+        let monitor = LoudnessMonitor::attach(master).unwrap();
+        let tone = system.create_dsp_by_type(DspType::Oscillator).unwrap();
+        tone.set_parameter(Oscillator::Type, Oscillator::Waveform::Sine)
+            .unwrap();
+        tone.set_parameter(Oscillator::Rate, 1000.0).unwrap();
+        let channel = system.play_dsp(&tone, None).unwrap();
+        // a peak of -20 dBFS
+        channel.set_volume(0.1).unwrap();
+        system
+            .render_offline(std::time::Duration::from_secs(4))
+            .unwrap();
+
+        // a full scale 1 kHz sine in one channel measures -3 LUFS, and panning
+        // the mono tone across two channels doesn't add more than 3 dB
+        let integrated = monitor.integrated().unwrap();
+        assert!(
+            (-30.0..=-17.0).contains(&integrated),
+            "integrated loudness {integrated} LUFS",
+        );
+        let peak = monitor.max_true_peak().unwrap();
+        assert!((-24.0..=-17.0).contains(&peak), "true peak {peak} dBTP");
+    });
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use fmod::*;
+
+#[test]
+fn suspended_mixer_rejects_system_calls() {
+    common::with_system(|system| {
+        let suspended = system.suspend_mixer().unwrap();
+        assert_eq!(system.update(), Err(Error::MixerSuspended));
+        assert_eq!(system.suspend_mixer().err(), Some(Error::MixerSuspended));
+        assert_eq!(unsafe { system.lock_dsp() }, Err(Error::MixerSuspended));
+
+        // calls which don't wait on the mixer aren't rejected
+        system.get_software_channels().unwrap();
+
+        suspended.resume().unwrap();
+        system.update().unwrap();
+    });
+}
+
+#[test]
+fn dropping_the_guard_resumes() {
+    common::with_system(|system| {
+        drop(system.suspend_mixer().unwrap());
+        system.update().unwrap();
+    });
+}
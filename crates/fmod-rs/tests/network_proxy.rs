@@ -0,0 +1,20 @@
+mod common;
+
+use cstr8::CString8;
+
+#[test]
+fn long_proxy_round_trips() {
+    // well past the 256 byte stack buffer tried first
+    let user = "u".repeat(300);
+    let password = "p".repeat(300);
+    let host = format!("{}.example.com", "h".repeat(200));
+    let proxy = format!("{user}:{password}@{host}:8888");
+    let proxy = CString8::new(proxy).unwrap();
+
+    common::with_system(|system| {
+        system.set_network_proxy(&proxy).unwrap();
+        let mut read = String::new();
+        system.get_network_proxy(&mut read).unwrap();
+        assert_eq!(read, proxy.as_str());
+    });
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use {
+    cstr8::CString8,
+    fmod::{util::*, *},
+    std::{f32::consts::TAU, fs, path::Path, time::Duration},
+};
+
+/// Writes one second of a 440 Hz mono 16-bit sine wave at 48 kHz.
+fn write_sine_wav(path: &Path) {
+    const SAMPLE_RATE: u32 = 48000;
+    let samples = (0..SAMPLE_RATE).map(|i| {
+        let t = i as f32 / SAMPLE_RATE as f32;
+        ((TAU * 440.0 * t).sin() * i16::MAX as f32 * 0.5) as i16
+    });
+    let data_len = SAMPLE_RATE * 2;
+
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    fs::write(path, wav).unwrap();
+}
+
+fn render(asset: &Path, output: &Path) -> Vec<u8> {
+    let asset = CString8::new(asset.to_str().unwrap()).unwrap();
+    let config = OfflineRenderConfig {
+        max_duration: Duration::from_secs(5),
+        ..OfflineRenderConfig::new(output)
+    };
+    let output = render_offline(config, |system| {
+        let sine = Handle::leak(system.create_sound(&asset, Mode::default())?);
+        let echo = Handle::leak(system.create_dsp_by_type(DspType::Echo)?);
+        let master = system.get_master_channel_group()?;
+        master.add_dsp(0, echo)?;
+        let ms = Duration::from_millis;
+        Ok(RenderScript::new()
+            .at(
+                ms(0),
+                RenderAction::Play {
+                    sound: sine,
+                    group: None,
+                },
+            )
+            .at(
+                ms(250),
+                RenderAction::Play {
+                    sound: sine,
+                    group: None,
+                },
+            )
+            .at(
+                ms(500),
+                RenderAction::SetParameter {
+                    dsp: echo,
+                    index: 1,
+                    value: 20.0,
+                },
+            )
+            .at(ms(750), RenderAction::Stop(master)))
+    })
+    .unwrap();
+    fs::read(output).unwrap()
+}
+
+#[test]
+fn render_offline_is_deterministic() {
+    let _lock = common::lock();
+    let dir = std::env::temp_dir().join(format!("fmod-rs-offline-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let asset = dir.join("sine.wav");
+    write_sine_wav(&asset);
+
+    let first = render(&asset, &dir.join("first.wav"));
+    let second = render(&asset, &dir.join("second.wav"));
+    fs::remove_dir_all(&dir).unwrap();
+
+    // stopped at 750ms, so more than a header but less than the maximum
+    assert!(first.len() > 44);
+    assert!(first.len() < 48000 * 2 * 2 * 5);
+    assert!(first == second, "offline renders differ");
+}
+
+#[test]
+fn render_offline_requires_nrt_output() {
+    common::with_system(|system| {
+        let error = system
+            .render_offline(Duration::from_millis(10))
+            .unwrap_err();
+        assert_eq!(error, Error::Unsupported);
+    });
+}
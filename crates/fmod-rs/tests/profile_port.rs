@@ -0,0 +1,29 @@
+mod common;
+
+use {
+    fmod::*,
+    std::net::{Ipv4Addr, TcpListener},
+};
+
+#[test]
+fn busy_profiler_port_is_diagnosed() {
+    let _lock = common::lock();
+    // any free port, kept busy while the listener lives
+    let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let system = System::new().unwrap();
+    system.set_output(OutputType::NoSound).unwrap();
+    let mut settings = system.get_advanced_settings().unwrap();
+    settings.profile_port = port;
+    system.set_advanced_settings(settings).unwrap();
+
+    let flags = InitFlags::Normal | InitFlags::ProfileEnable;
+    assert_eq!(system.init(32, flags), Err(Error::NetSocketError));
+    let error = system.init_checked(32, flags).unwrap_err();
+    assert_eq!(error, InitError::ProfilePortInUse { port });
+    assert_eq!(Error::from(error), Error::NetSocketError);
+
+    drop(listener);
+    system.init_checked(32, flags).unwrap();
+}
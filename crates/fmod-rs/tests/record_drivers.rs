@@ -0,0 +1,25 @@
+mod common;
+
+#[test]
+fn record_driver_names_come_from_record_drivers() {
+    common::with_system(|system| {
+        // the output and record driver lists are distinct
+        let num_drivers = system.get_num_drivers().unwrap();
+        let num_record_drivers = system.get_record_num_drivers().unwrap().available;
+        assert!(num_drivers > num_record_drivers);
+
+        let mut name = String::new();
+        for id in 0..num_record_drivers {
+            system.get_record_driver_name(id, &mut name).unwrap();
+            let details = system.get_record_driver_details(id).unwrap();
+            assert_eq!(name, details.name);
+        }
+
+        // an id which is only valid for output drivers isn't a record driver
+        let id = num_record_drivers;
+        system.get_driver_name(id, &mut name).unwrap();
+        let result = system.get_record_driver_name(id, &mut name);
+        assert!(result.is_err());
+        assert_eq!(result.err(), system.get_record_driver_info(id).err());
+    });
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use {fmod::*, std::ptr};
+
+#[test]
+fn register_and_look_up() {
+    common::with_system(|system| {
+        let group = system.create_channel_group(cstr8!("music")).unwrap();
+        let group = system.register_named_group("music", group).unwrap();
+        let dsp = system.create_dsp_by_type(DspType::Oscillator).unwrap();
+        let dsp = system.register_named_dsp("tone", dsp).unwrap();
+        let sounds = system.create_sound_group(cstr8!("sfx")).unwrap();
+        let sounds = system.register_named_sound_group("sfx", sounds).unwrap();
+
+        assert!(ptr::eq(system.named_group("music").unwrap(), group));
+        assert!(ptr::eq(system.named_dsp("tone").unwrap(), dsp));
+        assert!(ptr::eq(system.named_sound_group("sfx").unwrap(), sounds));
+        assert!(system.named_group("tone").is_none());
+        assert!(system.named_dsp("music").is_none());
+    });
+}
+
+#[test]
+fn duplicate_name_is_rejected() {
+    common::with_system(|system| {
+        let first = system.create_channel_group(cstr8!("first")).unwrap();
+        let first = system.register_named_group("music", first).unwrap();
+        let second = system.create_channel_group(cstr8!("second")).unwrap();
+        let error = system.register_named_group("music", second).unwrap_err();
+        assert_eq!(error, Error::InvalidParam);
+        assert!(ptr::eq(system.named_group("music").unwrap(), first));
+    });
+}
+
+#[test]
+fn play_sound_in_named_group() {
+    common::with_system(|system| {
+        let group = system.create_channel_group(cstr8!("music")).unwrap();
+        let group = system.register_named_group("music", group).unwrap();
+        let info = CreateSoundEx::new()
+            .num_channels(1)
+            .default_frequency(44100)
+            .length(44100 * 2)
+            .format(SoundFormat::Pcm16);
+        let sound = unsafe { system.create_sound_ex(ptr::null(), Mode::OpenUser, info) }.unwrap();
+
+        let channel = system.play_sound_in(&sound, "music", true).unwrap();
+        assert!(ptr::eq(channel.get_channel_group().unwrap(), group));
+        let error = system.play_sound_in(&sound, "missing", true).unwrap_err();
+        assert_eq!(error, Error::InvalidParam);
+    });
+}
+
+#[test]
+fn close_scope_releases_registered_objects() {
+    common::with_system(|system| {
+        let group = system.create_channel_group(cstr8!("music")).unwrap();
+        system.register_named_group("music", group).unwrap();
+        let dsp = system.create_dsp_by_type(DspType::Oscillator).unwrap();
+        system.register_named_dsp("tone", dsp).unwrap();
+
+        // registered objects are owned by the system, so aren't still alive
+        let closed = unsafe { system.close_scope() }.unwrap();
+        let system = closed.reinit(32, InitFlags::Normal).unwrap();
+        assert!(system.named_group("music").is_none());
+        assert!(system.named_dsp("tone").is_none());
+
+        let group = system.create_channel_group(cstr8!("music")).unwrap();
+        system.register_named_group("music", group).unwrap();
+    });
+}
+
+#[test]
+fn release_forgets_registered_objects() {
+    let _lock = common::lock();
+    let system = common::new_system(OutputType::NoSound);
+    let group = system.create_channel_group(cstr8!("music")).unwrap();
+    system.register_named_group("music", group).unwrap();
+    drop(system);
+
+    let system = common::new_system(OutputType::NoSound);
+    assert!(system.named_group("music").is_none());
+    let group = system.create_channel_group(cstr8!("music")).unwrap();
+    system.register_named_group("music", group).unwrap();
+}
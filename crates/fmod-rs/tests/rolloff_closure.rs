@@ -0,0 +1,76 @@
+mod common;
+
+use {
+    fmod::*,
+    std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+fn play_3d(system: &System) -> &Channel {
+    let dsp = Handle::leak(system.create_dsp_by_type(DspType::Oscillator).unwrap());
+    let channel = system.play_dsp(dsp, None).unwrap();
+    channel.set_mode(Mode::D3).unwrap();
+    let position = Vector::new(10.0, 0.0, 0.0);
+    channel
+        .set_3d_attributes(&position, &Vector::default())
+        .unwrap();
+    channel
+}
+
+#[test]
+fn closure_is_called_and_replaced() {
+    common::with_system(|system| {
+        let first = Arc::new(AtomicUsize::new(0));
+        let calls = Arc::clone(&first);
+        system
+            .set_3d_rolloff_closure(move |_, _| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                0.5
+            })
+            .unwrap();
+        let _channel = play_3d(system);
+        system.update().unwrap();
+        assert_ne!(first.load(Ordering::Relaxed), 0);
+
+        let second = Arc::new(AtomicUsize::new(0));
+        let calls = Arc::clone(&second);
+        system
+            .set_3d_rolloff_closure(move |_, _| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                0.25
+            })
+            .unwrap();
+        // the replaced closure is dropped once no callback is running it
+        assert_eq!(Arc::strong_count(&first), 1);
+        let before = first.load(Ordering::Relaxed);
+        system.update().unwrap();
+        assert_eq!(first.load(Ordering::Relaxed), before);
+        assert_ne!(second.load(Ordering::Relaxed), 0);
+
+        system.clear_3d_rolloff_callback().unwrap();
+        assert_eq!(Arc::strong_count(&second), 1);
+    });
+}
+
+#[test]
+fn closure_is_dropped_with_the_system() {
+    let _lock = common::lock();
+    let dropped = Arc::new(());
+    let system = common::new_system(OutputType::NoSound);
+    let kept = Arc::clone(&dropped);
+    system
+        .set_3d_rolloff_closure(move |_, _| {
+            let _ = &kept;
+            1.0
+        })
+        .unwrap();
+    assert_eq!(Arc::strong_count(&dropped), 2);
+    drop(system);
+    assert_eq!(Arc::strong_count(&dropped), 1);
+
+    // a new system can set its own closure
+    let system = common::new_system(OutputType::NoSound);
+    system.set_3d_rolloff_closure(|_, _| 1.0).unwrap();
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use {fmod::*, std::ptr};
+
+/// A second of silence, created without a file.
+fn user_sound(system: &System) -> Handle<'_, Sound> {
+    let info = CreateSoundEx::new()
+        .num_channels(1)
+        .default_frequency(44100)
+        .length(44100 * 2)
+        .format(SoundFormat::Pcm16);
+    unsafe { system.create_sound_ex(ptr::null(), Mode::OpenUser, info) }.unwrap()
+}
+
+#[test]
+fn max_audible_steals_lowest() {
+    common::with_system(|system| {
+        let group = system.create_sound_group(cstr8!("group")).unwrap();
+        group.set_max_audible(1).unwrap();
+        group
+            .set_max_audible_behavior(SoundGroupBehavior::StealLowest)
+            .unwrap();
+
+        let first = user_sound(system);
+        let second = user_sound(system);
+        first.set_sound_group(&group).unwrap();
+        second.set_sound_group(&group).unwrap();
+        assert!(ptr::eq(first.get_sound_group().unwrap(), &*group));
+        assert_eq!(group.get_num_sounds().unwrap(), 2);
+
+        first.set_mode(Mode::LoopNormal).unwrap();
+        second.set_mode(Mode::LoopNormal).unwrap();
+        system.play_sound(&first, None).unwrap();
+        system.play_sound(&second, None).unwrap();
+        system.update().unwrap();
+        assert_eq!(group.get_num_playing().unwrap(), 1);
+    });
+}
+
+#[test]
+fn set_mode_only_changes_settable_flags() {
+    common::with_system(|system| {
+        let sound = user_sound(system);
+        let before = sound.get_mode().unwrap();
+        assert!(!before.is_set(Mode::CreateStream));
+
+        sound
+            .set_mode(Mode::LoopBidi | Mode::D3 | Mode::CreateStream)
+            .unwrap();
+        let after = sound.get_mode().unwrap();
+        assert!(after.is_set(Mode::LoopBidi));
+        assert!(after.is_set(Mode::D3));
+        // creation flags are masked out rather than passed to FMOD
+        assert!(!after.is_set(Mode::CreateStream));
+
+        sound.set_mode(Mode::LoopOff | Mode::D2).unwrap();
+        let after = sound.get_mode().unwrap();
+        assert!(after.is_set(Mode::LoopOff));
+        assert!(after.is_set(Mode::D2));
+    });
+}
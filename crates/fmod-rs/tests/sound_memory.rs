@@ -0,0 +1,45 @@
+mod common;
+
+use {fmod::*, std::ptr};
+
+fn user_sound(system: &System) -> Result<Handle<'_, Sound>> {
+    let info = CreateSoundEx::new()
+        .num_channels(1)
+        .default_frequency(44100)
+        .length(44100 * 2)
+        .format(SoundFormat::Pcm16);
+    unsafe { system.create_sound_ex(ptr::null(), Mode::OpenUser, info) }
+}
+
+#[test]
+fn recorded_sounds_report_memory() {
+    common::with_system(|system| {
+        let group = system.create_sound_group(cstr8!("group")).unwrap();
+        let recorded = memory::record_sound_memory(|| user_sound(system)).unwrap();
+        let unrecorded = user_sound(system).unwrap();
+        recorded.set_sound_group(&group).unwrap();
+        unrecorded.set_sound_group(&group).unwrap();
+
+        let bytes = recorded.estimated_memory().unwrap();
+        assert_eq!(unrecorded.estimated_memory(), None);
+        assert_eq!(group.estimated_memory().unwrap(), bytes);
+
+        drop(recorded);
+        assert_eq!(group.estimated_memory().unwrap(), 0);
+    });
+}
+
+#[test]
+fn memory_is_per_system() {
+    let _lock = common::lock();
+    let system = common::new_system(OutputType::NoSound);
+    let sound = Handle::leak(memory::record_sound_memory(|| user_sound(&system)).unwrap());
+    assert!(sound.estimated_memory().is_some());
+    drop(system);
+
+    // a sound of a new system doesn't see the released system's records,
+    // even if it reuses the same address
+    let system = common::new_system(OutputType::NoSound);
+    let sound = user_sound(&system).unwrap();
+    assert_eq!(sound.estimated_memory(), None);
+}
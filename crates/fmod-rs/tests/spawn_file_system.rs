@@ -0,0 +1,124 @@
+mod common;
+
+use {
+    fmod::{file::*, *},
+    std::{
+        ffi::CStr,
+        future::Future,
+        pin::Pin,
+        sync::Arc,
+        task::{Context, Wake},
+        thread::{self, Thread},
+    },
+};
+
+const RATE: u32 = 44100;
+const FRAMES: u32 = RATE / 2;
+
+/// A 16-bit mono WAV file of a 440Hz sine.
+fn tone_wav() -> Vec<u8> {
+    let data_len = FRAMES * 2;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // channels
+    wav.extend_from_slice(&RATE.to_le_bytes());
+    wav.extend_from_slice(&(RATE * 2).to_le_bytes()); // bytes per second
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for i in 0..FRAMES {
+        let t = i as f32 / RATE as f32;
+        let sample = (t * 440.0 * std::f32::consts::TAU).sin() * 0.5 * i16::MAX as f32;
+        wav.extend_from_slice(&(sample as i16).to_le_bytes());
+    }
+    wav
+}
+
+/// Serves `tone.wav` from memory, reading on a thread per request.
+struct MemoryFs;
+
+impl FileSystem for MemoryFs {
+    type File = Arc<[u8]>;
+
+    fn open(name: &CStr) -> Result<FileOpenInfo<Self::File>> {
+        if name.to_bytes() != b"tone.wav" {
+            return Err(Error::FileNotFound);
+        }
+        let data: Arc<[u8]> = tone_wav().into();
+        Ok(FileOpenInfo {
+            file_size: data.len(),
+            handle: Box::pin(data),
+        })
+    }
+}
+
+impl SpawnFileSystem for MemoryFs {
+    fn read(file: Pin<&Self::File>, request: AsyncReadRequest) -> ReadFuture {
+        let data = Arc::clone(&file);
+        Box::pin(async move {
+            let start = usize::min(request.offset as usize, data.len());
+            let end = usize::min(start + request.size as usize, data.len());
+            Ok(data[start..end].to_vec())
+        })
+    }
+
+    fn spawn(task: ReadTask) {
+        thread::spawn(move || block_on(task));
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on(mut task: ReadTask) {
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    while Pin::new(&mut task).poll(&mut cx).is_pending() {
+        thread::park();
+    }
+}
+
+#[test]
+fn sounds_are_read_through_spawned_tasks() {
+    let _lock = common::lock();
+    let system = System::new().unwrap();
+    system.set_file_system_spawn::<MemoryFs>(-1).unwrap();
+    system.set_output(OutputType::NoSound).unwrap();
+    system.init(32, InitFlags::Normal).unwrap();
+
+    let sound = system
+        .create_sound(cstr8!("tone.wav"), Mode::CreateSample)
+        .unwrap();
+    assert_eq!(sound.get_length(TimeUnit::Pcm).unwrap(), FRAMES);
+    assert_eq!(sound.get_format().unwrap().format, SoundFormat::Pcm16);
+
+    // a stream keeps reading while it plays, and cancels its reads when
+    // it is released
+    let stream = system
+        .create_stream(cstr8!("tone.wav"), Mode::Default)
+        .unwrap();
+    let channel = system.play_sound(&stream, None).unwrap();
+    for _ in 0..10 {
+        system.update().unwrap();
+        thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert!(channel.get_position(TimeUnit::Pcm).unwrap() > 0);
+    drop(stream);
+
+    assert_eq!(
+        system
+            .create_sound(cstr8!("missing.wav"), Mode::CreateSample)
+            .err(),
+        Some(Error::FileNotFound),
+    );
+}
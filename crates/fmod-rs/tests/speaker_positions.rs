@@ -0,0 +1,58 @@
+mod common;
+
+use fmod::*;
+
+/// Creates a system mixing to `num_raw_speakers` raw speakers.
+fn raw_system(num_raw_speakers: i32) -> Handle<'static, System> {
+    let system = System::new().unwrap();
+    system.set_output(OutputType::NoSound).unwrap();
+    system
+        .set_software_format(SoftwareFormat {
+            sample_rate: 48000,
+            speaker_mode: SpeakerMode::Raw,
+            num_raw_speakers,
+        })
+        .unwrap();
+    system.init(32, InitFlags::Normal).unwrap();
+    system
+}
+
+#[test]
+fn invalid_speaker_leaves_layout_unchanged() {
+    let _lock = common::lock();
+    let system = raw_system(8);
+    assert!(system
+        .get_speaker_positions(SpeakerMode::Raw)
+        .unwrap()
+        .is_empty());
+
+    let speakers = [Speaker::FrontLeft, Speaker::FrontRight, Speaker::BackLeft];
+    let before = speakers.map(|speaker| system.get_speaker_position(speaker).unwrap());
+
+    let at = |x, y| SpeakerPosition { x, y, active: true };
+    let batch = [
+        (Speaker::FrontLeft, at(-0.5, 0.5)),
+        (Speaker::FrontRight, at(0.5, 0.5)),
+        (Speaker::None, at(0.0, 1.0)),
+        (Speaker::BackLeft, at(-0.5, -0.5)),
+    ];
+    assert!(system.set_speaker_positions(&batch).is_err());
+    let after = speakers.map(|speaker| system.get_speaker_position(speaker).unwrap());
+    assert_eq!(before, after);
+
+    // without the invalid speaker, the whole batch is applied
+    let batch = [batch[0], batch[1], batch[3]];
+    system.set_speaker_positions(&batch).unwrap();
+    for (speaker, position) in batch {
+        assert_eq!(system.get_speaker_position(speaker).unwrap(), position);
+    }
+}
+
+#[test]
+fn positions_of_mode_follow_channel_order() {
+    common::with_system(|system| {
+        let positions = system.get_speaker_positions(SpeakerMode::Stereo).unwrap();
+        let speakers: Vec<_> = positions.iter().map(|&(speaker, _)| speaker).collect();
+        assert_eq!(speakers, [Speaker::FrontLeft, Speaker::FrontRight]);
+    });
+}
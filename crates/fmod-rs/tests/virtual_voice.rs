@@ -0,0 +1,48 @@
+mod common;
+
+use fmod::*;
+
+#[test]
+fn quiet_channels_become_virtual() {
+    let _lock = common::lock();
+    let system = System::new().unwrap();
+    system.set_output(OutputType::NoSoundNrt).unwrap();
+    system.set_software_channels(4).unwrap();
+    system
+        .init(32, InitFlags::Normal | InitFlags::Vol0BecomesVirtual)
+        .unwrap();
+
+    // loudest first; only four can be real
+    let volumes = [1.0, 0.9, 0.8, 0.7, 0.3, 0.2, 0.1, 0.0];
+    let tones: Vec<_> = volumes
+        .iter()
+        .map(|_| system.create_dsp_by_type(DspType::Oscillator).unwrap())
+        .collect();
+    let channels: Vec<&Channel> = tones
+        .iter()
+        .zip(volumes)
+        .map(|(tone, volume)| {
+            let channel = system.play_dsp(tone, None).unwrap();
+            channel.set_volume(volume).unwrap();
+            channel
+        })
+        .collect();
+    system.update().unwrap();
+
+    for (channel, volume) in channels.iter().zip(volumes) {
+        let expected = volume < 0.5;
+        assert_eq!(channel.is_virtual().unwrap(), expected, "volume {volume}");
+    }
+    let usage = system.get_channels_playing().unwrap();
+    assert_eq!((usage.all, usage.real), (8, 4));
+
+    // indices identify the channels in the system's pool
+    let mut indices: Vec<i32> = channels.iter().map(|c| c.get_index().unwrap()).collect();
+    for &index in &indices {
+        let channel = system.get_channel(index).unwrap();
+        assert_eq!(channel.get_index().unwrap(), index);
+    }
+    indices.sort_unstable();
+    indices.dedup();
+    assert_eq!(indices.len(), 8);
+}
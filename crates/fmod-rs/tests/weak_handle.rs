@@ -0,0 +1,79 @@
+mod common;
+
+use fmod::*;
+
+#[test]
+fn upgrade_fails_after_release() {
+    common::with_system(|system| {
+        let group = system.create_channel_group(cstr8!("group")).unwrap();
+        let weak = Handle::downgrade(&group);
+        assert!(weak.upgrade().is_some());
+        drop(group);
+        assert!(weak.is_released());
+    });
+}
+
+#[test]
+fn release_while_upgraded_is_deferred() {
+    common::with_system(|system| {
+        let group = system.create_channel_group(cstr8!("group")).unwrap();
+        let weak = Handle::downgrade(&group);
+        let guard = weak.upgrade().unwrap();
+
+        // releasing on the same thread as the guard doesn't deadlock
+        group.release().unwrap();
+        assert!(weak.upgrade().is_none());
+        guard.set_volume(0.5).unwrap();
+        if cfg!(debug_assertions) {
+            let error = unsafe { system.close_scope() }.unwrap_err();
+            assert_eq!(error, CloseError::ObjectsStillAlive { count: 1 });
+        }
+
+        drop(guard);
+        assert!(weak.is_released());
+        let closed = unsafe { system.close_scope() }.unwrap();
+        closed.reinit(32, InitFlags::Normal).unwrap();
+    });
+}
+
+#[test]
+fn guard_does_not_block_other_releases() {
+    common::with_system(|system| {
+        let first = system.create_channel_group(cstr8!("first")).unwrap();
+        let second = system.create_channel_group(cstr8!("second")).unwrap();
+        let weak = Handle::downgrade(&first);
+        let guard = weak.upgrade().unwrap();
+        drop(second);
+        drop(guard);
+        assert!(weak.upgrade().is_some());
+    });
+}
+
+#[test]
+fn system_release_invalidates_its_weak_handles() {
+    let _lock = common::lock();
+    let system = common::new_system(OutputType::NoSound);
+    let group = system.create_channel_group(cstr8!("group")).unwrap();
+    let weak = Handle::downgrade(&group);
+    Handle::leak(group);
+    drop(system);
+    assert!(weak.is_released());
+}
+
+#[test]
+fn system_release_waits_for_guards() {
+    let _lock = common::lock();
+    let system = common::new_system(OutputType::NoSound);
+    let group = system.create_channel_group(cstr8!("group")).unwrap();
+    let weak = Handle::downgrade(&group);
+    Handle::leak(group);
+    let guard = weak.upgrade().unwrap();
+
+    drop(system);
+    assert!(weak.upgrade().is_none());
+    guard.set_volume(0.5).unwrap();
+    assert_eq!(System::new().unwrap_err(), Error::Initialized);
+
+    drop(guard);
+    drop(System::new().unwrap());
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use {
+    cstr8::CString8,
+    fmod::{codec::*, *},
+    std::{
+        fs,
+        path::Path,
+        sync::atomic::{AtomicBool, Ordering},
+    },
+};
+
+const MAGIC: &[u8; 4] = b"XOR!";
+const KEY: u8 = 0x5A;
+
+/// Mono 16-bit PCM, obfuscated by XORing every byte with [`KEY`].
+struct XorCodec;
+
+impl CodecDescription for XorCodec {
+    fn open(file: &mut CodecFile<'_>, _: Mode) -> Result<(Self, CodecWaveFormat)> {
+        let mut magic = [0; 4];
+        if file.read(&mut magic)? != magic.len() || &magic != MAGIC {
+            return Err(Error::Format);
+        }
+        let length_bytes = file.size()? - MAGIC.len() as u32;
+        let format = CodecWaveFormat {
+            format: SoundFormat::Pcm16,
+            channels: 1,
+            frequency: 8000,
+            length_bytes,
+            length_pcm: length_bytes / 2,
+            channel_mask: ChannelMask::Mono,
+            ..CodecWaveFormat::default()
+        };
+        Ok((XorCodec, format))
+    }
+
+    fn read(&mut self, file: &mut CodecFile<'_>, buffer: &mut [u8]) -> Result<usize> {
+        let read = file.read(buffer)?;
+        for byte in &mut buffer[..read] {
+            *byte ^= KEY;
+        }
+        Ok(read / 2)
+    }
+
+    fn set_position(&mut self, file: &mut CodecFile<'_>, _: i32, position: u32) -> Result {
+        file.seek(MAGIC.len() as u32 + position * 2)
+    }
+}
+
+fn samples() -> Vec<i16> {
+    (0..4000)
+        .map(|i| ((i * 97) % 20000 - 10000) as i16)
+        .collect()
+}
+
+fn write_obfuscated(path: &Path) {
+    let mut data = MAGIC.to_vec();
+    for sample in samples() {
+        data.extend(sample.to_le_bytes().map(|byte| byte ^ KEY));
+    }
+    fs::write(path, data).unwrap();
+}
+
+#[test]
+fn xor_codec_round_trip() {
+    let dir = std::env::temp_dir().join(format!("fmod-rs-codec-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tone.xor");
+    write_obfuscated(&path);
+    let name = CString8::new(path.to_str().unwrap()).unwrap();
+
+    common::with_system(|system| {
+        system.register_codec::<XorCodec>(cstr8!("xor"), 0).unwrap();
+
+        // decoded data matches the original samples
+        let sound = system.create_sound(&name, Mode::OpenOnly).unwrap();
+        assert_eq!(sound.get_length(TimeUnit::Pcm).unwrap(), 4000);
+        let mut decoded = vec![0; 4000 * 2];
+        assert_eq!(sound.read_data(&mut decoded).unwrap(), decoded.len());
+        let decoded: Vec<i16> = decoded
+            .chunks_exact(2)
+            .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect();
+        assert_eq!(decoded, samples());
+        drop(sound);
+
+        // and it plays like any other sound
+        let sound = system.create_sound(&name, Mode::CreateSample).unwrap();
+        let channel = system.play_sound(&sound, None).unwrap();
+        system.update().unwrap();
+        assert!(channel.is_playing().unwrap());
+    });
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Claims every file, but reports a format the mixer can't read.
+struct NotPcm;
+
+static NOT_PCM_CLOSED: AtomicBool = AtomicBool::new(false);
+
+impl CodecDescription for NotPcm {
+    fn open(_: &mut CodecFile<'_>, _: Mode) -> Result<(Self, CodecWaveFormat)> {
+        let format = CodecWaveFormat {
+            format: SoundFormat::Bitstream,
+            ..CodecWaveFormat::default()
+        };
+        Ok((NotPcm, format))
+    }
+
+    fn close(self, _: &mut CodecFile<'_>) -> Result {
+        NOT_PCM_CLOSED.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn read(&mut self, _: &mut CodecFile<'_>, _: &mut [u8]) -> Result<usize> {
+        Ok(0)
+    }
+
+    fn set_position(&mut self, _: &mut CodecFile<'_>, _: i32, _: u32) -> Result {
+        Ok(())
+    }
+}
+
+#[test]
+fn non_pcm_codec_is_closed() {
+    let dir = std::env::temp_dir().join(format!("fmod-rs-not-pcm-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tone.xor");
+    write_obfuscated(&path);
+    let name = CString8::new(path.to_str().unwrap()).unwrap();
+
+    common::with_system(|system| {
+        system
+            .register_codec::<NotPcm>(cstr8!("not pcm"), 0)
+            .unwrap();
+        assert!(system.create_sound(&name, Mode::OpenOnly).is_err());
+        assert!(NOT_PCM_CLOSED.load(Ordering::SeqCst));
+    });
+    fs::remove_dir_all(&dir).unwrap();
+}
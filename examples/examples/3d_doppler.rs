@@ -0,0 +1,201 @@
+/*============================================================================*/
+//! 3D Doppler Example
+//! Copyright (c), Firelight Technologies Pty, Ltd 2004-2024.
+//!
+//! This example builds on the 3D example: one sound is stationary while the
+//! other swings past the listener, with its velocity set so that FMOD applies
+//! doppler. The coordinate system can be switched between left and right
+//! handed to hear how the same positions pan to opposite sides.
+//!
+//! Pass `--headless` to run a fixed number of frames with the NoSound output
+//! and no terminal interface, switching handedness halfway through.
+//!
+//! For information on using FMOD example code in your own programs, visit
+//! https://www.fmod.com/legal
+/*============================================================================*/
+
+use fmod_examples::{media, sleep_ms, Buttons, Example};
+
+/// 50ms update for interface
+const INTERFACE_UPTIME: u64 = 50;
+
+/// Units per meter. I.e feet would = 3.28. centimeters would = 100.
+const DISTANCE_FACTOR: f32 = 1.0;
+
+/// How far either side of the center the moving sound swings.
+const SWING: f32 = 20.0 * DISTANCE_FACTOR;
+
+/// Frames to run with each handedness when headless.
+const HEADLESS_FRAMES: u32 = 100;
+
+fn main() -> anyhow::Result<()> {
+    let headless = std::env::args().any(|arg| arg == "--headless");
+    let mut example = match headless {
+        true => None,
+        false => Some(Example::init()?),
+    };
+
+    let mut right_handed = false;
+    let mut listenerpos = fmod::Vector::new(0.0, 0.0, -DISTANCE_FACTOR);
+    let mut t = 0.0_f32;
+    let mut frame = 0;
+
+    'system: loop {
+        // Create a System object and initialize, with the chosen handedness.
+        let system = fmod::System::new()?;
+        if headless {
+            system.set_output(fmod::OutputType::NoSound)?;
+        }
+        let mut flags = fmod::InitFlags::Normal;
+        if right_handed {
+            flags |= fmod::InitFlags::RightHanded3d;
+        }
+        system.init(100, flags)?;
+
+        // Set the distance units. (meters/feet etc).
+        system.set_3d_settings(fmod::Settings3d {
+            doppler_scale: 1.0,
+            distance_factor: DISTANCE_FACTOR,
+            rolloff_scale: 1.0,
+        })?;
+
+        // Load some sounds
+        let sound1 = system.create_sound(media!("drumloop.wav"), fmod::Mode::D3)?;
+        sound1.set_3d_min_max_distance(0.5 * DISTANCE_FACTOR..5000.0 * DISTANCE_FACTOR)?;
+        sound1.set_mode(fmod::Mode::LoopNormal)?;
+
+        let sound2 = system.create_sound(media!("jaguar.wav"), fmod::Mode::D3)?;
+        sound2.set_3d_min_max_distance(0.5 * DISTANCE_FACTOR..5000.0 * DISTANCE_FACTOR)?;
+        sound2.set_mode(fmod::Mode::LoopNormal)?;
+
+        // The first sound stays put, the second moves every frame.
+        let pos1 = fmod::Vector::new(-10.0 * DISTANCE_FACTOR, 0.0, 0.0);
+        let channel1 = system.create_sound_channel(&sound1, None)?;
+        channel1.set_3d_attributes(&pos1, &fmod::Vector::default())?;
+        channel1.set_paused(false)?;
+
+        let channel2 = system.create_sound_channel(&sound2, None)?;
+        let mut pos2 = fmod::Vector::new(SWING * (t * 0.5).sin(), 0.0, 2.0 * DISTANCE_FACTOR);
+        channel2.set_3d_attributes(&pos2, &fmod::Vector::default())?;
+        channel2.set_paused(false)?;
+
+        // main loop
+        loop {
+            let (quit, toggle, left, right) = match &mut example {
+                Some(example) => {
+                    example.update()?;
+                    (
+                        example.btn_press(Buttons::Quit),
+                        example.btn_press(Buttons::More),
+                        example.btn_down(Buttons::Left),
+                        example.btn_down(Buttons::Right),
+                    )
+                },
+                None => (
+                    frame == 2 * HEADLESS_FRAMES,
+                    frame == HEADLESS_FRAMES,
+                    false,
+                    false,
+                ),
+            };
+            frame += 1;
+
+            if quit || toggle {
+                sound1.release()?;
+                sound2.release()?;
+                system.release()?;
+                if quit {
+                    break 'system;
+                }
+                right_handed = !right_handed;
+                continue 'system;
+            }
+
+            if left {
+                listenerpos.x = (listenerpos.x - DISTANCE_FACTOR).max(-24.0 * DISTANCE_FACTOR);
+            }
+
+            if right {
+                listenerpos.x = (listenerpos.x + DISTANCE_FACTOR).min(23.0 * DISTANCE_FACTOR);
+            }
+
+            // UPDATE THE MOVING SOUND
+            {
+                // t is in seconds, advancing by one frame each update.
+                t += INTERFACE_UPTIME as f32 / 1000.0;
+                let lastpos = pos2;
+                pos2.x = SWING * (t * 0.5).sin();
+
+                // vel = how far we moved last FRAME (m/f), then time compensate it to SECONDS (m/s).
+                let vel = fmod::Vector::new(
+                    (pos2.x - lastpos.x) * (1000.0 / INTERFACE_UPTIME as f32),
+                    0.0,
+                    0.0,
+                );
+                channel2.set_3d_attributes(&pos2, &vel)?;
+            }
+
+            // UPDATE THE LISTENER
+            {
+                // The same vectors are used in either coordinate system, so
+                // in right handed mode the listener faces the other way and
+                // left and right are swapped.
+                let forward = fmod::Vector::new(0.0, 0.0, 1.0);
+                let up = fmod::Vector::new(0.0, 1.0, 0.0);
+
+                system.set_3d_listener_attributes(
+                    0,
+                    fmod::Attributes3d {
+                        position: listenerpos,
+                        velocity: fmod::Vector::default(),
+                        orientation: fmod::Orientation3d { forward, up },
+                    },
+                )?;
+            }
+
+            system.update()?;
+
+            let Some(example) = &mut example else {
+                continue;
+            };
+
+            // Create small visual display.
+            let mut s = *b"|................................................|";
+            let column = |x: f32| ((x / DISTANCE_FACTOR) + 25.0).clamp(0.0, 49.0) as usize;
+            s[column(pos1.x)] = b'1';
+            s[column(pos2.x)] = b'2';
+            s[column(listenerpos.x)] = b'L';
+
+            example.draw("==================================================");
+            example.draw("3D Doppler Example.");
+            example.draw("Copyright (c) Firelight Technologies 2004-2024.");
+            example.draw("==================================================");
+            example.draw("");
+            example.draw(format_args!(
+                "Press {} or {} to move the listener",
+                Buttons::Left.name(),
+                Buttons::Right.name(),
+            ));
+            example.draw(format_args!(
+                "Press {} to toggle left/right handed coordinates",
+                Buttons::More.name(),
+            ));
+            example.draw(format_args!("Press {} to quit", Buttons::Quit.name()));
+            example.draw("");
+            example.draw(format_args!(
+                "Coordinates are {} handed",
+                if right_handed { "right" } else { "left" },
+            ));
+            example.draw("");
+            example.draw(std::str::from_utf8(&s)?);
+
+            sleep_ms(INTERFACE_UPTIME - 1);
+        }
+    }
+
+    if let Some(example) = example {
+        example.close()?;
+    }
+
+    Ok(())
+}
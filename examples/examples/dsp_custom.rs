@@ -1,6 +1,3 @@
-#![allow(unused)]
-// contains todo!s
-
 /*============================================================================*/
 //! Custom DSP Example
 //! Copyright (c), Firelight Technologies Pty, Ltd 2004-2024.
@@ -13,9 +10,180 @@
 //! https://www.fmod.com/legal
 /*============================================================================*/
 
-use fmod_examples::{media, sleep_ms, yeet, Buttons, Example};
+use {
+    fmod::{cstr8, Dsp, DspDescription, DspParameterDesc, DspParameterKind},
+    fmod_examples::{media, sleep_ms, Buttons, Example},
+    std::sync::atomic::{AtomicU32, Ordering},
+};
+
+const VOLUME: i32 = 0;
+const PEAK: i32 = 1;
+
+/// Scales the signal by a volume parameter, and reports the peak level of the
+/// last block it processed through a read-only parameter.
+struct MyDsp {
+    volume: AtomicU32,
+    peak: AtomicU32,
+}
+
+impl DspDescription for MyDsp {
+    const PARAMETERS: &'static [DspParameterDesc] = &[
+        DspParameterDesc {
+            name: cstr8!("Volume"),
+            label: cstr8!("%"),
+            description: cstr8!("Linear volume."),
+            kind: DspParameterKind::Float {
+                min: 0.0,
+                max: 1.0,
+                default: 1.0,
+            },
+        },
+        DspParameterDesc {
+            name: cstr8!("Peak"),
+            label: cstr8!("%"),
+            description: cstr8!("Peak level of the last block (read only)."),
+            kind: DspParameterKind::Float {
+                min: 0.0,
+                max: 1.0,
+                default: 0.0,
+            },
+        },
+    ];
+
+    fn create() -> fmod::Result<Self> {
+        Ok(MyDsp {
+            volume: AtomicU32::new(1.0f32.to_bits()),
+            peak: AtomicU32::new(0.0f32.to_bits()),
+        })
+    }
+
+    fn read(&self, input: &[f32], output: &mut [f32], _channels: usize) -> fmod::Result {
+        // The input and output have the same number of interleaved channels,
+        // so every sample can be scaled the same way.
+        let volume = f32::from_bits(self.volume.load(Ordering::Relaxed));
+        let mut peak = 0.0f32;
+        for (output, input) in output.iter_mut().zip(input) {
+            *output = input * volume;
+            peak = peak.max(output.abs());
+        }
+        self.peak.store(peak.to_bits(), Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn set_parameter_float(&self, index: i32, value: f32) -> fmod::Result {
+        match index {
+            VOLUME => {
+                self.volume.store(value.to_bits(), Ordering::Relaxed);
+                Ok(())
+            },
+            _ => Err(fmod::Error::InvalidParam),
+        }
+    }
+
+    fn get_parameter_float(&self, index: i32) -> fmod::Result<f32> {
+        match index {
+            VOLUME => Ok(f32::from_bits(self.volume.load(Ordering::Relaxed))),
+            PEAK => Ok(f32::from_bits(self.peak.load(Ordering::Relaxed))),
+            _ => Err(fmod::Error::InvalidParam),
+        }
+    }
+}
 
 fn main() -> anyhow::Result<()> {
-    todo!("Custom DSP Example");
-    // TODO: DspDescription, System::create_dsp
+    let mut example = Example::init()?;
+
+    {
+        // Create a System object and initialize.
+        let system = fmod::System::new()?;
+        system.init(32, fmod::InitFlags::Normal)?;
+
+        let sound = system.create_sound(media!("stereo.ogg"), fmod::Mode::LoopNormal)?;
+        let channel = system.play_sound(&sound, None)?;
+
+        // Create the DSP effect.
+        let plugin = system.register_dsp::<MyDsp>(cstr8!("My first DSP unit"))?;
+        let mydsp = system.create_dsp_by_plugin(plugin)?;
+
+        // Attach the DSP, inactive by default.
+        mydsp.set_bypass(true)?;
+        let master_group = system.get_master_channel_group()?;
+        master_group.add_dsp(0, &mydsp)?;
+
+        // Main loop.
+        while !example.btn_down(Buttons::Quit) {
+            example.update()?;
+
+            if example.btn_press(Buttons::Action1) {
+                let bypass = mydsp.get_bypass()?;
+                mydsp.set_bypass(!bypass)?;
+            }
+
+            if example.btn_press(Buttons::Action2) {
+                change_volume(&mydsp, -0.1)?;
+            }
+
+            if example.btn_press(Buttons::Action3) {
+                change_volume(&mydsp, 0.1)?;
+            }
+
+            system.update()?;
+
+            let info = mydsp.get_info()?;
+            let volume: f32 = mydsp.get_parameter(VOLUME)?;
+            let peak: f32 = mydsp.get_parameter(PEAK)?;
+            let bypass = mydsp.get_bypass()?;
+            let playing = channel.is_playing()?;
+
+            example.draw("==================================================");
+            example.draw("Custom DSP Example.");
+            example.draw("Copyright (c) Firelight Technologies 2004-2024.");
+            example.draw("==================================================");
+            example.draw("");
+            example.draw(format_args!(
+                "Press {} to toggle filter bypass",
+                Buttons::Action1.name()
+            ));
+            example.draw(format_args!(
+                "Press {} to decrease volume 10%",
+                Buttons::Action2.name()
+            ));
+            example.draw(format_args!(
+                "Press {} to increase volume 10%",
+                Buttons::Action3.name()
+            ));
+            example.draw(format_args!("Press {} to quit", Buttons::Quit.name()));
+            example.draw("");
+            example.draw(format_args!(
+                "Filter is {}",
+                if bypass { "inactive" } else { "active" }
+            ));
+            example.draw(format_args!("Volume is {:.0}%", volume * 100.0));
+            example.draw(format_args!(
+                "{}",
+                if playing { "Playing" } else { "Stopped" }
+            ));
+            example.draw("");
+            if !bypass {
+                let level = (peak * 50.0).round() as usize;
+                example.draw(format_args!("{} peak |{:=<level$}", info.name(), ""));
+            }
+
+            sleep_ms(50);
+        }
+
+        // Shut down
+        unsafe { master_group.remove_dsp(&mydsp)? };
+        mydsp.release()?;
+        sound.release()?;
+        system.release()?;
+    }
+
+    example.close()?;
+
+    Ok(())
+}
+
+fn change_volume(dsp: &Dsp, delta: f32) -> fmod::Result {
+    let volume: f32 = dsp.get_parameter(VOLUME)?;
+    dsp.set_parameter(VOLUME, (volume + delta).clamp(0.0, 1.0))
 }
@@ -0,0 +1,107 @@
+/*============================================================================*/
+//! Geometry Example
+//! Copyright (c), Firelight Technologies Pty, Ltd 2004-2024.
+//!
+//! This example shows how to occlude a 3D sound with geometry. A wall is built
+//! with GeometryBuilder between the listener and a looping sound; moving the
+//! listener out from behind the wall makes the sound audible again.
+//!
+//! For information on using FMOD example code in your own programs, visit
+//! https://www.fmod.com/legal
+/*============================================================================*/
+
+use fmod_examples::{media, sleep_ms, Buttons, Example};
+
+/// 50ms update for interface
+const INTERFACE_UPTIME: u64 = 50;
+
+fn main() -> anyhow::Result<()> {
+    let mut example = Example::init()?;
+
+    {
+        // Create a System object and initialize.
+        let system = fmod::System::new()?;
+        system.init(100, fmod::InitFlags::Normal)?;
+        system.set_geometry_settings(100.0)?;
+
+        // Build a wall, 10 units wide, halfway between the listener and the sound.
+        let wall = fmod::GeometryBuilder::new()
+            .cuboid(
+                fmod::Vector::new(0.0, 0.0, 5.0),
+                fmod::Vector::new(5.0, 5.0, 0.5),
+                fmod::Occlusion {
+                    direct: 1.0,
+                    reverb: 0.3,
+                },
+            )
+            .build(&system)?;
+
+        // Play a looping sound behind the wall.
+        let source = fmod::Vector::new(0.0, 0.0, 10.0);
+        let sound = system.create_sound(media!("drumloop.wav"), fmod::Mode::D3)?;
+        sound.set_3d_min_max_distance(1.0..100.0)?;
+        sound.set_mode(fmod::Mode::LoopNormal)?;
+        let channel = system.create_sound_channel(&sound, None)?;
+        channel.set_3d_attributes(&source, &fmod::Vector::default())?;
+        channel.set_paused(false)?;
+
+        // Main loop.
+        let mut listener = fmod::Vector::new(0.0, 0.0, 0.0);
+        while !example.btn_press(Buttons::Quit) {
+            example.update()?;
+
+            if example.btn_down(Buttons::Left) {
+                listener.x = (listener.x - 0.5).max(-15.0);
+            }
+
+            if example.btn_down(Buttons::Right) {
+                listener.x = (listener.x + 0.5).min(15.0);
+            }
+
+            system.set_3d_listener_attributes(
+                0,
+                fmod::Attributes3d {
+                    position: listener,
+                    velocity: fmod::Vector::default(),
+                    orientation: fmod::Orientation3d {
+                        forward: fmod::Vector::new(0.0, 0.0, 1.0),
+                        up: fmod::Vector::new(0.0, 1.0, 0.0),
+                    },
+                },
+            )?;
+
+            system.update()?;
+
+            let occlusion = system.get_geometry_occlusion(&listener, &source)?;
+
+            example.draw("==================================================");
+            example.draw("Geometry Example.");
+            example.draw("Copyright (c) Firelight Technologies 2004-2024.");
+            example.draw("==================================================");
+            example.draw("");
+            example.draw(format_args!(
+                "Press {} or {} to move the listener",
+                Buttons::Left.name(),
+                Buttons::Right.name(),
+            ));
+            example.draw(format_args!("Press {} to quit", Buttons::Quit.name()));
+            example.draw("");
+            example.draw(format_args!("Listener at x = {:5.1}", listener.x));
+            example.draw(format_args!(
+                "Occlusion: direct {:.2}, reverb {:.2}",
+                occlusion.direct, occlusion.reverb,
+            ));
+
+            sleep_ms(INTERFACE_UPTIME - 1);
+        }
+
+        // Shut down
+        wall.release()?;
+        sound.release()?;
+        system.release()?;
+    }
+
+    example.close()?;
+
+    Ok(())
+}